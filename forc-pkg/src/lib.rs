@@ -9,6 +9,8 @@ pub mod manifest;
 mod pkg;
 pub mod source;
 
+/// The JSON ABI types used for test entries, re-exported so that consumers agree on their version.
+pub use fuel_abi_types::program_abi;
 pub use lock::Lock;
 pub use manifest::{
     BuildProfile, PackageManifest, PackageManifestFile, WorkspaceManifest, WorkspaceManifestFile,
@@ -9,7 +9,10 @@ use std::{
     sync::Arc,
 };
 
-use sway_core::{fuel_prelude::fuel_tx, language::parsed::TreeType, parse_tree_type, BuildTarget};
+use sway_core::{
+    fuel_prelude::fuel_tx, language::parsed::TreeType, parse_tree_type, BuildTarget,
+    OptimizationGoal, OptimizationLevel,
+};
 use sway_utils::constants;
 
 /// The name of a workspace member package.
@@ -225,6 +228,16 @@ pub struct BuildProfile {
     pub json_abi_with_callpaths: bool,
     #[serde(default)]
     pub error_on_warnings: bool,
+    #[serde(default)]
+    pub inline_threshold: Option<u64>,
+    #[serde(default)]
+    pub optimization_goal: OptimizationGoal,
+    #[serde(default)]
+    pub optimization_level: OptimizationLevel,
+    #[serde(default)]
+    pub ir_passes: Option<Vec<String>>,
+    #[serde(default)]
+    pub print_ir_after: Vec<String>,
 }
 
 impl DependencyDetails {
@@ -696,6 +709,11 @@ impl BuildProfile {
             include_tests: false,
             json_abi_with_callpaths: false,
             error_on_warnings: false,
+            inline_threshold: None,
+            optimization_goal: OptimizationGoal::Size,
+            optimization_level: OptimizationLevel::O1,
+            ir_passes: None,
+            print_ir_after: vec![],
         }
     }
 
@@ -713,6 +731,11 @@ impl BuildProfile {
             include_tests: false,
             json_abi_with_callpaths: false,
             error_on_warnings: false,
+            inline_threshold: None,
+            optimization_goal: OptimizationGoal::Size,
+            optimization_level: OptimizationLevel::O1,
+            ir_passes: None,
+            print_ir_after: vec![],
         }
     }
 }
@@ -1009,7 +1032,8 @@ pub fn find_dir_within(dir: &Path, pkg_name: &str) -> Option<PathBuf> {
 
 #[cfg(test)]
 mod tests {
-    use super::DependencyDetails;
+    use super::{BuildProfile, DependencyDetails};
+    use sway_core::OptimizationLevel;
 
     #[test]
     fn test_invalid_dependency_details_mixed_together() {
@@ -1149,4 +1173,33 @@ mod tests {
         assert!(dependency_details_git_branch.validate().is_ok());
         assert!(dependency_details_git_rev.validate().is_ok());
     }
+
+    #[test]
+    fn test_build_profile_ir_pipeline() {
+        let profile: BuildProfile = toml::from_str(
+            r#"
+            optimization-level = 0
+            ir-passes = ["mem2reg", "inline_module", "dce"]
+            print-ir-after = ["inline_module"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(profile.optimization_level, OptimizationLevel::O0);
+        assert_eq!(
+            profile.ir_passes,
+            Some(vec![
+                "mem2reg".to_string(),
+                "inline_module".to_string(),
+                "dce".to_string()
+            ])
+        );
+        assert_eq!(profile.print_ir_after, vec!["inline_module".to_string()]);
+
+        let profile: BuildProfile = toml::from_str("").unwrap();
+        assert_eq!(profile.optimization_level, OptimizationLevel::O1);
+        assert!(profile.ir_passes.is_none());
+        assert!(profile.print_ir_after.is_empty());
+
+        assert!(toml::from_str::<BuildProfile>("optimization-level = 7").is_err());
+    }
 }
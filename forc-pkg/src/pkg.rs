@@ -114,6 +114,11 @@ pub struct BuiltPackage {
     ///
     /// For non-contract members, this is always `None`.
     pub bytecode_without_tests: Option<BuiltPackageBytecode>,
+    /// The ABI matching `bytecode_without_tests`, e.g. used to decode the values logged by the
+    /// deployed contract during tests.
+    pub program_abi_without_tests: Option<ProgramABI>,
+    /// The source map matching `bytecode_without_tests`.
+    source_map_without_tests: Option<SourceMap>,
 }
 
 /// The package descriptors that a `BuiltPackage` holds so that the source used for building the
@@ -151,15 +156,67 @@ pub enum PkgEntryKind {
 #[derive(Debug, Clone)]
 pub enum TestPassCondition {
     ShouldRevert(Option<u64>),
+    /// The test should revert after logging the given enum variant, e.g. `Error::NotOwner`.
+    ShouldRevertWithVariant(String),
     ShouldNotRevert,
 }
 
+/// The receipts a test is expected to emit, in addition to its pass condition.
+#[derive(Debug, Clone, Default)]
+pub struct ExpectedReceipts {
+    /// The values expected to be logged, in order, declared with `#[test(logs = "...")]`.
+    pub logs: Option<Vec<String>>,
+    /// The number of transfers expected, declared with `#[test(transfers = "<n>")]`.
+    pub transfers: Option<usize>,
+    /// The number of messages expected to be sent with `smo`, declared with
+    /// `#[test(messages = "<n>")]`.
+    pub messages: Option<usize>,
+}
+
+/// The state of the simulated chain a test is run against, declared with test attributes.
+#[derive(Debug, Clone, Default)]
+pub struct TestChainState {
+    /// The height of the block the test is run in, declared with `#[test(block_height = "<n>")]`.
+    pub block_height: Option<u32>,
+    /// The address owning the coins spent by the test, i.e. the `msg_sender` of the contract
+    /// calls it makes, declared with `#[test(caller = "<address>")]`.
+    pub caller: Option<fuel_tx::Address>,
+    /// The coins owned by the caller, declared with
+    /// `#[test(coins = "<asset id>:<amount>, ...")]`.
+    pub coins: Vec<(fuel_tx::AssetId, u64)>,
+    /// The balances of the contract under test, declared with
+    /// `#[test(balances = "<asset id>:<amount>, ...")]`.
+    pub balances: Vec<(fuel_tx::AssetId, u64)>,
+    /// The storage slots of the contract under test overridden after its deployment, declared
+    /// with `#[test(storage_slots = "<key>:<value>, ...")]`.
+    pub storage: Vec<(fuel_tx::Bytes32, fuel_tx::Bytes32)>,
+}
+
 /// Data specific to the test entry point.
 #[derive(Debug, Clone)]
 pub struct PkgTestEntry {
     pub pass_condition: TestPassCondition,
     pub span: Span,
     pub file_path: Arc<PathBuf>,
+    /// Only `Some` for parameterized tests, i.e. those declared with `#[test(fuzz)]`.
+    pub fuzz: Option<FuzzTestEntry>,
+    /// The receipts the test is expected to emit.
+    pub expected_receipts: ExpectedReceipts,
+    /// Whether the test is declared with `#[test(ignore)]`, in which case it is only run if
+    /// ignored tests are explicitly included.
+    pub ignored: bool,
+    /// The state of the simulated chain the test is run against.
+    pub chain_state: TestChainState,
+}
+
+/// Data specific to a parameterized test entry point, whose inputs are generated by the test
+/// runner.
+#[derive(Debug, Clone)]
+pub struct FuzzTestEntry {
+    /// The JSON ABI of the test function, describing the types of its parameters.
+    pub abi: program_abi::ProgramABI,
+    /// The number of runs requested with `#[test(fuzz = "<runs>")]`, if any.
+    pub runs: Option<usize>,
 }
 
 /// The result of successfully compiling a workspace.
@@ -264,6 +321,8 @@ pub struct PrintOpts {
     pub intermediate_asm: bool,
     /// Print the generated Sway IR (Intermediate Representation).
     pub ir: bool,
+    /// Print the Sway IR after each of the named IR passes, or after every pass for `all`.
+    pub ir_after: Vec<String>,
 }
 
 #[derive(Default, Clone)]
@@ -421,6 +480,17 @@ impl Edge {
 }
 
 impl BuiltPackage {
+    /// The source map of the BuiltPackage, mapping each instruction to its source.
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    /// The source map of the contract deployed for tests, i.e. the one matching
+    /// `bytecode_without_tests`.
+    pub fn source_map_without_tests(&self) -> Option<&SourceMap> {
+        self.source_map_without_tests.as_ref()
+    }
+
     /// Writes bytecode of the BuiltPackage to the given `path`.
     pub fn write_bytecode(&self, path: &Path) -> Result<()> {
         fs::write(path, &self.bytecode.bytes)?;
@@ -1558,6 +1628,11 @@ pub fn sway_build_config(
     .print_intermediate_asm(build_profile.print_intermediate_asm)
     .print_ir(build_profile.print_ir)
     .include_tests(build_profile.include_tests)
+    .inline_threshold(build_profile.inline_threshold)
+    .optimization_goal(build_profile.optimization_goal)
+    .optimization_level(build_profile.optimization_level)
+    .ir_passes(build_profile.ir_passes.clone())
+    .print_ir_after(build_profile.print_ir_after.clone())
     .time_phases(build_profile.time_phases)
     .metrics(build_profile.metrics_outfile.clone());
     Ok(build_config)
@@ -1865,8 +1940,15 @@ pub fn compile(
         .unwrap_or_default();
     let entries = entries
         .iter()
-        .map(|finalized_entry| PkgEntry::from_finalized_entry(finalized_entry, engines))
+        .map(|finalized_entry| {
+            PkgEntry::from_finalized_entry(finalized_entry, typed_program, engines)
+        })
         .collect::<anyhow::Result<_>>()?;
+    metrics.register_allocation = asm_res
+        .value
+        .as_ref()
+        .map(|asm| asm.0.register_allocation_metrics.clone())
+        .unwrap_or_default();
     let bc_res = time_expr!(
         "compile asm to bytecode",
         "compile_asm_to_bytecode",
@@ -1930,10 +2012,15 @@ impl PkgEntry {
         self.kind.test().is_some()
     }
 
-    fn from_finalized_entry(finalized_entry: &FinalizedEntry, engines: &Engines) -> Result<Self> {
+    fn from_finalized_entry(
+        finalized_entry: &FinalizedEntry,
+        program: &ty::TyProgram,
+        engines: &Engines,
+    ) -> Result<Self> {
         let pkg_entry_kind = match &finalized_entry.test_decl_ref {
             Some(test_decl_ref) => {
-                let pkg_test_entry = PkgTestEntry::from_decl(test_decl_ref.clone(), engines)?;
+                let pkg_test_entry =
+                    PkgTestEntry::from_decl(test_decl_ref.clone(), program, engines)?;
                 PkgEntryKind::Test(pkg_test_entry)
             }
             None => PkgEntryKind::Main,
@@ -1957,11 +2044,39 @@ impl PkgEntryKind {
 }
 
 impl PkgTestEntry {
-    fn from_decl(decl_ref: DeclRefFunction, engines: &Engines) -> Result<Self> {
+    fn from_decl(
+        decl_ref: DeclRefFunction,
+        program: &ty::TyProgram,
+        engines: &Engines,
+    ) -> Result<Self> {
         let span = decl_ref.span();
         let test_function_decl = engines.de().get_function(&decl_ref);
+        let test_name = &test_function_decl.name;
 
         const FAILING_TEST_KEYWORD: &str = "should_revert";
+        const FUZZ_TEST_KEYWORD: &str = "fuzz";
+        const IGNORED_TEST_KEYWORD: &str = "ignore";
+        const LOGS_KEYWORD: &str = "logs";
+        const TRANSFERS_KEYWORD: &str = "transfers";
+        const MESSAGES_KEYWORD: &str = "messages";
+        const BLOCK_HEIGHT_KEYWORD: &str = "block_height";
+        const CALLER_KEYWORD: &str = "caller";
+        const COINS_KEYWORD: &str = "coins";
+        const BALANCES_KEYWORD: &str = "balances";
+        const STORAGE_SLOTS_KEYWORD: &str = "storage_slots";
+        const TEST_KEYWORDS: &[&str] = &[
+            FAILING_TEST_KEYWORD,
+            FUZZ_TEST_KEYWORD,
+            IGNORED_TEST_KEYWORD,
+            LOGS_KEYWORD,
+            TRANSFERS_KEYWORD,
+            MESSAGES_KEYWORD,
+            BLOCK_HEIGHT_KEYWORD,
+            CALLER_KEYWORD,
+            COINS_KEYWORD,
+            BALANCES_KEYWORD,
+            STORAGE_SLOTS_KEYWORD,
+        ];
 
         let test_args: HashMap<String, Option<String>> = test_function_decl
             .attributes
@@ -1979,21 +2094,167 @@ impl PkgTestEntry {
             })
             .collect();
 
-        let pass_condition = if test_args.is_empty() {
-            anyhow::Ok(TestPassCondition::ShouldNotRevert)
-        } else if let Some(args) = test_args.get(FAILING_TEST_KEYWORD) {
-            let expected_revert_code = args
+        if test_args
+            .keys()
+            .any(|arg| !TEST_KEYWORDS.contains(&arg.as_str()))
+        {
+            bail!("Invalid test argument(s) for test: {test_name}.")
+        }
+
+        let pass_condition = match test_args.get(FAILING_TEST_KEYWORD) {
+            Some(Some(arg)) => {
+                let arg_str = arg.replace('"', "");
+                match arg_str.parse::<u64>() {
+                    Ok(expected_revert_code) => {
+                        TestPassCondition::ShouldRevert(Some(expected_revert_code))
+                    }
+                    Err(_) if arg_str.contains("::") => {
+                        TestPassCondition::ShouldRevertWithVariant(arg_str)
+                    }
+                    Err(_) => bail!(
+                        "Invalid expected revert of test {test_name}: `{arg_str}`. Expected a \
+                         revert code or an enum variant, e.g. `Error::NotOwner`."
+                    ),
+                }
+            }
+            Some(None) => TestPassCondition::ShouldRevert(None),
+            None => TestPassCondition::ShouldNotRevert,
+        };
+
+        let count_arg = |keyword: &str| -> Result<Option<usize>> {
+            let Some(arg) = test_args.get(keyword) else {
+                return Ok(None);
+            };
+            let arg_str = arg
                 .as_ref()
+                .map(|arg| arg.replace('"', ""))
+                .ok_or_else(|| {
+                    anyhow!("Test {test_name} must declare the number of expected {keyword}.")
+                })?;
+            Ok(Some(arg_str.parse::<usize>()?))
+        };
+        let expected_receipts = ExpectedReceipts {
+            logs: test_args.get(LOGS_KEYWORD).map(|arg| {
+                let arg_str = arg.as_deref().unwrap_or("").trim_matches('"');
+                split_expected_logs(&arg_str.replace("\\\"", "\""))
+            }),
+            transfers: count_arg(TRANSFERS_KEYWORD)?,
+            messages: count_arg(MESSAGES_KEYWORD)?,
+        };
+        let ignored = test_args.contains_key(IGNORED_TEST_KEYWORD);
+
+        let str_arg = |keyword: &str| -> Result<Option<String>> {
+            test_args
+                .get(keyword)
                 .map(|arg| {
-                    let arg_str = arg.replace('"', "");
-                    arg_str.parse::<u64>()
+                    arg.as_ref().map(|arg| arg.replace('"', "")).ok_or_else(|| {
+                        anyhow!("Test {test_name} must declare a value for `{keyword}`.")
+                    })
                 })
-                .transpose()?;
-            anyhow::Ok(TestPassCondition::ShouldRevert(expected_revert_code))
-        } else {
-            let test_name = &test_function_decl.name;
-            bail!("Invalid test argument(s) for test: {test_name}.")
-        }?;
+                .transpose()
+        };
+        let pairs_arg = |keyword: &str| -> Result<Vec<(String, String)>> {
+            let arg_str = str_arg(keyword)?.unwrap_or_default();
+            arg_str
+                .split(',')
+                .map(str::trim)
+                .filter(|pair| !pair.is_empty())
+                .map(|pair| {
+                    let (key, value) = pair.split_once(':').ok_or_else(|| {
+                        anyhow!(
+                            "Invalid `{keyword}` of test {test_name}: `{pair}`. Expected \
+                             `<key>:<value>` pairs separated by commas."
+                        )
+                    })?;
+                    Ok((key.trim().to_string(), value.trim().to_string()))
+                })
+                .collect()
+        };
+        let parse_hex = |keyword: &str, hex: &str| -> Result<fuel_tx::Bytes32> {
+            fuel_tx::Bytes32::from_str(hex).map_err(|_| {
+                anyhow!("Invalid `{keyword}` of test {test_name}: `{hex}` is not a 32 byte value.")
+            })
+        };
+        let balances_arg = |keyword: &str| -> Result<Vec<(fuel_tx::AssetId, u64)>> {
+            pairs_arg(keyword)?
+                .into_iter()
+                .map(|(asset_id, amount)| {
+                    let asset_id = fuel_tx::AssetId::from(*parse_hex(keyword, &asset_id)?);
+                    Ok((asset_id, amount.parse::<u64>()?))
+                })
+                .collect()
+        };
+        let chain_state = TestChainState {
+            block_height: str_arg(BLOCK_HEIGHT_KEYWORD)?
+                .map(|arg| arg.parse::<u32>())
+                .transpose()?,
+            caller: str_arg(CALLER_KEYWORD)?
+                .map(|arg| parse_hex(CALLER_KEYWORD, &arg).map(|caller| (*caller).into()))
+                .transpose()?,
+            coins: balances_arg(COINS_KEYWORD)?,
+            balances: balances_arg(BALANCES_KEYWORD)?,
+            storage: pairs_arg(STORAGE_SLOTS_KEYWORD)?
+                .into_iter()
+                .map(|(key, value)| {
+                    Ok((
+                        parse_hex(STORAGE_SLOTS_KEYWORD, &key)?,
+                        parse_hex(STORAGE_SLOTS_KEYWORD, &value)?,
+                    ))
+                })
+                .collect::<Result<_>>()?,
+        };
+        let is_contract = matches!(program.kind, ty::TyProgramKind::Contract { .. });
+        if !is_contract && (!chain_state.balances.is_empty() || !chain_state.storage.is_empty()) {
+            bail!(
+                "Test {test_name} declares the balances or storage of the contract under test, \
+                 but is not declared in a contract."
+            )
+        }
+
+        let fuzz = match test_args.get(FUZZ_TEST_KEYWORD) {
+            Some(args) => {
+                if test_function_decl.parameters.is_empty() {
+                    bail!(
+                        "Test {test_name} is declared with `#[test(fuzz)]` but has no parameters."
+                    )
+                }
+                let runs = args
+                    .as_ref()
+                    .map(|arg| {
+                        let arg_str = arg.replace('"', "");
+                        arg_str.parse::<usize>()
+                    })
+                    .transpose()?;
+                let mut types = vec![];
+                let abi_function = test_function_decl.generate_json_abi_function(
+                    &mut JsonAbiContext {
+                        program,
+                        json_abi_with_callpaths: false,
+                    },
+                    engines.te(),
+                    engines.de(),
+                    &mut types,
+                );
+                let mut abi = program_abi::ProgramABI {
+                    types,
+                    functions: vec![abi_function],
+                    logged_types: None,
+                    messages_types: None,
+                    configurables: None,
+                };
+                standardize_json_abi_types(&mut abi);
+                Some(FuzzTestEntry { abi, runs })
+            }
+            None => {
+                if !test_function_decl.parameters.is_empty() {
+                    bail!(
+                        "Test {test_name} has parameters, so must be declared with \
+                         `#[test(fuzz)]` for its inputs to be generated."
+                    )
+                }
+                None
+            }
+        };
 
         let file_path = Arc::new(
             engines.se().get_path(
@@ -2005,10 +2266,39 @@ impl PkgTestEntry {
             pass_condition,
             span,
             file_path,
+            fuzz,
+            expected_receipts,
+            ignored,
+            chain_state,
         })
     }
 }
 
+/// Split the list of values expected to be logged by a test, e.g. `"1, Point { x: 1, y: 2 }"`,
+/// into the individual values.  Only commas outside of any brackets separate values.
+fn split_expected_logs(logs: &str) -> Vec<String> {
+    let mut values = vec![];
+    let mut depth = 0usize;
+    let mut value = String::new();
+    for c in logs.chars() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                values.push(value.trim().to_string());
+                value.clear();
+                continue;
+            }
+            _ => (),
+        }
+        value.push(c);
+    }
+    if !value.trim().is_empty() || !values.is_empty() {
+        values.push(value.trim().to_string());
+    }
+    values
+}
+
 /// The suffix that helps identify the file which contains the hash of the binary file created when
 /// scripts are built_package.
 pub const SWAY_BIN_HASH_SUFFIX: &str = "-bin-hash";
@@ -2074,6 +2364,11 @@ fn build_profile_from_opts(
         profile.print_dca_graph_url_format = print.dca_graph_url_format.clone();
     }
     profile.print_ir |= print.ir;
+    for pass in &print.ir_after {
+        if !profile.print_ir_after.contains(pass) {
+            profile.print_ir_after.push(pass.clone());
+        }
+    }
     profile.print_finalized_asm |= print.finalized_asm;
     profile.print_intermediate_asm |= print.intermediate_asm;
     profile.terse |= pkg.terse;
@@ -2290,7 +2585,8 @@ pub fn build(
         let is_contract_dependency = is_contract_dependency(plan.graph(), node);
         // If we are building a contract and tests are enabled or we are building a contract
         // dependency, we need the tests exlcuded bytecode.
-        let bytecode_without_tests = if (include_tests
+        let mut source_map_without_tests = None;
+        let (bytecode_without_tests, program_abi_without_tests) = if (include_tests
             && matches!(manifest.program_type(), Ok(TreeType::Contract)))
             || is_contract_dependency
         {
@@ -2319,12 +2615,14 @@ pub fn build(
                 Err(errs) => return fail(&[], &errs),
             };
 
-            let compiled_without_tests = compile(
+            // The contract without tests gets a source map of its own, as its instructions are laid
+            // out differently.
+            let mut compiled_without_tests = compile(
                 &descriptor,
                 &profile,
                 &engines,
                 dep_namespace,
-                &mut source_map,
+                &mut SourceMap::new(),
             )?;
 
             if let Some(outfile) = profile.metrics_outfile {
@@ -2354,9 +2652,16 @@ pub fn build(
                 // We finally set the contract ID value here to use for compilation later if tests are enabled.
                 contract_id_value = Some(format!("0x{contract_id}"));
             }
-            Some(compiled_without_tests.bytecode)
+            if let ProgramABI::Fuel(ref mut program_abi) = compiled_without_tests.program_abi {
+                standardize_json_abi_types(program_abi);
+            }
+            source_map_without_tests = Some(compiled_without_tests.source_map);
+            (
+                Some(compiled_without_tests.bytecode),
+                Some(compiled_without_tests.program_abi),
+            )
         } else {
-            None
+            (None, None)
         };
 
         // Build all non member nodes with tests disabled by overriding the current profile.
@@ -2418,6 +2723,8 @@ pub fn build(
             bytecode: compiled.bytecode,
             warnings: compiled.warnings,
             bytecode_without_tests,
+            program_abi_without_tests,
+            source_map_without_tests,
         };
 
         if outputs.contains(&node) {
@@ -159,6 +159,7 @@ fn build_opts_from_cmd(cmd: &cmd::Run) -> pkg::BuildOpts {
             finalized_asm: cmd.print.finalized_asm,
             intermediate_asm: cmd.print.intermediate_asm,
             ir: cmd.print.ir,
+            ir_after: cmd.print.ir_after.clone(),
         },
         minify: pkg::MinifyOpts {
             json_abi: cmd.minify.json_abi,
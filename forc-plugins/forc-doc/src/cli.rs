@@ -31,4 +31,16 @@ pub struct Command {
     /// Do not build documentation for dependencies.
     #[clap(long)]
     pub no_deps: bool,
+    /// The format to build the documentation in. The `json` format writes the documented items of
+    /// the package and its dependencies to `out/doc/doc.json`, in place of the HTML pages.
+    #[clap(long, value_enum, default_value_t = DocFormat::Html, conflicts_with = "open")]
+    pub format: DocFormat,
+}
+
+/// The formats `forc doc` can build the documentation in.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum DocFormat {
+    #[default]
+    Html,
+    Json,
 }
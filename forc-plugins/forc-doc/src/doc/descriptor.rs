@@ -70,7 +70,9 @@ impl Descriptor {
                             attrs_opt: attrs_opt.clone(),
                             item_context: ItemContext {
                                 context_opt: context,
+                                inherent_impls: None,
                                 impl_traits: None,
+                                implementors: None,
                             },
                         },
                         raw_attributes: attrs_opt,
@@ -107,7 +109,9 @@ impl Descriptor {
                             attrs_opt: attrs_opt.clone(),
                             item_context: ItemContext {
                                 context_opt: context,
+                                inherent_impls: None,
                                 impl_traits: None,
+                                implementors: None,
                             },
                         },
                         raw_attributes: attrs_opt,
@@ -155,7 +159,9 @@ impl Descriptor {
                             attrs_opt: attrs_opt.clone(),
                             item_context: ItemContext {
                                 context_opt: context,
+                                inherent_impls: None,
                                 impl_traits: None,
+                                implementors: None,
                             },
                         },
                         raw_attributes: attrs_opt,
@@ -197,7 +203,9 @@ impl Descriptor {
                         attrs_opt: attrs_opt.clone(),
                         item_context: ItemContext {
                             context_opt: context,
+                            inherent_impls: None,
                             impl_traits: None,
+                            implementors: None,
                         },
                     },
                     raw_attributes: attrs_opt,
@@ -232,7 +240,9 @@ impl Descriptor {
                         attrs_opt: attrs_opt.clone(),
                         item_context: ItemContext {
                             context_opt: context,
+                            inherent_impls: None,
                             impl_traits: None,
+                            implementors: None,
                         },
                     },
                     raw_attributes: attrs_opt,
@@ -292,7 +302,9 @@ impl Descriptor {
                             attrs_opt: attrs_opt.clone(),
                             item_context: ItemContext {
                                 context_opt: None,
+                                inherent_impls: None,
                                 impl_traits: None,
+                                implementors: None,
                             },
                         },
                         raw_attributes: attrs_opt,
@@ -325,7 +337,9 @@ impl Descriptor {
                             attrs_opt: attrs_opt.clone(),
                             item_context: ItemContext {
                                 context_opt: None,
+                                inherent_impls: None,
                                 impl_traits: None,
+                                implementors: None,
                             },
                         },
                         raw_attributes: attrs_opt,
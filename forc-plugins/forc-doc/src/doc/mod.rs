@@ -1,14 +1,25 @@
 use crate::{
     doc::{descriptor::Descriptor, module::ModuleInfo},
-    render::{item::components::*, link::DocLink, util::format::docstring::*},
+    render::{
+        item::{components::*, context::DocImplTrait},
+        link::DocLink,
+        util::format::docstring::*,
+    },
 };
 use anyhow::Result;
 use std::option::Option;
 use sway_core::{
-    decl_engine::DeclEngine,
-    language::ty::{TyAstNodeContent, TyDecl, TyImplTrait, TyModule, TyProgram, TySubmodule},
+    decl_engine::{DeclEngine, InterfaceDeclId},
+    language::{
+        ty::{
+            AbiDecl, EnumDecl, StructDecl, TraitDecl, TyAstNodeContent, TyDecl, TyImplTrait,
+            TyModule, TyProgram, TySubmodule,
+        },
+        CallPath,
+    },
+    Engines, TypeInfo,
 };
-use sway_types::Spanned;
+use sway_types::Span;
 
 mod descriptor;
 pub mod module;
@@ -16,23 +27,23 @@ pub mod module;
 #[derive(Default)]
 pub(crate) struct Documentation(pub(crate) Vec<Document>);
 impl Documentation {
-    /// Gather [Documentation] from the [TyProgram].
+    /// Gather [Documentation] from the [TyProgram], along with its `impl` blocks.
     pub(crate) fn from_ty_program(
         decl_engine: &DeclEngine,
         project_name: &str,
         typed_program: &TyProgram,
         document_private_items: bool,
+        impl_traits: &mut Vec<TyImplTrait>,
     ) -> Result<Documentation> {
         // the first module prefix will always be the project name
         let mut docs: Documentation = Default::default();
-        let mut impl_traits: Vec<TyImplTrait> = Vec::new();
         let module_info = ModuleInfo::from_ty_module(vec![project_name.to_owned()], None);
         Documentation::from_ty_module(
             decl_engine,
             module_info,
             &typed_program.root,
             &mut docs,
-            &mut impl_traits,
+            impl_traits,
             document_private_items,
         )?;
 
@@ -46,41 +57,110 @@ impl Documentation {
                 decl_engine,
                 typed_submodule,
                 &mut docs,
-                &mut impl_traits,
+                impl_traits,
                 &module_prefix,
                 document_private_items,
             )?;
         }
 
-        // match for the spans to add the impl_traits to their corresponding doc:
-        // currently this compares the spans as str, but this needs to change
-        // to compare the actual types
-        if !impl_traits.is_empty() {
-            for doc in &mut docs.0 {
-                let mut impl_vec: Vec<TyImplTrait> = Vec::new();
-
-                match doc.item_body.ty_decl {
-                    TyDecl::StructDecl(ref struct_decl) => {
-                        for impl_trait in &impl_traits {
-                            if struct_decl.name.as_str()
-                                == impl_trait.implementing_for.span.as_str()
-                                && struct_decl.name.as_str()
-                                    != impl_trait.trait_name.suffix.span().as_str()
-                            {
-                                impl_vec.push(impl_trait.clone());
-                            }
-                        }
-                    }
-                    _ => continue,
+        Ok(docs)
+    }
+    /// Add the `impl` blocks of every documented package to the [Document]s of the types they are
+    /// for, and of the traits or abis they implement, so that they are linked across packages.
+    pub(crate) fn link_impl_traits(
+        engines: &Engines,
+        docs: &mut [Documentation],
+        impl_traits: &[TyImplTrait],
+    ) {
+        let decl_engine = engines.de();
+        let type_engine = engines.te();
+        // The documented traits and abis, along with the spans of their declarations.
+        let interface_links = docs
+            .iter()
+            .flat_map(|docs| docs.0.iter())
+            .filter_map(|doc| match &doc.item_body.ty_decl {
+                TyDecl::TraitDecl(TraitDecl { decl_id, .. }) => {
+                    Some((decl_engine.get_trait(decl_id).span, doc.link()))
+                }
+                TyDecl::AbiDecl(AbiDecl { decl_id, .. }) => {
+                    Some((decl_engine.get_abi(decl_id).span, doc.link()))
                 }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let interface_span = |impl_trait: &TyImplTrait| -> Option<Span> {
+            impl_trait
+                .trait_decl_ref
+                .as_ref()
+                .map(|decl_ref| match decl_ref.id() {
+                    InterfaceDeclId::Trait(decl_id) => decl_engine.get_trait(decl_id).span,
+                    InterfaceDeclId::Abi(decl_id) => decl_engine.get_abi(decl_id).span,
+                })
+        };
+        let implementing_for = |impl_trait: &TyImplTrait| -> Option<CallPath> {
+            match type_engine.get(impl_trait.implementing_for.type_id) {
+                TypeInfo::Struct(decl_ref) => Some(decl_engine.get_struct(&decl_ref).call_path),
+                TypeInfo::Enum(decl_ref) => Some(decl_engine.get_enum(&decl_ref).call_path),
+                _ => None,
+            }
+        };
 
-                if !impl_vec.is_empty() {
-                    doc.item_body.item_context.impl_traits = Some(impl_vec);
+        for doc in docs.iter_mut().flat_map(|docs| docs.0.iter_mut()) {
+            let to_doc_impls = |impl_traits: Vec<&TyImplTrait>| {
+                (!impl_traits.is_empty()).then(|| {
+                    impl_traits
+                        .into_iter()
+                        .map(|impl_trait| DocImplTrait {
+                            impl_trait: impl_trait.clone(),
+                            module_info: doc.module_info.clone(),
+                            trait_link: interface_span(impl_trait).and_then(|span| {
+                                interface_links
+                                    .iter()
+                                    .find(|(interface_span, _)| *interface_span == span)
+                                    .map(|(_, link)| link.clone())
+                            }),
+                        })
+                        .collect::<Vec<_>>()
+                })
+            };
+            let type_call_path = match &doc.item_body.ty_decl {
+                TyDecl::StructDecl(StructDecl { decl_id, .. }) => {
+                    Some(decl_engine.get_struct(decl_id).call_path)
+                }
+                TyDecl::EnumDecl(EnumDecl { decl_id, .. }) => {
+                    Some(decl_engine.get_enum(decl_id).call_path)
                 }
+                _ => None,
+            };
+            let interface_decl_span = match &doc.item_body.ty_decl {
+                TyDecl::TraitDecl(TraitDecl { decl_id, .. }) => {
+                    Some(decl_engine.get_trait(decl_id).span)
+                }
+                TyDecl::AbiDecl(AbiDecl { decl_id, .. }) => Some(decl_engine.get_abi(decl_id).span),
+                _ => None,
+            };
+
+            if let Some(call_path) = type_call_path {
+                let (inherent_impls, trait_impls): (Vec<_>, Vec<_>) = impl_traits
+                    .iter()
+                    .filter(|impl_trait| implementing_for(impl_trait).as_ref() == Some(&call_path))
+                    .partition(|impl_trait| impl_trait.trait_decl_ref.is_none());
+                let inherent_impls = to_doc_impls(inherent_impls);
+                let trait_impls = to_doc_impls(trait_impls);
+                doc.item_body.item_context.inherent_impls = inherent_impls;
+                doc.item_body.item_context.impl_traits = trait_impls;
+            } else if let Some(decl_span) = interface_decl_span {
+                let implementors = to_doc_impls(
+                    impl_traits
+                        .iter()
+                        .filter(|impl_trait| {
+                            interface_span(impl_trait).as_ref() == Some(&decl_span)
+                        })
+                        .collect(),
+                );
+                doc.item_body.item_context.implementors = implementors;
             }
         }
-
-        Ok(docs)
     }
     fn from_ty_module(
         decl_engine: &DeclEngine,
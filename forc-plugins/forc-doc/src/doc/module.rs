@@ -122,11 +122,20 @@ impl ModuleInfo {
     /// result           =               "../../other_submodule/enum.Name.html";
     /// ```
     /// In this case the first module to match is "module", so we have no need to go back further than that.
+    ///
+    /// An item of another package is reached from the root of the documentation, where the
+    /// documentation of each package has its own directory.
     pub(crate) fn file_path_from_location(
         &self,
         file_name: &str,
         current_module_info: &ModuleInfo,
     ) -> Result<String> {
+        if self.project_name() != current_module_info.project_name() {
+            return Ok(current_module_info.to_html_shorthand_path_string(&format!(
+                "{}/{file_name}",
+                self.module_prefixes.join("/")
+            )));
+        }
         let mut mid = 0; // the index to split the module_info from call_path at
         let mut offset = 0; // the number of directories to go back
         let mut next_location_iter = self.module_prefixes.iter().rev().enumerate().peekable();
@@ -206,4 +215,28 @@ mod tests {
         let project_opt = module_info.parent();
         assert_eq!(None, project_opt);
     }
+
+    #[test]
+    fn test_file_path_from_location() {
+        let module_info = |module_prefixes: &[&str]| {
+            ModuleInfo::from_ty_module(
+                module_prefixes
+                    .iter()
+                    .map(|prefix| prefix.to_string())
+                    .collect(),
+                None,
+            )
+        };
+        let current_module_info = module_info(&["core", "ops"]);
+
+        let file_path = module_info(&["core", "ops"])
+            .file_path_from_location("trait.Eq.html", &current_module_info)
+            .unwrap();
+        assert_eq!("../ops/trait.Eq.html", file_path);
+
+        let file_path = module_info(&["impl_traits", "bar"])
+            .file_path_from_location("struct.Bar.html", &current_module_info)
+            .unwrap();
+        assert_eq!("../../impl_traits/bar/struct.Bar.html", file_path);
+    }
 }
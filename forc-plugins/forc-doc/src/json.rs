@@ -0,0 +1,431 @@
+//! The documentation as JSON, for tools building on the documented items rather than on their
+//! rendered pages.
+//!
+//! The schema is versioned by [JSON_SCHEMA_VERSION], which is bumped whenever a field is renamed
+//! or removed, or changes meaning. Fields may be added without bumping it.
+use crate::{
+    doc::{Document, Documentation},
+    render::{item::context::DocImplTrait, util::format::docstring::DocStrings},
+};
+use anyhow::Result;
+use serde::Serialize;
+use std::{fs, path::Path};
+use sway_core::{
+    decl_engine::DeclEngine,
+    language::ty::{self, TyFunctionParameter, TyTraitInterfaceItem, TyTraitItem},
+    transform::{AttributeKind, AttributesMap},
+    TypeParameter,
+};
+use sway_types::{Ident, Span, Spanned};
+
+/// The version of the schema of the JSON documentation.
+pub(crate) const JSON_SCHEMA_VERSION: u32 = 1;
+/// The name of the file the JSON documentation is written to, within the documentation directory.
+pub(crate) const JSON_FILENAME: &str = "doc.json";
+
+/// The documentation of a package and its dependencies.
+#[derive(Debug, Serialize)]
+pub(crate) struct JsonDocumentation {
+    schema_version: u32,
+    packages: Vec<JsonPackage>,
+}
+impl JsonDocumentation {
+    pub(crate) fn new() -> Self {
+        Self {
+            schema_version: JSON_SCHEMA_VERSION,
+            packages: vec![],
+        }
+    }
+    /// Add the documented items of a package.
+    pub(crate) fn add_package(
+        &mut self,
+        decl_engine: &DeclEngine,
+        name: &str,
+        raw_docs: &Documentation,
+    ) {
+        self.packages.push(JsonPackage {
+            name: name.to_owned(),
+            items: raw_docs
+                .0
+                .iter()
+                .map(|doc| JsonItem::from_doc(decl_engine, doc))
+                .collect(),
+        });
+    }
+    /// Write the documentation to the root of the documentation directory.
+    pub(crate) fn write(&self, doc_path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(doc_path.join(JSON_FILENAME), json)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonPackage {
+    name: String,
+    items: Vec<JsonItem>,
+}
+
+/// A documented item.
+#[derive(Debug, Default, Serialize)]
+struct JsonItem {
+    name: String,
+    /// The full path of the item, e.g. `std::vec::Vec`.
+    path: String,
+    /// The kind of the item, e.g. `struct`.
+    kind: &'static str,
+    /// Whether the item is `pub`. Abis and contract storage are always public.
+    public: bool,
+    /// The declaration of the item, formatted, without the body of functions.
+    signature: String,
+    /// The names of the type parameters of the item.
+    generics: Vec<String>,
+    /// The doc comments of the item, as markdown.
+    docs: String,
+    /// The attributes of the item other than doc comments.
+    attributes: Vec<JsonAttribute>,
+    /// The fields of a struct or of contract storage.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fields: Vec<JsonField>,
+    /// The variants of an enum.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    variants: Vec<JsonField>,
+    /// The methods required by a trait or abi, or those of the inherent `impl` blocks of a type.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    methods: Vec<JsonMethod>,
+    /// The traits implemented by a type, or the types implementing a trait or abi.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    implementations: Vec<JsonImplementation>,
+}
+impl JsonItem {
+    fn from_doc(decl_engine: &DeclEngine, doc: &Document) -> Self {
+        let name = doc.item_header.item_name.as_str().to_owned();
+        let module_prefixes = &doc.module_info.module_prefixes;
+        let item = Self {
+            path: format!("{}::{name}", module_prefixes.join("::")),
+            kind: doc.item_body.ty_decl.doc_name(),
+            signature: doc.item_body.code_str.clone(),
+            name,
+            ..Default::default()
+        };
+        let item_context = &doc.item_body.item_context;
+        match &doc.item_body.ty_decl {
+            ty::TyDecl::StructDecl(ty::StructDecl { decl_id, .. }) => {
+                let struct_decl = decl_engine.get_struct(decl_id);
+                Self {
+                    public: struct_decl.visibility.is_public(),
+                    generics: generics(&struct_decl.type_parameters),
+                    fields: struct_decl
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            JsonField::new(&field.name, &field.type_argument, &field.attributes)
+                        })
+                        .collect(),
+                    methods: inherent_methods(decl_engine, &item_context.inherent_impls),
+                    implementations: implementations(&item_context.impl_traits),
+                    ..item.with_attributes(&struct_decl.attributes)
+                }
+            }
+            ty::TyDecl::EnumDecl(ty::EnumDecl { decl_id, .. }) => {
+                let enum_decl = decl_engine.get_enum(decl_id);
+                Self {
+                    public: enum_decl.visibility.is_public(),
+                    generics: generics(&enum_decl.type_parameters),
+                    variants: enum_decl
+                        .variants
+                        .iter()
+                        .map(|variant| {
+                            JsonField::new(
+                                &variant.name,
+                                &variant.type_argument,
+                                &variant.attributes,
+                            )
+                        })
+                        .collect(),
+                    methods: inherent_methods(decl_engine, &item_context.inherent_impls),
+                    implementations: implementations(&item_context.impl_traits),
+                    ..item.with_attributes(&enum_decl.attributes)
+                }
+            }
+            ty::TyDecl::TraitDecl(ty::TraitDecl { decl_id, .. }) => {
+                let trait_decl = decl_engine.get_trait(decl_id);
+                Self {
+                    public: trait_decl.visibility.is_public(),
+                    generics: generics(&trait_decl.type_parameters),
+                    methods: required_methods(decl_engine, &trait_decl.interface_surface),
+                    implementations: implementations(&item_context.implementors),
+                    ..item.with_attributes(&trait_decl.attributes)
+                }
+            }
+            ty::TyDecl::AbiDecl(ty::AbiDecl { decl_id, .. }) => {
+                let abi_decl = decl_engine.get_abi(decl_id);
+                Self {
+                    public: true,
+                    methods: required_methods(decl_engine, &abi_decl.interface_surface),
+                    implementations: implementations(&item_context.implementors),
+                    ..item.with_attributes(&abi_decl.attributes)
+                }
+            }
+            ty::TyDecl::StorageDecl(ty::StorageDecl { decl_id, .. }) => {
+                let storage_decl = decl_engine.get_storage(decl_id);
+                Self {
+                    public: true,
+                    fields: storage_decl
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            JsonField::new(&field.name, &field.type_argument, &field.attributes)
+                        })
+                        .collect(),
+                    ..item.with_attributes(&storage_decl.attributes)
+                }
+            }
+            ty::TyDecl::FunctionDecl(ty::FunctionDecl { decl_id, .. }) => {
+                let fn_decl = decl_engine.get_function(decl_id);
+                Self {
+                    public: fn_decl.visibility.is_public(),
+                    generics: generics(&fn_decl.type_parameters),
+                    ..item.with_attributes(&fn_decl.attributes)
+                }
+            }
+            ty::TyDecl::ConstantDecl(ty::ConstantDecl { decl_id, .. }) => {
+                let const_decl = decl_engine.get_constant(decl_id);
+                Self {
+                    public: const_decl.visibility.is_public(),
+                    ..item.with_attributes(&const_decl.attributes)
+                }
+            }
+            _ => item,
+        }
+    }
+    fn with_attributes(self, attributes: &AttributesMap) -> Self {
+        Self {
+            docs: attributes.to_raw_string(),
+            attributes: json_attributes(attributes),
+            ..self
+        }
+    }
+}
+
+/// An attribute other than a doc comment, e.g. `#[storage(read)]`.
+#[derive(Debug, Serialize)]
+struct JsonAttribute {
+    name: String,
+    args: Vec<String>,
+}
+
+/// A field of a struct or of contract storage, or a variant of an enum.
+#[derive(Debug, Serialize)]
+struct JsonField {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+    docs: String,
+}
+impl JsonField {
+    fn new(
+        name: &Ident,
+        type_argument: &sway_core::TypeArgument,
+        attributes: &AttributesMap,
+    ) -> Self {
+        Self {
+            name: name.as_str().to_owned(),
+            ty: type_argument.span.as_str().to_owned(),
+            docs: attributes.to_raw_string(),
+        }
+    }
+}
+
+/// A method of a trait, an abi or a type.
+#[derive(Debug, Serialize)]
+struct JsonMethod {
+    name: String,
+    /// The declaration of the method, without its body.
+    signature: String,
+    parameters: Vec<JsonParameter>,
+    return_type: String,
+    /// The storage access of the method, as given by its `#[storage]` attribute: `read` and or
+    /// `write`, or nothing if the method is pure.
+    storage: Vec<String>,
+    docs: String,
+    attributes: Vec<JsonAttribute>,
+}
+impl JsonMethod {
+    fn new(
+        name: &Ident,
+        parameters: &[TyFunctionParameter],
+        return_type_span: &Span,
+        attributes: &AttributesMap,
+    ) -> Self {
+        let parameters = parameters
+            .iter()
+            .map(|param| JsonParameter {
+                name: param.name.as_str().to_owned(),
+                // The span of the type of `self` is that of `self` itself.
+                ty: match param.is_self() {
+                    true => "Self".to_owned(),
+                    false => param.type_argument.span.as_str().to_owned(),
+                },
+                is_reference: param.is_reference,
+                is_mutable: param.is_mutable,
+            })
+            .collect::<Vec<_>>();
+        let params = parameters
+            .iter()
+            .map(|param| {
+                let prefix = match (param.is_reference, param.is_mutable) {
+                    (true, true) => "ref mut ",
+                    (false, true) => "mut ",
+                    _ => "",
+                };
+                match param.name == "self" {
+                    true => format!("{prefix}self"),
+                    false => format!("{prefix}{}: {}", param.name, param.ty),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let (return_type, signature) = match explicit_return_type(name, return_type_span) {
+            Some(return_type) => (
+                return_type,
+                format!("fn {}({params}) -> {return_type}", name.as_str()),
+            ),
+            None => ("()", format!("fn {}({params})", name.as_str())),
+        };
+        Self {
+            name: name.as_str().to_owned(),
+            signature,
+            parameters,
+            return_type: return_type.to_owned(),
+            storage: attributes
+                .get(&AttributeKind::Storage)
+                .into_iter()
+                .flatten()
+                .flat_map(|attribute| &attribute.args)
+                .map(|arg| arg.name.as_str().to_owned())
+                .collect(),
+            docs: attributes.to_raw_string(),
+            attributes: json_attributes(attributes),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct JsonParameter {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+    is_reference: bool,
+    is_mutable: bool,
+}
+
+/// An `impl` block of a trait or abi for a type.
+#[derive(Debug, Serialize)]
+struct JsonImplementation {
+    /// The trait or abi implemented.
+    #[serde(rename = "trait")]
+    trait_name: String,
+    /// The type implementing it.
+    #[serde(rename = "for")]
+    implementing_for: String,
+}
+
+/// The return type of a method, if it is given in the declaration of the method.
+///
+/// A method declared without one returns `()`, and the span of its return type is then that of
+/// its whole signature, which includes its name.
+fn explicit_return_type<'a>(name: &Ident, return_type_span: &'a Span) -> Option<&'a str> {
+    let name_span = name.span();
+    let is_signature = return_type_span.source_id() == name_span.source_id()
+        && return_type_span.start() <= name_span.start()
+        && name_span.end() <= return_type_span.end();
+    (!is_signature).then(|| return_type_span.as_str())
+}
+
+fn generics(type_parameters: &[TypeParameter]) -> Vec<String> {
+    type_parameters
+        .iter()
+        .map(|param| param.name_ident.as_str().to_owned())
+        .collect()
+}
+
+fn json_attributes(attributes: &AttributesMap) -> Vec<JsonAttribute> {
+    let mut json_attributes = attributes
+        .iter()
+        .filter(|(kind, _)| !matches!(kind, AttributeKind::Doc | AttributeKind::DocComment))
+        .flat_map(|(_, attributes)| attributes)
+        .map(|attribute| JsonAttribute {
+            name: attribute.name.as_str().to_owned(),
+            args: attribute
+                .args
+                .iter()
+                .map(|arg| arg.name.as_str().to_owned())
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    // The attributes are kept in a map, so sort them to keep the output stable.
+    json_attributes.sort_by(|a, b| (&a.name, &a.args).cmp(&(&b.name, &b.args)));
+    json_attributes
+}
+
+fn required_methods(
+    decl_engine: &DeclEngine,
+    interface_surface: &[TyTraitInterfaceItem],
+) -> Vec<JsonMethod> {
+    interface_surface
+        .iter()
+        .flat_map(|item| match item {
+            TyTraitInterfaceItem::TraitFn(decl_ref) => Some(decl_engine.get_trait_fn(decl_ref)),
+            _ => None,
+        })
+        .map(|method| {
+            JsonMethod::new(
+                &method.name,
+                &method.parameters,
+                &method.return_type_span,
+                &method.attributes,
+            )
+        })
+        .collect()
+}
+
+fn inherent_methods(
+    decl_engine: &DeclEngine,
+    inherent_impls: &Option<Vec<DocImplTrait>>,
+) -> Vec<JsonMethod> {
+    inherent_impls
+        .iter()
+        .flatten()
+        .flat_map(|doc_impl_trait| &doc_impl_trait.impl_trait.items)
+        .flat_map(|item| match item {
+            TyTraitItem::Fn(decl_ref) => Some(decl_engine.get_function(decl_ref.id())),
+            _ => None,
+        })
+        .map(|method| {
+            JsonMethod::new(
+                &method.name,
+                &method.parameters,
+                &method.return_type.span,
+                &method.attributes,
+            )
+        })
+        .collect()
+}
+
+fn implementations(impl_traits: &Option<Vec<DocImplTrait>>) -> Vec<JsonImplementation> {
+    impl_traits
+        .iter()
+        .flatten()
+        .map(|doc_impl_trait| JsonImplementation {
+            trait_name: doc_impl_trait.impl_trait.trait_name.to_string(),
+            implementing_for: doc_impl_trait
+                .impl_trait
+                .implementing_for
+                .span
+                .as_str()
+                .to_owned(),
+        })
+        .collect()
+}
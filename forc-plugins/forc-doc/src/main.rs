@@ -1,16 +1,19 @@
 use crate::{
     doc::Documentation,
-    render::{constant::INDEX_FILENAME, RenderedDocumentation},
+    json::{JsonDocumentation, JSON_FILENAME},
+    render::{constant::INDEX_FILENAME, intra_doc_links::IntraDocLinks, RenderedDocumentation},
+    search::{search_items, write_search_index, SearchIndex},
 };
 use anyhow::{bail, Result};
 use clap::Parser;
-use cli::Command;
+use cli::{Command, DocFormat};
 use colored::*;
 use forc_pkg as pkg;
 use forc_util::default_output_directory;
 use include_dir::{include_dir, Dir};
 use pkg::{manifest::ManifestFile, PackageManifestFile};
 use std::{
+    collections::BTreeSet,
     path::Path,
     process::Command as Process,
     {fs, path::PathBuf},
@@ -19,7 +22,11 @@ use sway_core::{language::ty::TyProgram, BuildTarget, Engines};
 
 mod cli;
 mod doc;
+mod json;
 mod render;
+mod search;
+#[cfg(test)]
+mod tests;
 
 pub(crate) const ASSETS_DIR_NAME: &str = "static.files";
 
@@ -47,7 +54,14 @@ struct ProgramInfo<'a> {
 /// Main method for `forc doc`.
 pub fn main() -> Result<()> {
     let build_instructions = Command::parse();
+    build(&build_instructions)?;
 
+    Ok(())
+}
+
+/// Build the documentation as given by `build_instructions`, returning the path of the
+/// documentation directory.
+fn build(build_instructions: &Command) -> Result<PathBuf> {
     // get manifest directory
     let dir = if let Some(ref path) = build_instructions.manifest_path {
         PathBuf::from(path)
@@ -96,6 +110,9 @@ pub fn main() -> Result<()> {
         &engines,
     )?;
 
+    // Gather the typed programs of all of the packages to document first, so that the items of
+    // each package can be linked to those of the others.
+    let mut programs = vec![];
     if !build_instructions.no_deps {
         let order = plan.compilation_order();
         let graph = plan.graph();
@@ -113,14 +130,7 @@ pub fn main() -> Result<()> {
                         pkg_manifest_file.path().display()
                     ),
                 };
-                let program_info = ProgramInfo {
-                    ty_program,
-                    engines: &engines,
-                    manifest: &manifest_file,
-                    pkg_manifest: pkg_manifest_file,
-                };
-
-                build_docs(program_info, &doc_path, &build_instructions)?;
+                programs.push((ty_program, manifest_file, pkg_manifest_file));
             }
         }
     } else {
@@ -135,14 +145,67 @@ pub fn main() -> Result<()> {
                 pkg_manifest.path().display()
             ),
         };
+        programs.push((
+            ty_program,
+            ManifestFile::from_dir(manifest.dir())?,
+            pkg_manifest,
+        ));
+    }
+
+    let mut impl_traits = vec![];
+    let mut raw_docs = programs
+        .iter()
+        .map(|(ty_program, _, pkg_manifest)| {
+            Documentation::from_ty_program(
+                engines.de(),
+                pkg_manifest.project_name(),
+                ty_program,
+                build_instructions.document_private_items,
+                &mut impl_traits,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Documentation::link_impl_traits(&engines, &mut raw_docs, &impl_traits);
+
+    if build_instructions.format == DocFormat::Json {
+        let mut json_docs = JsonDocumentation::new();
+        for ((_, _, pkg_manifest), raw_docs) in programs.iter().zip(&raw_docs) {
+            json_docs.add_package(engines.de(), pkg_manifest.project_name(), raw_docs);
+        }
+        json_docs.write(&doc_path)?;
+        println!(
+            "    {} {}",
+            "Finished".bold().yellow(),
+            doc_path.join(JSON_FILENAME).display()
+        );
+        return Ok(doc_path);
+    }
+
+    let mut intra_doc_links = IntraDocLinks::new(engines.de(), &raw_docs);
+    for (ty_program, _, pkg_manifest) in &programs {
+        intra_doc_links.add_scopes(pkg_manifest.project_name(), ty_program);
+    }
+
+    let mut search_index = SearchIndex::new();
+    for ((ty_program, manifest_file, pkg_manifest_file), raw_docs) in
+        programs.into_iter().zip(raw_docs)
+    {
         let program_info = ProgramInfo {
             ty_program,
             engines: &engines,
-            manifest: &manifest,
-            pkg_manifest,
+            manifest: &manifest_file,
+            pkg_manifest: pkg_manifest_file,
         };
-        build_docs(program_info, &doc_path, &build_instructions)?;
+        build_docs(
+            program_info,
+            raw_docs,
+            &doc_path,
+            build_instructions,
+            &intra_doc_links,
+            &mut search_index,
+        )?;
     }
+    write_search_index(&doc_path, &search_index)?;
 
     // CSS, icons and logos
     static ASSETS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/src/static.files");
@@ -184,13 +247,16 @@ pub fn main() -> Result<()> {
         }
     }
 
-    Ok(())
+    Ok(doc_path)
 }
 
 fn build_docs(
     program_info: ProgramInfo,
+    raw_docs: Documentation,
     doc_path: &Path,
     build_instructions: &Command,
+    intra_doc_links: &IntraDocLinks,
+    search_index: &mut SearchIndex,
 ) -> Result<()> {
     let Command {
         document_private_items,
@@ -210,12 +276,10 @@ fn build_docs(
         manifest.dir().to_string_lossy()
     );
 
-    let raw_docs = Documentation::from_ty_program(
-        engines.de(),
-        pkg_manifest.project_name(),
-        &ty_program,
-        document_private_items,
-    )?;
+    search_index.insert(
+        pkg_manifest.project_name().to_owned(),
+        search_items(&raw_docs),
+    );
     let root_attributes =
         (!ty_program.root.attributes.is_empty()).then_some(ty_program.root.attributes);
     let forc_version = pkg_manifest
@@ -233,14 +297,33 @@ fn build_docs(
     )?;
 
     // write file contents to doc folder
-    write_content(rendered_docs, doc_path)?;
+    let mut unresolved_links = BTreeSet::new();
+    write_content(
+        rendered_docs,
+        doc_path,
+        intra_doc_links,
+        &mut unresolved_links,
+    )?;
+    for (module, link) in unresolved_links {
+        println!(
+            "{}: unresolved link to `{link}` in the documentation of `{module}`",
+            "warning".yellow().bold()
+        );
+    }
     println!("    {}", "Finished".bold().yellow());
 
     Ok(())
 }
 
-fn write_content(rendered_docs: RenderedDocumentation, doc_path: &Path) -> Result<()> {
+fn write_content(
+    rendered_docs: RenderedDocumentation,
+    doc_path: &Path,
+    intra_doc_links: &IntraDocLinks,
+    unresolved_links: &mut BTreeSet<(String, String)>,
+) -> Result<()> {
     for doc in rendered_docs.0 {
+        let file_contents =
+            intra_doc_links.resolve(&doc.module_info, &doc.file_contents.0, unresolved_links);
         let mut doc_path = doc_path.to_path_buf();
         for prefix in doc.module_info.module_prefixes {
             doc_path.push(prefix)
@@ -248,7 +331,7 @@ fn write_content(rendered_docs: RenderedDocumentation, doc_path: &Path) -> Resul
 
         fs::create_dir_all(&doc_path)?;
         doc_path.push(doc.html_filename);
-        fs::write(&doc_path, doc.file_contents.0.as_bytes())?;
+        fs::write(&doc_path, file_contents.as_bytes())?;
     }
 
     Ok(())
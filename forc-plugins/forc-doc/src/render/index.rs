@@ -1,6 +1,9 @@
 use crate::{
     doc::module::ModuleInfo,
-    render::{constant::IDENTITY, link::DocLinks, sidebar::*, BlockTitle, DocStyle, Renderable},
+    render::{
+        constant::IDENTITY, link::DocLinks, search::generate_searchbar, sidebar::*, BlockTitle,
+        DocStyle, Renderable,
+    },
     RenderPlan, ASSETS_DIR_NAME,
 };
 use anyhow::Result;
@@ -36,6 +39,7 @@ impl Renderable for AllDocIndex {
     fn render(self, render_plan: RenderPlan) -> Result<Box<dyn RenderBox>> {
         let doc_links = self.all_docs.clone().render(render_plan.clone())?;
         let sidebar = self.sidebar().render(render_plan)?;
+        let searchbar = generate_searchbar(&self.project_name);
         Ok(box_html! {
             head {
                 meta(charset="utf-8");
@@ -57,27 +61,7 @@ impl Renderable for AllDocIndex {
                 : sidebar;
                 main {
                     div(class="width-limiter") {
-                        // div(class="sub-container") {
-                        //     nav(class="sub") {
-                        //         form(class="search-form") {
-                        //             div(class="search-container") {
-                        //                 span;
-                        //                 input(
-                        //                     class="search-input",
-                        //                     name="search",
-                        //                     autocomplete="off",
-                        //                     spellcheck="false",
-                        //                     // TODO: Add functionality.
-                        //                     placeholder="Searchbar unimplemented, see issue #3480...",
-                        //                     type="search"
-                        //                 );
-                        //                 div(id="help-button", title="help", tabindex="-1") {
-                        //                     button(type="button") { : "?" }
-                        //                 }
-                        //             }
-                        //         }
-                        //     }
-                        // }
+                        : searchbar;
                         section(id="main-content", class="content") {
                             h1(class="fqn") {
                                 span(class="in-band") { : "List of all items" }
@@ -157,6 +141,7 @@ impl Renderable for ModuleIndex {
         let ayu_hjs = self
             .module_info
             .to_html_shorthand_path_string(&format!("{ASSETS_DIR_NAME}/ayu.min.css"));
+        let searchbar = generate_searchbar(&self.module_info);
         let mut rendered_module_anchors = self.module_info.get_anchors()?;
         rendered_module_anchors.pop();
 
@@ -184,27 +169,7 @@ impl Renderable for ModuleIndex {
                 : sidebar;
                 main {
                     div(class="width-limiter") {
-                        // div(class="sub-container") {
-                        //     nav(class="sub") {
-                        //         form(class="search-form") {
-                        //             div(class="search-container") {
-                        //                 span;
-                        //                 input(
-                        //                     class="search-input",
-                        //                     name="search",
-                        //                     autocomplete="off",
-                        //                     spellcheck="false",
-                        //                     // TODO: Add functionality.
-                        //                     placeholder="Searchbar unimplemented, see issue #3480...",
-                        //                     type="search"
-                        //                 );
-                        //                 div(id="help-button", title="help", tabindex="-1") {
-                        //                     button(type="button") { : "?" }
-                        //                 }
-                        //             }
-                        //         }
-                        //     }
-                        // }
+                        : searchbar;
                         section(id="main-content", class="content") {
                             div(class="main-heading") {
                                 h1(class="fqn") {
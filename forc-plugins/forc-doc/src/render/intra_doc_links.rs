@@ -0,0 +1,336 @@
+//! Intra-doc links: doc comments linking to other items by their path, e.g. [`Vec`] or
+//! [`std::storage::storage_map::StorageMap::insert`].
+//!
+//! Such links have no destination in markdown, so the doc comments render them as links to
+//! [INTRA_DOC_LINK_HREF]. They are resolved once the pages are rendered, through the names in scope
+//! in the module of the page, or else as absolute paths. Those which can't be resolved are
+//! rendered as their text, e.g. `[<code>Vec</code>]`.
+use crate::{
+    doc::{
+        module::{ModuleInfo, ModulePrefixes},
+        Documentation,
+    },
+    render::constant::INDEX_FILENAME,
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+};
+use sway_core::{
+    decl_engine::DeclEngine,
+    language::ty::{self, TyModule, TyProgram, TyTraitInterfaceItem, TyTraitItem},
+};
+use sway_types::{Span, Spanned};
+
+/// The destination of the links to items by their path in rendered doc comments, until they are
+/// resolved.
+pub(crate) const INTRA_DOC_LINK_HREF: &str = "#intra-doc-link";
+const LINK_START: &str = "<a href=\"#intra-doc-link\">";
+const LINK_END: &str = "</a>";
+const CODE_START: &str = "<code>";
+const CODE_END: &str = "</code>";
+/// The types without a page, which aren't warned about when linked to.
+const PRIMITIVE_TYPES: [&str; 11] = [
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u256",
+    "b256",
+    "bool",
+    "str",
+    "raw_ptr",
+    "raw_slice",
+    "Self",
+];
+
+/// A documented item intra-doc links may point to.
+struct LinkTarget {
+    module_info: ModuleInfo,
+    html_filename: String,
+    /// The ids of the anchors of the fields, variants and methods of the item, by name.
+    members: HashMap<String, String>,
+}
+
+/// The documented items and modules of all documented packages, along with the names in scope in
+/// each of their modules.
+#[derive(Default)]
+pub(crate) struct IntraDocLinks {
+    /// The documented items, by full path, e.g. `std::vec::Vec`.
+    items: HashMap<String, LinkTarget>,
+    /// The full paths of the documented items, by the span of their declaration.
+    decl_paths: HashMap<Span, String>,
+    /// The names in scope in each module, along with the span of the declaration they refer to.
+    scopes: HashMap<ModulePrefixes, HashMap<String, Span>>,
+    modules: BTreeSet<ModulePrefixes>,
+}
+impl IntraDocLinks {
+    /// Collect the items of the [Documentation] of every documented package.
+    pub(crate) fn new(decl_engine: &DeclEngine, docs: &[Documentation]) -> Self {
+        let mut links = IntraDocLinks::default();
+        for doc in docs.iter().flat_map(|docs| docs.0.iter()) {
+            let path = format!(
+                "{}::{}",
+                doc.module_info.module_prefixes.join("::"),
+                doc.item_header.item_name.as_str()
+            );
+            let mut members = HashMap::new();
+            let mut add_methods = |items: &[TyTraitItem]| {
+                for item in items {
+                    if let TyTraitItem::Fn(decl_ref) = item {
+                        let name = decl_ref.name().as_str();
+                        members.insert(name.to_owned(), format!("method.{name}"));
+                    }
+                }
+            };
+            for doc_impl_trait in doc.item_body.item_context.inherent_impls.iter().flatten() {
+                add_methods(&doc_impl_trait.impl_trait.items);
+            }
+            let ty_decl = &doc.item_body.ty_decl;
+            match ty_decl {
+                ty::TyDecl::StructDecl(ty::StructDecl { decl_id, .. }) => {
+                    for field in decl_engine.get_struct(decl_id).fields {
+                        let name = field.name.as_str();
+                        members.insert(name.to_owned(), format!("structfield.{name}"));
+                    }
+                }
+                ty::TyDecl::EnumDecl(ty::EnumDecl { decl_id, .. }) => {
+                    for variant in decl_engine.get_enum(decl_id).variants {
+                        let name = variant.name.as_str();
+                        members.insert(name.to_owned(), format!("variant.{name}"));
+                    }
+                }
+                ty::TyDecl::TraitDecl(ty::TraitDecl { decl_id, .. }) => {
+                    add_required_methods(
+                        &mut members,
+                        &decl_engine.get_trait(decl_id).interface_surface,
+                    );
+                }
+                ty::TyDecl::AbiDecl(ty::AbiDecl { decl_id, .. }) => {
+                    add_required_methods(
+                        &mut members,
+                        &decl_engine.get_abi(decl_id).interface_surface,
+                    );
+                }
+                _ => {}
+            }
+
+            links.decl_paths.insert(ty_decl.span(), path.clone());
+            links
+                .modules
+                .insert(doc.module_info.module_prefixes.clone());
+            links.items.insert(
+                path,
+                LinkTarget {
+                    module_info: doc.module_info.clone(),
+                    html_filename: doc.html_filename(),
+                    members,
+                },
+            );
+        }
+        links
+    }
+    /// Collect the names in scope in the modules of a documented package, declared or imported.
+    pub(crate) fn add_scopes(&mut self, project_name: &str, ty_program: &TyProgram) {
+        self.add_module_scope(vec![project_name.to_owned()], &ty_program.root);
+    }
+    fn add_module_scope(&mut self, module_prefixes: ModulePrefixes, ty_module: &TyModule) {
+        let module = ty_module.namespace.module();
+        let scope = module
+            .symbols()
+            .iter()
+            .chain(module.imported_symbols())
+            .map(|(name, decl)| (name.as_str().to_owned(), decl.span()))
+            .collect();
+        for (_, submodule) in &ty_module.submodules {
+            let mut submodule_prefixes = module_prefixes.clone();
+            submodule_prefixes.push(submodule.mod_name_span.as_str().to_owned());
+            self.add_module_scope(submodule_prefixes, &submodule.module);
+        }
+        self.scopes.insert(module_prefixes, scope);
+    }
+    /// Replace the intra-doc links of a rendered page of the given module by links to the items
+    /// they point to. The links which can't be resolved are left as they are, and collected along
+    /// with the path of the module.
+    pub(crate) fn resolve(
+        &self,
+        module_info: &ModuleInfo,
+        html: &str,
+        unresolved: &mut BTreeSet<(String, String)>,
+    ) -> String {
+        let mut resolved = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find(LINK_START) {
+            let content_start = start + LINK_START.len();
+            let Some(content_len) = rest[content_start..].find(LINK_END) else {
+                break;
+            };
+            let content = &rest[content_start..content_start + content_len];
+            let link_end = content_start + content_len + LINK_END.len();
+            resolved.push_str(&rest[..start]);
+            let path = content
+                .strip_prefix(CODE_START)
+                .and_then(|content| content.strip_suffix(CODE_END))
+                .map(|text| text.strip_suffix("()").unwrap_or(text))
+                .filter(|path| is_path(path));
+            match path.and_then(|path| self.href(module_info, path)) {
+                Some(href) => write!(resolved, "<a href=\"{href}\">{content}</a>"),
+                None => {
+                    if let Some(path) = path.filter(|path| !PRIMITIVE_TYPES.contains(path)) {
+                        unresolved
+                            .insert((module_info.module_prefixes.join("::"), path.to_owned()));
+                    }
+                    write!(resolved, "[{content}]")
+                }
+            }
+            .expect("writing to a String never fails");
+            rest = &rest[link_end..];
+        }
+        resolved.push_str(rest);
+        resolved
+    }
+    /// The link to the item or module at the given path, relative to a page of the given module.
+    fn href(&self, module_info: &ModuleInfo, path: &str) -> Option<String> {
+        let segments = path.split("::").collect::<Vec<_>>();
+        let mut candidates = vec![];
+        // The first segment may be a name in scope in the module.
+        if let Some(decl_path) = self
+            .scopes
+            .get(&module_info.module_prefixes)
+            .and_then(|scope| scope.get(segments[0]))
+            .and_then(|span| self.decl_paths.get(span))
+        {
+            candidates.push(
+                std::iter::once(decl_path.as_str())
+                    .chain(segments[1..].iter().copied())
+                    .collect::<Vec<_>>()
+                    .join("::"),
+            );
+        }
+        // Or else, the path may be absolute, relative to the module, or relative to the package.
+        candidates.push(path.to_owned());
+        candidates.push(format!(
+            "{}::{path}",
+            module_info.module_prefixes.join("::")
+        ));
+        candidates.push(format!("{}::{path}", module_info.project_name()));
+
+        candidates.iter().find_map(|candidate| {
+            if let Some(target) = self.items.get(candidate) {
+                return Some(target.href(module_info, None));
+            }
+            if let Some((parent, member)) = candidate.rsplit_once("::") {
+                if let Some(anchor) = self
+                    .items
+                    .get(parent)
+                    .and_then(|target| target.members.get(member).map(|anchor| (target, anchor)))
+                {
+                    return Some(anchor.0.href(module_info, Some(anchor.1)));
+                }
+            }
+            let module_prefixes = candidate
+                .split("::")
+                .map(str::to_owned)
+                .collect::<ModulePrefixes>();
+            self.modules.contains(&module_prefixes).then(|| {
+                module_info.to_html_shorthand_path_string(&format!(
+                    "{}/{}",
+                    module_prefixes.join("/"),
+                    INDEX_FILENAME
+                ))
+            })
+        })
+    }
+}
+impl LinkTarget {
+    fn href(&self, module_info: &ModuleInfo, anchor: Option<&String>) -> String {
+        let href = module_info.to_html_shorthand_path_string(&format!(
+            "{}/{}",
+            self.module_info.module_prefixes.join("/"),
+            self.html_filename
+        ));
+        match anchor {
+            Some(anchor) => format!("{href}#{anchor}"),
+            None => href,
+        }
+    }
+}
+
+fn add_required_methods(
+    members: &mut HashMap<String, String>,
+    interface_surface: &[TyTraitInterfaceItem],
+) {
+    for item in interface_surface {
+        if let TyTraitInterfaceItem::TraitFn(decl_ref) = item {
+            let name = decl_ref.name().as_str();
+            members.insert(name.to_owned(), format!("tymethod.{name}"));
+        }
+    }
+}
+
+/// Whether the text of a link is a path, e.g. `std::vec::Vec`.
+fn is_path(text: &str) -> bool {
+    !text.is_empty()
+        && text.split("::").all(|segment| {
+            segment
+                .chars()
+                .next()
+                .map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_path, IntraDocLinks, LinkTarget};
+    use crate::{doc::module::ModuleInfo, render::util::format::docstring::markdown_to_html};
+    use std::collections::{BTreeSet, HashMap};
+
+    #[test]
+    fn test_resolve() {
+        let module_info = ModuleInfo::from_ty_module(vec!["pkg".to_owned()], None);
+        let mut links = IntraDocLinks::default();
+        links.items.insert(
+            "pkg::Foo".to_owned(),
+            LinkTarget {
+                module_info: module_info.clone(),
+                html_filename: "struct.Foo.html".to_owned(),
+                members: HashMap::from([("bar".to_owned(), "structfield.bar".to_owned())]),
+            },
+        );
+
+        let html = markdown_to_html(
+            "[`Foo`], [`Foo::bar`], [`Missing`] and [`u64`].\n\n\
+            ```sway\nlet foo = [`Foo`];\n```\n\n\
+            `` [`Foo`] `` and [`Foo` and `Missing`].\n",
+        );
+        let mut unresolved = BTreeSet::new();
+        let resolved = links.resolve(&module_info, &html, &mut unresolved);
+
+        assert!(resolved.contains("<a href=\"../pkg/struct.Foo.html\"><code>Foo</code></a>"));
+        assert!(resolved.contains(
+            "<a href=\"../pkg/struct.Foo.html#structfield.bar\"><code>Foo::bar</code></a>"
+        ));
+        assert!(resolved.contains("[<code>Missing</code>]"));
+        assert!(resolved.contains("[<code>u64</code>]"));
+        assert!(resolved.contains("let foo = [`Foo`];"));
+        assert!(resolved.contains("<code>[`Foo`]</code>"));
+        assert!(resolved.contains("[<code>Foo</code> and <code>Missing</code>]"));
+        assert_eq!(2, resolved.matches("<a href=").count());
+        assert_eq!(
+            BTreeSet::from([("pkg".to_owned(), "Missing".to_owned())]),
+            unresolved
+        );
+    }
+
+    #[test]
+    fn test_is_path() {
+        assert!(is_path("Vec"));
+        assert!(is_path("std::storage::storage_map::StorageMap::insert"));
+        assert!(!is_path("Vec&lt;T&gt;"));
+        assert!(!is_path("std::"));
+        assert!(!is_path("0x00"));
+    }
+}
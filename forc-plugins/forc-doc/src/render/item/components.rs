@@ -1,8 +1,8 @@
 use crate::{
     doc::module::ModuleInfo,
     render::{
-        constant::IDENTITY, item::context::ItemContext, sidebar::*, title::DocBlockTitle, DocStyle,
-        Renderable,
+        constant::IDENTITY, item::context::ItemContext, search::generate_searchbar, sidebar::*,
+        title::DocBlockTitle, DocStyle, Renderable,
     },
     RenderPlan, ASSETS_DIR_NAME,
 };
@@ -109,10 +109,13 @@ impl Renderable for ItemBody {
         let block_title = ty_decl.as_block_title();
         let sidebar = sidebar.render(render_plan.clone())?;
         let item_context = (item_context.context_opt.is_some()
-            || item_context.impl_traits.is_some())
+            || item_context.inherent_impls.is_some()
+            || item_context.impl_traits.is_some()
+            || item_context.implementors.is_some())
         .then(|| -> Result<Box<dyn RenderBox>> { item_context.render(render_plan.clone()) });
         let sway_hjs =
             module_info.to_html_shorthand_path_string(&format!("{ASSETS_DIR_NAME}/highlight.js"));
+        let searchbar = generate_searchbar(&module_info);
         let rendered_module_anchors = module_info.get_anchors()?;
 
         Ok(box_html! {
@@ -121,27 +124,7 @@ impl Renderable for ItemBody {
                 // this is the main code block
                 main {
                     div(class="width-limiter") {
-                        // div(class="sub-container") {
-                        //     nav(class="sub") {
-                        //         form(class="search-form") {
-                        //             div(class="search-container") {
-                        //                 span;
-                        //                 input(
-                        //                     class="search-input",
-                        //                     name="search",
-                        //                     autocomplete="off",
-                        //                     spellcheck="false",
-                        //                     // TODO: https://github.com/FuelLabs/sway/issues/3480
-                        //                     placeholder="Searchbar unimplemented, see issue #3480...",
-                        //                     type="search"
-                        //                 );
-                        //                 div(id="help-button", title="help", tabindex="-1") {
-                        //                     button(type="button") { : "?" }
-                        //                 }
-                        //             }
-                        //         }
-                        //     }
-                        // }
+                        : searchbar;
                         section(id="main-content", class="content") {
                             div(class="main-heading") {
                                 h1(class="fqn") {
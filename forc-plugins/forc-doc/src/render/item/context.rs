@@ -263,10 +263,12 @@ impl Renderable for Context {
 pub(crate) struct ItemContext {
     /// [Context] can be fields on a struct, variants of an enum, etc.
     pub(crate) context_opt: Option<Context>,
+    /// The `impl` blocks of this type which don't implement a trait.
+    pub(crate) inherent_impls: Option<Vec<DocImplTrait>>,
     /// The traits implemented for this type.
-    pub(crate) impl_traits: Option<Vec<TyImplTrait>>,
-    // TODO: All other Implementation types, eg
-    // implementations on foreign types, method implementations, etc.
+    pub(crate) impl_traits: Option<Vec<DocImplTrait>>,
+    /// The implementations of this trait or abi.
+    pub(crate) implementors: Option<Vec<DocImplTrait>>,
 }
 impl ItemContext {
     pub(crate) fn to_doclinks(&self) -> DocLinks {
@@ -335,6 +337,26 @@ impl ItemContext {
                 }
             }
         }
+        let impl_links = |doc_impls: &Vec<DocImplTrait>| {
+            doc_impls
+                .iter()
+                .map(|doc_impl| DocLink {
+                    name: doc_impl.impl_trait.trait_name.suffix.as_str().to_string(),
+                    module_info: ModuleInfo::from_ty_module(vec![], None),
+                    html_filename: format!("{}{}", IDENTITY, doc_impl.anchor_id()),
+                    preview_opt: None,
+                })
+                .collect()
+        };
+        if let Some(inherent_impls) = &self.inherent_impls {
+            links.insert(BlockTitle::Implementations, impl_links(inherent_impls));
+        }
+        if let Some(impl_traits) = &self.impl_traits {
+            links.insert(BlockTitle::TraitImplementations, impl_links(impl_traits));
+        }
+        if let Some(implementors) = &self.implementors {
+            links.insert(BlockTitle::Implementors, impl_links(implementors));
+        }
         DocLinks {
             style: DocStyle::Item {
                 title: None,
@@ -365,13 +387,28 @@ impl Renderable for ItemContext {
             None => None,
         };
 
-        let impl_traits = match self.impl_traits {
-            Some(impl_traits) => {
-                let mut impl_vec: Vec<_> = Vec::new();
-                for impl_trait in impl_traits {
-                    impl_vec.push(impl_trait.render(render_plan.clone())?)
+        let render_impls = |doc_impls: Option<Vec<DocImplTrait>>| -> Result<Option<Vec<_>>> {
+            match doc_impls {
+                Some(doc_impls) => {
+                    let mut impl_vec: Vec<_> = Vec::new();
+                    for doc_impl in doc_impls {
+                        impl_vec.push(doc_impl.render(render_plan.clone())?)
+                    }
+                    Ok(Some(impl_vec))
                 }
-                Some(impl_vec)
+                None => Ok(None),
+            }
+        };
+        let inherent_impls = render_impls(self.inherent_impls)?;
+        let impl_traits = render_impls(self.impl_traits)?;
+        // Implementors are only listed, their items are documented on the pages of their types.
+        let implementors = match self.implementors {
+            Some(implementors) => {
+                let mut implementor_vec: Vec<_> = Vec::new();
+                for implementor in implementors {
+                    implementor_vec.push(implementor.render_header(&render_plan)?)
+                }
+                Some(implementor_vec)
             }
             None => None,
         };
@@ -380,6 +417,17 @@ impl Renderable for ItemContext {
             @ if let Some(context) = context_opt {
                 : Raw(context);
             }
+            @ if inherent_impls.is_some() {
+                h2(id="implementations", class="small-section-header") {
+                    : "Implementations";
+                    a(href=format!("{IDENTITY}implementations"), class="anchor");
+                }
+                div(id="implementations-list") {
+                    @ for inherent_impl in inherent_impls.unwrap() {
+                        : inherent_impl;
+                    }
+                }
+            }
             @ if impl_traits.is_some() {
                 h2(id="trait-implementations", class="small-section-header") {
                     : "Trait Implementations";
@@ -391,38 +439,110 @@ impl Renderable for ItemContext {
                     }
                 }
             }
+            @ if implementors.is_some() {
+                h2(id="implementors", class="small-section-header") {
+                    : "Implementors";
+                    a(href=format!("{IDENTITY}implementors"), class="anchor");
+                }
+                div(id="implementors-list") {
+                    @ for implementor in implementors.unwrap() {
+                        : Raw(implementor);
+                    }
+                }
+            }
         })
     }
 }
-impl Renderable for TyImplTrait {
-    fn render(self, render_plan: RenderPlan) -> Result<Box<dyn RenderBox>> {
-        let TyImplTrait {
-            trait_name,
-            impl_type_parameters: _,
-            trait_type_arguments: _,
-            items,
-            trait_decl_ref: _,
-            implementing_for,
-            ..
-        } = self;
-
-        let mut rendered_items = Vec::new();
-        for item in items {
-            rendered_items.push(item.render(render_plan.clone())?)
+/// An `impl` block, shown on the page of the type it is for or of the trait it implements.
+#[derive(Clone, Debug)]
+pub(crate) struct DocImplTrait {
+    pub(crate) impl_trait: TyImplTrait,
+    /// The module of the page the `impl` block is shown on, which links are relative to.
+    pub(crate) module_info: ModuleInfo,
+    /// The link to the trait or abi implemented, if it is documented.
+    pub(crate) trait_link: Option<DocLink>,
+}
+impl DocImplTrait {
+    /// Whether the `impl` block is for the type itself rather than for a trait.
+    fn is_inherent(&self) -> bool {
+        self.impl_trait.trait_decl_ref.is_none()
+    }
+    /// The id of the anchor of the `impl` block, e.g. `impl-Eq-for-Foo`.
+    fn anchor_id(&self) -> String {
+        let implementing_for = self.impl_trait.implementing_for.span.as_str();
+        match self.is_inherent() {
+            true => format!("impl-{implementing_for}"),
+            false => format!(
+                "impl-{}-for-{implementing_for}",
+                self.impl_trait.trait_name.suffix.as_str()
+            ),
         }
+    }
+    /// Renders the header of the `impl` block, linking to the trait it implements and the type
+    /// it is for when they are documented.
+    fn render_header(&self, render_plan: &RenderPlan) -> Result<String> {
+        let trait_name = self.impl_trait.trait_name.suffix.as_str().to_string();
+        let trait_href = self
+            .trait_link
+            .as_ref()
+            .map(|link| {
+                link.module_info
+                    .file_path_from_location(&link.html_filename, &self.module_info)
+            })
+            .transpose()?;
+        let implementing_for_src = self.impl_trait.implementing_for.span.as_str().to_string();
+        let implementing_for = render_type_anchor(
+            render_plan
+                .engines
+                .te()
+                .get(self.impl_trait.implementing_for.type_id),
+            render_plan,
+            &self.module_info,
+        )
+        .unwrap_or_else(|_| {
+            box_html! {
+                : implementing_for_src;
+            }
+        });
+        let anchor_id = self.anchor_id();
+        let is_inherent = self.is_inherent();
 
-        let impl_for = box_html! {
-                div(id=format!("impl-{}", trait_name.suffix.as_str()), class="impl has-srclink") {
-                a(href=format!("{IDENTITY}impl-{}", trait_name.suffix.as_str()), class="anchor");
+        Ok(box_html! {
+            div(id=&anchor_id, class="impl has-srclink") {
+                a(href=format!("{IDENTITY}{anchor_id}"), class="anchor");
                 h3(class="code-header in-band") {
                     : "impl ";
-                    : trait_name.suffix.as_str(); // TODO: add links
-                    : " for ";
-                    : implementing_for.span.as_str();
+                    @ if !is_inherent {
+                        @ if let Some(href) = trait_href {
+                            a(class="trait", href=href) {
+                                : trait_name;
+                            }
+                        } else {
+                            : trait_name;
+                        }
+                        : " for ";
+                    }
+                    : implementing_for;
                 }
             }
         }
-        .into_string()?;
+        .into_string()?)
+    }
+}
+impl Renderable for DocImplTrait {
+    fn render(self, render_plan: RenderPlan) -> Result<Box<dyn RenderBox>> {
+        let impl_for = self.render_header(&render_plan)?;
+
+        let mut rendered_items = Vec::new();
+        // Constants of `impl` blocks aren't documented yet.
+        for item in self
+            .impl_trait
+            .items
+            .into_iter()
+            .filter(|item| matches!(item, TyTraitItem::Fn(_)))
+        {
+            rendered_items.push(item.render(render_plan.clone())?)
+        }
 
         Ok(box_html! {
             // check if the implementation has methods
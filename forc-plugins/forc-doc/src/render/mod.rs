@@ -20,8 +20,10 @@ use sway_types::BaseIdent;
 
 pub mod constant;
 mod index;
+pub mod intra_doc_links;
 pub mod item;
 pub mod link;
+mod search;
 mod sidebar;
 mod title;
 pub mod util;
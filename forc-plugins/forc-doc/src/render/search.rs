@@ -0,0 +1,36 @@
+use crate::{doc::module::ModuleInfo, render::constant::SEARCH_INDEX_FILENAME, ASSETS_DIR_NAME};
+use horrorshow::{box_html, Raw, RenderBox};
+
+/// Renders the search bar of a page, along with the search results, which replace the content of
+/// the page while searching, and the scripts loading the search index.
+pub(crate) fn generate_searchbar(module_info: &ModuleInfo) -> Box<dyn RenderBox> {
+    let root_path = module_info.to_html_shorthand_path_string("");
+    let search_index = module_info.to_html_shorthand_path_string(SEARCH_INDEX_FILENAME);
+    let search_js =
+        module_info.to_html_shorthand_path_string(&format!("{ASSETS_DIR_NAME}/search.js"));
+    box_html! {
+        div(class="sub-container") {
+            nav(class="sub") {
+                form(class="search-form") {
+                    div(class="search-container") {
+                        input(
+                            id="search-input",
+                            class="search-input",
+                            name="search",
+                            autocomplete="off",
+                            spellcheck="false",
+                            placeholder="Click or press 'S' to search, 'Esc' to exit...",
+                            type="search"
+                        );
+                    }
+                }
+            }
+        }
+        section(id="search", class="search-results") {}
+        script {
+            : Raw(format!("var ROOT_PATH = \"{root_path}\";"));
+        }
+        script(src=search_index);
+        script(src=search_js);
+    }
+}
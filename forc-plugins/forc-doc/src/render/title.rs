@@ -18,6 +18,9 @@ pub(crate) enum BlockTitle {
     Fields,
     Variants,
     RequiredMethods,
+    Implementations,
+    TraitImplementations,
+    Implementors,
 }
 impl BlockTitle {
     pub(crate) fn as_str(&self) -> &str {
@@ -33,6 +36,9 @@ impl BlockTitle {
             Self::Fields => "Fields",
             Self::Variants => "Variants",
             Self::RequiredMethods => "Required Methods",
+            Self::Implementations => "Implementations",
+            Self::TraitImplementations => "Trait Implementations",
+            Self::Implementors => "Implementors",
         }
     }
     pub(crate) fn item_title_str(&self) -> &str {
@@ -48,6 +54,9 @@ impl BlockTitle {
             Self::Fields => "Fields",
             Self::Variants => "Variants",
             Self::RequiredMethods => "Required Methods",
+            Self::Implementations => "Implementations",
+            Self::TraitImplementations => "Trait Implementations",
+            Self::Implementors => "Implementors",
         }
     }
     pub(crate) fn class_title_str(&self) -> &str {
@@ -1,5 +1,5 @@
-use crate::render::util::format::constant::*;
-use comrak::{markdown_to_html, ComrakOptions};
+use crate::render::{intra_doc_links::INTRA_DOC_LINK_HREF, util::format::constant::*};
+use comrak::{format_html, parse_document_with_broken_link_callback, Arena, ComrakOptions};
 use std::fmt::Write;
 use sway_core::transform::{AttributeKind, AttributesMap};
 use sway_lsp::utils::markdown::format_docs;
@@ -11,18 +11,7 @@ pub(crate) trait DocStrings {
 /// Creates an HTML String from an [AttributesMap]
 impl DocStrings for AttributesMap {
     fn to_html_string(&self) -> String {
-        let docs = self.to_raw_string();
-
-        let mut options = ComrakOptions::default();
-        options.render.hardbreaks = true;
-        options.extension.strikethrough = true;
-        options.extension.table = true;
-        options.extension.autolink = true;
-        options.extension.superscript = true;
-        options.extension.footnotes = true;
-        options.parse.smart = true;
-        options.parse.default_info_string = Some(SWAY_FILEINE.into());
-        markdown_to_html(&format_docs(&docs), &options)
+        markdown_to_html(&format_docs(&self.to_raw_string()))
     }
     fn to_raw_string(&self) -> String {
         let attributes = self.get(&AttributeKind::DocComment);
@@ -39,6 +28,39 @@ impl DocStrings for AttributesMap {
     }
 }
 
+/// Render the markdown of doc comments to HTML.
+///
+/// The links to items by their path, e.g. [`Vec`], have no destination in markdown: they are
+/// rendered as links to [INTRA_DOC_LINK_HREF], to be resolved once the pages are rendered.
+pub(crate) fn markdown_to_html(markdown: &str) -> String {
+    let mut options = ComrakOptions::default();
+    options.render.hardbreaks = true;
+    options.extension.strikethrough = true;
+    options.extension.table = true;
+    options.extension.autolink = true;
+    options.extension.superscript = true;
+    options.extension.footnotes = true;
+    options.parse.smart = true;
+    options.parse.default_info_string = Some(SWAY_FILEINE.into());
+
+    // Only the labels of links found in the text are given to the callback, so links in code
+    // aren't marked.
+    let mut mark_intra_doc_link = |label: &[u8]| {
+        (label.len() > 2 && label.starts_with(b"`") && label.ends_with(b"`"))
+            .then(|| (INTRA_DOC_LINK_HREF.as_bytes().to_vec(), vec![]))
+    };
+    let arena = Arena::new();
+    let root = parse_document_with_broken_link_callback(
+        &arena,
+        markdown,
+        &options,
+        Some(&mut mark_intra_doc_link),
+    );
+    let mut html = vec![];
+    format_html(root, &options, &mut html).expect("writing to a Vec never fails");
+    String::from_utf8(html).expect("comrak renders valid UTF-8")
+}
+
 /// Create a docstring preview from raw html attributes.
 ///
 /// Returns `None` if there are no attributes.
@@ -0,0 +1,94 @@
+//! The search index of the documentation, covering the documented package and its dependencies.
+use crate::{
+    doc::{Document, Documentation},
+    render::{constant::SEARCH_INDEX_FILENAME, util::format::docstring::create_preview},
+};
+use anyhow::Result;
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// The items of each documented package, by package name.
+pub(crate) type SearchIndex = BTreeMap<String, Vec<SearchItem>>;
+
+/// A documented item, as found by the search bar.
+#[derive(Debug, Serialize)]
+pub(crate) struct SearchItem {
+    name: String,
+    /// The full path of the item, e.g. `std::vec::Vec`.
+    path: String,
+    /// The kind of the item, e.g. `struct`.
+    kind: &'static str,
+    /// The path of the item's page, relative to the root of the documentation.
+    html_filename: String,
+    /// The summary of the item's documentation, as plain text.
+    preview: String,
+}
+impl SearchItem {
+    fn from_doc(doc: &Document) -> Self {
+        let name = doc.item_header.item_name.as_str().to_owned();
+        let module_prefixes = &doc.module_info.module_prefixes;
+        Self {
+            path: format!("{}::{name}", module_prefixes.join("::")),
+            kind: doc.item_body.ty_decl.doc_name(),
+            html_filename: format!("{}/{}", module_prefixes.join("/"), doc.html_filename()),
+            preview: create_preview(doc.raw_attributes.clone())
+                .map(|preview| html_to_text(&preview))
+                .unwrap_or_default(),
+            name,
+        }
+    }
+}
+
+/// Collect the [SearchItem]s of the [Documentation] of a package.
+pub(crate) fn search_items(raw_docs: &Documentation) -> Vec<SearchItem> {
+    raw_docs.0.iter().map(SearchItem::from_doc).collect()
+}
+
+/// Write the [SearchIndex] to the root of the documentation, as a script defining `SEARCH_INDEX`
+/// so that it can be loaded by pages opened from the file system.
+pub(crate) fn write_search_index(doc_path: &Path, search_index: &SearchIndex) -> Result<()> {
+    let json = serde_json::to_string(search_index)?;
+    fs::write(
+        doc_path.join(SEARCH_INDEX_FILENAME),
+        format!("var SEARCH_INDEX = {json};\n"),
+    )?;
+
+    Ok(())
+}
+
+/// Strip the tags of some HTML rendered from markdown, collapse its whitespace and unescape it.
+fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::html_to_text;
+
+    #[test]
+    fn test_html_to_text() {
+        let html =
+            "<p>Returns a <code>Vec&lt;T&gt;</code> with\nthe given <em>capacity</em>.</p>\n";
+        assert_eq!(
+            "Returns a Vec<T> with the given capacity.",
+            html_to_text(html)
+        );
+    }
+}
@@ -0,0 +1,146 @@
+// Searches the items of the `SEARCH_INDEX`, defined by the `search-index.js` file at the root of the
+// documentation, from the search bar of a page. `ROOT_PATH` is the path from the page to the
+// root of the documentation.
+(function () {
+  "use strict";
+
+  const MAX_RESULTS = 50;
+
+  // Lower ranks are better matches, `null` if the item doesn't match.
+  function rank(item, query) {
+    const name = item.name.toLowerCase();
+    if (name === query) {
+      return 0;
+    }
+    if (name.startsWith(query)) {
+      return 1;
+    }
+    if (name.includes(query)) {
+      return 2;
+    }
+    if (item.path.toLowerCase().includes(query)) {
+      return 3;
+    }
+    return null;
+  }
+
+  function search(query) {
+    const results = [];
+    for (const items of Object.values(window.SEARCH_INDEX || {})) {
+      for (const item of items) {
+        const itemRank = rank(item, query);
+        if (itemRank !== null) {
+          results.push({ item, rank: itemRank });
+        }
+      }
+    }
+    results.sort(
+      (a, b) =>
+        a.rank - b.rank ||
+        a.item.name.length - b.item.name.length ||
+        a.item.path.localeCompare(b.item.path)
+    );
+    return results.slice(0, MAX_RESULTS).map((result) => result.item);
+  }
+
+  function renderResult(item) {
+    const link = document.createElement("a");
+    link.href = ROOT_PATH + item.html_filename;
+    const row = document.createElement("div");
+    const name = document.createElement("div");
+    name.className = "result-name";
+    const kind = document.createElement("span");
+    kind.className = "grey";
+    kind.textContent = item.kind + " ";
+    const path = document.createElement("span");
+    path.className = item.kind;
+    path.textContent = item.path;
+    name.append(kind, path);
+    const desc = document.createElement("div");
+    desc.className = "desc";
+    const preview = document.createElement("span");
+    preview.textContent = item.preview;
+    desc.append(preview);
+    row.append(name, desc);
+    link.append(row);
+    return link;
+  }
+
+  window.addEventListener("DOMContentLoaded", () => {
+    const input = document.getElementById("search-input");
+    const results = document.getElementById("search");
+    const content = document.getElementById("main-content");
+    if (!input || !results || !content) {
+      return;
+    }
+
+    function showResults() {
+      const query = input.value.trim().toLowerCase();
+      results.replaceChildren();
+      if (query === "") {
+        results.classList.remove("active");
+        content.classList.remove("hidden");
+        return;
+      }
+      const items = search(query);
+      const title = document.createElement("h1");
+      title.className = "search-results-title";
+      title.textContent = items.length
+        ? `Results for ${input.value.trim()}`
+        : `No results for ${input.value.trim()}`;
+      results.append(title, ...items.map(renderResult));
+      results.classList.add("active");
+      content.classList.add("hidden");
+    }
+
+    function clearSearch() {
+      input.value = "";
+      showResults();
+      input.blur();
+    }
+
+    function resultLinks() {
+      return Array.from(results.querySelectorAll("a"));
+    }
+
+    input.addEventListener("input", showResults);
+    input.form.addEventListener("submit", (event) => {
+      event.preventDefault();
+      const first = resultLinks()[0];
+      if (first) {
+        window.location.href = first.href;
+      }
+    });
+
+    document.addEventListener("keydown", (event) => {
+      if (event.ctrlKey || event.altKey || event.metaKey) {
+        return;
+      }
+      const links = resultLinks();
+      const focused = links.indexOf(document.activeElement);
+      if (event.key === "Escape") {
+        clearSearch();
+      } else if (document.activeElement !== input && focused === -1) {
+        // Focus the search bar with `S` or `/`, unless some other input has the focus.
+        if ((event.key === "s" || event.key === "S" || event.key === "/") &&
+            !["INPUT", "TEXTAREA"].includes(document.activeElement.tagName)) {
+          event.preventDefault();
+          input.focus();
+        }
+      } else if (event.key === "ArrowDown") {
+        event.preventDefault();
+        const next = links[focused + 1];
+        if (next) {
+          next.focus();
+        }
+      } else if (event.key === "ArrowUp" && focused !== -1) {
+        event.preventDefault();
+        if (focused === 0) {
+          input.focus();
+        } else {
+          links[focused - 1].focus();
+        }
+      }
+    });
+  });
+})();
@@ -9,8 +9,17 @@ impl Foo for Bar {
     fn foo() {}
 }
 impl Baz for Bar {}
+impl core::ops::Eq for Bar {
+    fn eq(self, other: Self) -> bool {
+        true
+    }
+}
 impl Bar {
     fn foo_bar() {
         Self::foo()
     }
+    /// something about bar
+    fn bar_value(self, offset: u64) -> u64 {
+        offset
+    }
 }
\ No newline at end of file
@@ -0,0 +1,127 @@
+//! Tests building the documentation of the packages in `src/tests/data`.
+use crate::{build, cli::Command, json::JSON_FILENAME};
+use clap::Parser;
+use std::{fs, path::PathBuf, sync::Mutex};
+
+const IMPL_TRAITS_PKG_NAME: &str = "impl_traits";
+
+/// Building the documentation of a package replaces its documentation directory, so the tests
+/// take turns building theirs.
+static BUILD_LOCK: Mutex<()> = Mutex::new(());
+
+fn tests_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("tests")
+}
+
+/// Build the documentation of the package `pkg_name` in `src/tests/data` with the additional
+/// arguments `args`, and read the file at `file_path` within its documentation directory.
+fn build_and_read(pkg_name: &str, args: &[&str], file_path: &str) -> String {
+    let _guard = BUILD_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    let manifest_path = tests_dir().join("data").join(pkg_name);
+    let command = Command::try_parse_from(
+        [
+            "forc-doc",
+            "--manifest-path",
+            manifest_path.to_str().unwrap(),
+            "--offline",
+            "--silent",
+        ]
+        .iter()
+        .chain(args),
+    )
+    .unwrap();
+    let doc_path = build(&command).unwrap();
+    fs::read_to_string(doc_path.join(file_path)).unwrap()
+}
+
+#[test]
+fn json_matches_expected() {
+    let json = build_and_read(
+        IMPL_TRAITS_PKG_NAME,
+        &["--no-deps", "--format", "json"],
+        JSON_FILENAME,
+    );
+    let expected_path = tests_dir()
+        .join("expected")
+        .join(format!("{IMPL_TRAITS_PKG_NAME}.json"));
+    let expected = fs::read_to_string(&expected_path).unwrap();
+    assert_eq!(
+        json.trim_end(),
+        expected.trim_end(),
+        "the JSON documentation of `{IMPL_TRAITS_PKG_NAME}` differs from {}",
+        expected_path.display()
+    );
+}
+
+/// Whether `html` has the header of the `impl` block of `trait_name` for `type_name`, linking to
+/// the trait at `trait_href` and to the type at `type_href`.
+fn has_impl_header(
+    html: &str,
+    trait_name: &str,
+    trait_href: &str,
+    type_name: &str,
+    type_href: &str,
+) -> bool {
+    html.contains(&format!("id=\"impl-{trait_name}-for-{type_name}\""))
+        && html.contains(&format!(
+            "impl <a class=\"trait\" href=\"{trait_href}\">{trait_name}</a> for \
+            <a class=\"struct\" href=\"{type_href}\">{type_name}</a>"
+        ))
+}
+
+#[test]
+fn html_struct_implementations() {
+    let html = build_and_read(IMPL_TRAITS_PKG_NAME, &[], "impl_traits/bar/struct.Bar.html");
+
+    assert!(html.contains("<a href=\"#implementations\">Implementations</a>"));
+    assert!(html.contains("id=\"method.foo_bar\""));
+    assert!(html.contains("id=\"method.bar_value\""));
+
+    assert!(html.contains("<a href=\"#trait-implementations\">Trait Implementations</a>"));
+    let bar_href = "../bar/struct.Bar.html";
+    assert!(has_impl_header(
+        &html,
+        "Foo",
+        "../foo/trait.Foo.html",
+        "Bar",
+        bar_href
+    ));
+    assert!(has_impl_header(
+        &html,
+        "Baz",
+        "../foo/trait.Baz.html",
+        "Bar",
+        bar_href
+    ));
+    assert!(has_impl_header(
+        &html,
+        "Eq",
+        "../../core/ops/trait.Eq.html",
+        "Bar",
+        bar_href
+    ));
+}
+
+#[test]
+fn html_trait_implementors() {
+    let html = build_and_read(IMPL_TRAITS_PKG_NAME, &[], "impl_traits/foo/trait.Foo.html");
+    assert!(html.contains("<a href=\"#implementors\">Implementors</a>"));
+    assert!(has_impl_header(
+        &html,
+        "Foo",
+        "../foo/trait.Foo.html",
+        "Bar",
+        "../bar/struct.Bar.html"
+    ));
+
+    let html = build_and_read(IMPL_TRAITS_PKG_NAME, &[], "core/ops/trait.Eq.html");
+    assert!(has_impl_header(
+        &html,
+        "Eq",
+        "../ops/trait.Eq.html",
+        "Bar",
+        "../../impl_traits/bar/struct.Bar.html"
+    ));
+}
@@ -4,9 +4,11 @@ use anyhow::{bail, Result};
 use clap::Parser;
 use forc_pkg::{manifest::ManifestFile, WorkspaceManifestFile};
 use prettydiff::{basic::DiffOp, diff_lines};
+use serde::Serialize;
 use std::{
     default::Default,
     fs,
+    ops::Range,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -29,9 +31,13 @@ pub struct App {
     /// Run in 'check' mode.
     ///
     /// - Exits with `0` if input is formatted correctly.
-    /// - Exits with `1` and prints a diff if formatting is required.
+    /// - Exits with `1` and prints a unified diff of each file if formatting is required.
     #[clap(short, long)]
     pub check: bool,
+    /// Print the files which were formatted as JSON, or in 'check' mode the files which require
+    /// formatting along with their diffs, instead of printing the diffs.
+    #[clap(long)]
+    pub json: bool,
     /// Path to the project, if not specified, current working directory will be used.
     #[clap(short, long)]
     pub path: Option<String>,
@@ -40,6 +46,18 @@ pub struct App {
     pub file: Option<String>,
 }
 
+/// The number of unchanged lines shown around the changes of a diff.
+const DIFF_CONTEXT_LINES: usize = 3;
+
+/// A file which was formatted, or requires formatting in 'check' mode.
+#[derive(Debug, Serialize)]
+pub struct ChangedFile {
+    pub path: PathBuf,
+    /// The unified diff of the changes, in 'check' mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
 fn main() {
     init_tracing_subscriber(Default::default());
     if let Err(err) = run() {
@@ -56,6 +74,7 @@ fn run() -> Result<()> {
         None => std::env::current_dir()?,
     };
 
+    let mut changed_files = vec![];
     let mut formatter = Formatter::from_dir(&dir)?;
     if let Some(f) = app.file.as_ref() {
         let file_path = &PathBuf::from(f);
@@ -65,28 +84,40 @@ fn run() -> Result<()> {
         let manifest_file = find_parent_manifest_dir(file_path)
             .map(|path| path.join(constants::MANIFEST_FILE_NAME));
 
-        if is_sway_file(file_path) {
-            format_file(&app, file_path.to_path_buf(), manifest_file, &mut formatter)?;
-            return Ok(());
-        }
-
-        bail!(
-            "Provided file '{}' is not a valid Sway file",
-            file_path.display()
-        );
-    };
-
-    let manifest_file = forc_pkg::manifest::ManifestFile::from_dir(&dir)?;
-
-    match manifest_file {
-        ManifestFile::Workspace(ws) => {
-            format_workspace_at_dir(&app, &ws, &dir)?;
+        if !is_sway_file(file_path) {
+            bail!(
+                "Provided file '{}' is not a valid Sway file",
+                file_path.display()
+            );
         }
-        ManifestFile::Package(_) => {
-            format_pkg_at_dir(&app, &dir, &mut formatter)?;
+        format_file(
+            &app,
+            file_path.to_path_buf(),
+            manifest_file,
+            &mut formatter,
+            &mut changed_files,
+        )?;
+    } else {
+        let manifest_file = forc_pkg::manifest::ManifestFile::from_dir(&dir)?;
+
+        match manifest_file {
+            ManifestFile::Workspace(ws) => {
+                format_workspace_at_dir(&app, &ws, &dir, &mut changed_files)?;
+            }
+            ManifestFile::Package(_) => {
+                format_pkg_at_dir(&app, &dir, &mut formatter, &mut changed_files)?;
+            }
         }
     }
 
+    if app.json {
+        println!("{}", serde_json::to_string_pretty(&changed_files)?);
+    }
+    if app.check && !changed_files.is_empty() {
+        // One or more files are not formatted, exit with error
+        bail!("Files contain formatting violations.");
+    }
+
     Ok(())
 }
 
@@ -113,20 +144,16 @@ fn get_sway_dirs(workspace_dir: PathBuf) -> Vec<PathBuf> {
     dirs_to_format
 }
 
-/// Format a file, given its path.
-/// Returns:
-/// - Ok(true) if executed successfully and formatted,
-/// - Ok(false) if executed successfully and not formatted,
-/// - Err if it fails to execute at all.
+/// Format a file, given its path, adding it to `changed_files` if it was or would be edited.
 fn format_file(
     app: &App,
     file: PathBuf,
     manifest_file: Option<PathBuf>,
     formatter: &mut Formatter,
-) -> Result<bool> {
+    changed_files: &mut Vec<ChangedFile>,
+) -> Result<()> {
     let file = file.canonicalize()?;
     if let Ok(file_content) = fs::read_to_string(&file) {
-        let mut edited = false;
         let file_content: Arc<str> = Arc::from(file_content);
         let build_config = manifest_file.map(|f| {
             BuildConfig::root_from_file_name_and_manifest_path(
@@ -137,17 +164,20 @@ fn format_file(
         });
         match Formatter::format(formatter, file_content.clone(), build_config.as_ref()) {
             Ok(formatted_content) => {
-                if app.check {
-                    if *file_content != formatted_content {
-                        info!("File was edited by formatter: \n{:?}\n", file);
-                        display_file_diff(&file_content, &formatted_content)?;
-                        edited = true;
-                    }
-                } else {
+                if *file_content != formatted_content {
+                    record_changed_file(
+                        app,
+                        &file,
+                        &file_content,
+                        &formatted_content,
+                        changed_files,
+                    );
+                }
+                if !app.check {
                     write_file_formatted(&file, &formatted_content)?;
                 }
 
-                return Ok(edited);
+                return Ok(());
             }
             Err(err) => {
                 // there could still be Sway files that are not part of the build
@@ -163,8 +193,12 @@ fn format_file(
 }
 
 /// Format the workspace at the given directory.
-fn format_workspace_at_dir(app: &App, workspace: &WorkspaceManifestFile, dir: &Path) -> Result<()> {
-    let mut contains_edits = false;
+fn format_workspace_at_dir(
+    app: &App,
+    workspace: &WorkspaceManifestFile,
+    dir: &Path,
+    changed_files: &mut Vec<ChangedFile>,
+) -> Result<()> {
     let mut formatter = Formatter::from_dir(dir)?;
     let mut members = vec![];
 
@@ -183,6 +217,7 @@ fn format_workspace_at_dir(app: &App, workspace: &WorkspaceManifestFile, dir: &P
                     path,
                     Some(workspace.dir().to_path_buf()),
                     &mut formatter,
+                    changed_files,
                 )?;
             }
         }
@@ -199,121 +234,181 @@ fn format_workspace_at_dir(app: &App, workspace: &WorkspaceManifestFile, dir: &P
             // In order of priority: member > workspace > default.
             formatter = Formatter::from_dir(&sub_dir)?;
         }
-        format_pkg_at_dir(app, &sub_dir, &mut formatter)?;
+        format_pkg_at_dir(app, &sub_dir, &mut formatter, changed_files)?;
     }
 
     let manifest_file = dir.join(constants::MANIFEST_FILE_NAME);
 
     // Finally, format the root manifest using taplo formatter
-    contains_edits |= format_manifest(app, manifest_file)?;
-
-    if app.check && contains_edits {
-        // One or more files are not formatted, exit with error
-        bail!("Files contain formatting violations.");
-    }
-
-    Ok(())
+    format_manifest(app, manifest_file, changed_files)
 }
 
-/// Format the given manifest at a path.
-/// Returns:
-/// - Ok(true) if executed successfully and formatted,
-/// - Ok(false) if executed successfully and not formatted,
-/// - Err if it fails to execute at all.
-fn format_manifest(app: &App, manifest_file: PathBuf) -> Result<bool> {
+/// Format the given manifest at a path, adding it to `changed_files` if it was or would be edited.
+fn format_manifest(
+    app: &App,
+    manifest_file: PathBuf,
+    changed_files: &mut Vec<ChangedFile>,
+) -> Result<()> {
     if let Ok(manifest_content) = fs::read_to_string(&manifest_file) {
-        let mut edited = false;
         let taplo_alphabetize = taplo_fmt::Options {
             reorder_keys: true,
             ..Default::default()
         };
         let formatted_content = taplo_fmt::format(&manifest_content, taplo_alphabetize);
-        if !app.check {
-            write_file_formatted(&manifest_file, &formatted_content)?;
-        } else if formatted_content != manifest_content {
-            edited = true;
-            error!(
-                "Improperly formatted manifest file: {}",
-                manifest_file.display()
+        if formatted_content != manifest_content {
+            record_changed_file(
+                app,
+                &manifest_file,
+                &manifest_content,
+                &formatted_content,
+                changed_files,
             );
-            display_file_diff(&manifest_content, &formatted_content)?;
-        } else {
+        } else if app.check && !app.json {
             info!(
                 "Manifest Forc.toml formatted correctly: {}",
                 manifest_file.display()
             )
         }
+        if !app.check {
+            write_file_formatted(&manifest_file, &formatted_content)?;
+        }
 
-        return Ok(edited);
+        return Ok(());
     };
 
     bail!("failed to format manifest: {:?}", manifest_file)
 }
 
 /// Format the package at the given directory.
-fn format_pkg_at_dir(app: &App, dir: &Path, formatter: &mut Formatter) -> Result<()> {
+fn format_pkg_at_dir(
+    app: &App,
+    dir: &Path,
+    formatter: &mut Formatter,
+    changed_files: &mut Vec<ChangedFile>,
+) -> Result<()> {
     match find_parent_manifest_dir(dir) {
         Some(path) => {
             let manifest_path = path.clone();
             let manifest_file = manifest_path.join(constants::MANIFEST_FILE_NAME);
             let files = get_sway_files(path);
-            let mut contains_edits = false;
 
             for file in files {
-                contains_edits |= format_file(app, file, Some(manifest_file.clone()), formatter)?;
+                format_file(
+                    app,
+                    file,
+                    Some(manifest_file.clone()),
+                    formatter,
+                    changed_files,
+                )?;
             }
             // format manifest using taplo formatter
-            contains_edits |= format_manifest(app, manifest_file)?;
-
-            if app.check && contains_edits {
-                // One or more files are not formatted, exit with error
-                bail!("Files contain formatting violations.");
-            }
-
-            Ok(())
+            format_manifest(app, manifest_file, changed_files)
         }
         _ => bail!("Manifest file does not exist"),
     }
 }
 
-fn display_file_diff(file_content: &str, formatted_content: &str) -> Result<()> {
+/// Add a file whose content differs from its formatted content to `changed_files`, printing its
+/// diff in 'check' mode unless the changed files are printed as JSON.
+fn record_changed_file(
+    app: &App,
+    path: &Path,
+    file_content: &str,
+    formatted_content: &str,
+    changed_files: &mut Vec<ChangedFile>,
+) {
+    let diff = app
+        .check
+        .then(|| unified_diff(path, file_content, formatted_content));
+    if let (Some(diff), false) = (&diff, app.json) {
+        display_file_diff(diff);
+    }
+    changed_files.push(ChangedFile {
+        path: path.to_path_buf(),
+        diff,
+    });
+}
+
+/// Build the unified diff between the content of a file and its formatted content.
+fn unified_diff(path: &Path, file_content: &str, formatted_content: &str) -> String {
     let changeset = diff_lines(file_content, formatted_content);
-    let mut count_of_updates = 0;
+    // Each line of the diff, prefixed by its kind of change.
+    let mut lines: Vec<(char, &str)> = vec![];
     for diff in changeset.diff() {
-        // max 100 updates
-        if count_of_updates >= 100 {
-            break;
-        }
         match diff {
-            DiffOp::Equal(old) => {
-                for o in old {
-                    info!("{}", o)
-                }
-            }
-            DiffOp::Insert(new) => {
-                count_of_updates += 1;
-                for n in new {
-                    println_green(&format!("+{n}"));
-                }
-            }
-            DiffOp::Remove(old) => {
-                count_of_updates += 1;
-                for o in old {
-                    println_red(&format!("-{o}"));
-                }
-            }
+            DiffOp::Equal(old) => lines.extend(old.iter().map(|line| (' ', *line))),
+            DiffOp::Insert(new) => lines.extend(new.iter().map(|line| ('+', *line))),
+            DiffOp::Remove(old) => lines.extend(old.iter().map(|line| ('-', *line))),
             DiffOp::Replace(old, new) => {
-                count_of_updates += 1;
-                for o in old {
-                    println_red(&format!("-{o}"));
-                }
-                for n in new {
-                    println_green(&format!("+{n}"));
-                }
+                lines.extend(old.iter().map(|line| ('-', *line)));
+                lines.extend(new.iter().map(|line| ('+', *line)));
             }
         }
     }
-    Ok(())
+
+    // Group the changes which are close enough for their context lines to overlap into hunks.
+    let mut hunks: Vec<Range<usize>> = vec![];
+    for (ix, _) in lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (kind, _))| *kind != ' ')
+    {
+        let start = ix.saturating_sub(DIFF_CONTEXT_LINES);
+        let end = (ix + 1 + DIFF_CONTEXT_LINES).min(lines.len());
+        match hunks.last_mut() {
+            Some(hunk) if start <= hunk.end => hunk.end = end,
+            _ => hunks.push(start..end),
+        }
+    }
+
+    let mut diff = format!("--- {}\n+++ {}\n", path.display(), path.display());
+    let (mut old_line, mut new_line) = (1, 1);
+    let mut last_end = 0;
+    for hunk in hunks {
+        for (kind, _) in &lines[last_end..hunk.start] {
+            old_line += usize::from(*kind != '+');
+            new_line += usize::from(*kind != '-');
+        }
+        let hunk_lines = &lines[hunk.clone()];
+        let old_count = hunk_lines.iter().filter(|(kind, _)| *kind != '+').count();
+        let new_count = hunk_lines.iter().filter(|(kind, _)| *kind != '-').count();
+        // An empty range starts at the line before it.
+        let old_start = if old_count == 0 {
+            old_line - 1
+        } else {
+            old_line
+        };
+        let new_start = if new_count == 0 {
+            new_line - 1
+        } else {
+            new_line
+        };
+        diff.push_str(&format!(
+            "@@ -{old_start},{old_count} +{new_start},{new_count} @@\n"
+        ));
+        for (kind, line) in hunk_lines {
+            diff.push_str(&format!("{kind}{line}\n"));
+        }
+        old_line += old_count;
+        new_line += new_count;
+        last_end = hunk.end;
+    }
+    diff
+}
+
+fn display_file_diff(diff: &str) {
+    for (ix, line) in diff.lines().enumerate() {
+        // Only the first two lines are file headers, a removed `--` line also starts with `---`.
+        if ix < 2 || line.starts_with("@@") {
+            println!("{line}");
+        } else if line.starts_with('+') {
+            println_green(line);
+        } else if line.starts_with('-') {
+            println_red(line);
+        } else {
+            println!("{line}");
+        }
+    }
 }
 
 fn write_file_formatted(file: &Path, formatted_content: &str) -> Result<()> {
@@ -324,8 +419,8 @@ fn write_file_formatted(file: &Path, formatted_content: &str) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::taplo_fmt;
-    use std::default::Default;
+    use super::{taplo_fmt, unified_diff};
+    use std::{default::Default, path::Path};
 
     #[test]
     fn test_forc_indentation() {
@@ -408,4 +503,107 @@ core = { git = "https://github.com/FuelLabs/sway-lib-core", version = "v0.0.1" }
         let formatted_content = taplo_fmt::format(disordered_forc_manifest, taplo_alphabetize);
         assert_eq!(formatted_content, correct_forc_manifest);
     }
+
+    #[test]
+    fn test_unified_diff_single_change() {
+        let old = "l1\nl2\nl3\nl4\nl5\nl6\nl7\nl8\nl9\n";
+        let new = "l1\nl2\nl3\nl4\nL5\nl6\nl7\nl8\nl9\n";
+        let expected = "\
+--- main.sw
++++ main.sw
+@@ -2,7 +2,7 @@
+ l2
+ l3
+ l4
+-l5
++L5
+ l6
+ l7
+ l8
+";
+        assert_eq!(unified_diff(Path::new("main.sw"), old, new), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_overlapping_context() {
+        let old = "l1\nl2\nl3\nl4\nl5\nl6\nl7\nl8\nl9\nl10\nl11\n";
+        let new = "l1\nl2\nL3\nl4\nl5\nl6\nl7\nL8\nl9\nl10\nl11\n";
+        let expected = "\
+--- main.sw
++++ main.sw
+@@ -1,11 +1,11 @@
+ l1
+ l2
+-l3
++L3
+ l4
+ l5
+ l6
+ l7
+-l8
++L8
+ l9
+ l10
+ l11
+";
+        assert_eq!(unified_diff(Path::new("main.sw"), old, new), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_insertion() {
+        let old = "l1\nl2\nl3\nl4\nl5\nl6\nl7\nl8\n";
+        let new = "l1\nl2\nl3\nl4\nnew\nl5\nl6\nl7\nl8\n";
+        let expected = "\
+--- main.sw
++++ main.sw
+@@ -2,6 +2,7 @@
+ l2
+ l3
+ l4
++new
+ l5
+ l6
+ l7
+";
+        assert_eq!(unified_diff(Path::new("main.sw"), old, new), expected);
+
+        // The empty range of the old file starts at the line before the insertion.
+        let expected = "\
+--- main.sw
++++ main.sw
+@@ -0,0 +1,2 @@
++l1
++l2
+";
+        assert_eq!(unified_diff(Path::new("main.sw"), "", "l1\nl2\n"), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_deletion() {
+        let old = "l1\nl2\nl3\nl4\nl5\nl6\nl7\nl8\nl9\n";
+        let new = "l1\nl2\nl3\nl4\nl6\nl7\nl8\nl9\n";
+        let expected = "\
+--- main.sw
++++ main.sw
+@@ -2,7 +2,6 @@
+ l2
+ l3
+ l4
+-l5
+ l6
+ l7
+ l8
+";
+        assert_eq!(unified_diff(Path::new("main.sw"), old, new), expected);
+
+        // The empty range of the new file starts at the line before the deletion.
+        let expected = "\
+--- main.sw
++++ main.sw
+@@ -1,2 +0,0 @@
+-l1
+-l2
+";
+        assert_eq!(unified_diff(Path::new("main.sw"), "l1\nl2\n", ""), expected);
+    }
 }
@@ -0,0 +1,386 @@
+//! The types and values of test parameters and logged values, described by the JSON ABI.
+//!
+//! Values are encoded and decoded using the same memory layout the compiler uses, e.g. for the
+//! arguments of a script's `main()` or the data of a `logd` instruction.
+
+use anyhow::{anyhow, bail};
+use forc_pkg::program_abi::{TypeApplication, TypeDeclaration};
+use std::{collections::HashMap, fmt};
+
+/// The type of a test parameter or logged value, resolved from the JSON ABI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ParamType {
+    Unit,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    B256,
+    Str(usize),
+    Array(Box<ParamType>, usize),
+    Tuple(Vec<ParamType>),
+    Struct {
+        name: String,
+        fields: Vec<(String, ParamType)>,
+    },
+    Enum {
+        name: String,
+        variants: Vec<(String, ParamType)>,
+    },
+}
+
+/// A value of a test parameter or a logged value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Value {
+    Unit,
+    Bool(bool),
+    Uint(u64),
+    B256([u8; 32]),
+    Str(String),
+    Array(Vec<Value>),
+    Tuple(Vec<Value>),
+    Struct(Vec<Value>),
+    Enum(usize, Box<Value>),
+}
+
+impl ParamType {
+    /// Resolve the type of `application`, where `generics` maps the type ids of any generic type
+    /// parameters in scope to their concrete types.
+    pub(crate) fn resolve(
+        types: &HashMap<usize, &TypeDeclaration>,
+        application: &TypeApplication,
+        generics: &HashMap<usize, ParamType>,
+    ) -> anyhow::Result<Self> {
+        let decl = types
+            .get(&application.type_id)
+            .ok_or_else(|| anyhow!("missing type declaration {}", application.type_id))?;
+        let type_field = decl.type_field.as_str();
+        if type_field.starts_with("generic ") {
+            return generics
+                .get(&decl.type_id)
+                .cloned()
+                .ok_or_else(|| anyhow!("unresolved generic parameter `{type_field}`"));
+        }
+
+        // Bind the type arguments of this application to the declaration's type parameters.
+        let mut decl_generics = generics.clone();
+        if let (Some(type_params), Some(type_args)) =
+            (&decl.type_parameters, &application.type_arguments)
+        {
+            for (type_param, type_arg) in type_params.iter().zip(type_args) {
+                let ty = Self::resolve(types, type_arg, generics)?;
+                decl_generics.insert(*type_param, ty);
+            }
+        }
+        let components = || -> anyhow::Result<Vec<(String, ParamType)>> {
+            decl.components
+                .iter()
+                .flatten()
+                .map(|component| {
+                    let ty = Self::resolve(types, component, &decl_generics)?;
+                    Ok((component.name.clone(), ty))
+                })
+                .collect()
+        };
+
+        let ty = match type_field {
+            "()" => ParamType::Unit,
+            "bool" => ParamType::Bool,
+            "u8" => ParamType::U8,
+            "u16" => ParamType::U16,
+            "u32" => ParamType::U32,
+            "u64" => ParamType::U64,
+            "b256" => ParamType::B256,
+            _ if type_field.starts_with("str[") => ParamType::Str(parse_len(type_field)?),
+            _ if type_field.starts_with('[') => {
+                let (_, elem_ty) = components()?
+                    .pop()
+                    .ok_or_else(|| anyhow!("missing element type for `{type_field}`"))?;
+                ParamType::Array(Box::new(elem_ty), parse_len(type_field)?)
+            }
+            _ if type_field.starts_with('(') => {
+                ParamType::Tuple(components()?.into_iter().map(|(_, ty)| ty).collect())
+            }
+            _ if type_field.starts_with("struct ") => ParamType::Struct {
+                name: type_name(type_field),
+                fields: components()?,
+            },
+            _ if type_field.starts_with("enum ") => {
+                let variants = components()?;
+                if variants.is_empty() {
+                    bail!("the empty enum `{type_field}` has no values")
+                }
+                ParamType::Enum {
+                    name: type_name(type_field),
+                    variants,
+                }
+            }
+            _ => bail!("values of type `{type_field}` are not supported"),
+        };
+        Ok(ty)
+    }
+
+    /// The size of a value of this type in bytes.  This matches the compiler's memory layout.
+    fn size(&self) -> usize {
+        match self {
+            ParamType::Unit
+            | ParamType::Bool
+            | ParamType::U8
+            | ParamType::U16
+            | ParamType::U32
+            | ParamType::U64 => 8,
+            ParamType::B256 => 32,
+            ParamType::Str(len) => word_aligned(*len),
+            ParamType::Array(elem_ty, len) => elem_ty.size() * len,
+            ParamType::Tuple(elem_tys) => elem_tys.iter().map(ParamType::size).sum(),
+            ParamType::Struct { fields, .. } => fields.iter().map(|(_, ty)| ty.size()).sum(),
+            ParamType::Enum { variants, .. } => 8 + Self::union_size(variants),
+        }
+    }
+
+    /// The size of the union of enum variants.  Enums with only unit variants are just a tag.
+    fn union_size(variants: &[(String, ParamType)]) -> usize {
+        if variants.iter().all(|(_, ty)| *ty == ParamType::Unit) {
+            0
+        } else {
+            variants.iter().map(|(_, ty)| ty.size()).max().unwrap_or(0)
+        }
+    }
+
+    /// Encode a value of this type, appending it to `bytes`.
+    pub(crate) fn encode(&self, value: &Value, bytes: &mut Vec<u8>) {
+        match (self, value) {
+            (ParamType::Unit, Value::Unit) => bytes.extend([0; 8]),
+            (ParamType::Bool, Value::Bool(b)) => bytes.extend((*b as u64).to_be_bytes()),
+            (_, Value::Uint(n)) => bytes.extend(n.to_be_bytes()),
+            (_, Value::B256(b)) => bytes.extend(b),
+            (ParamType::Str(len), Value::Str(s)) => {
+                bytes.extend(s.as_bytes());
+                bytes.resize(bytes.len() + word_aligned(*len) - s.len(), 0);
+            }
+            (ParamType::Array(elem_ty, _), Value::Array(elems)) => {
+                for elem in elems {
+                    elem_ty.encode(elem, bytes);
+                }
+            }
+            (ParamType::Tuple(elem_tys), Value::Tuple(elems)) => {
+                for (elem_ty, elem) in elem_tys.iter().zip(elems) {
+                    elem_ty.encode(elem, bytes);
+                }
+            }
+            (ParamType::Struct { fields, .. }, Value::Struct(elems)) => {
+                for ((_, field_ty), elem) in fields.iter().zip(elems) {
+                    field_ty.encode(elem, bytes);
+                }
+            }
+            (ParamType::Enum { variants, .. }, Value::Enum(tag, inner)) => {
+                bytes.extend((*tag as u64).to_be_bytes());
+                // Variants are padded on the left to the size of the largest variant.
+                let union_size = Self::union_size(variants);
+                if union_size != 0 {
+                    let variant_ty = &variants[*tag].1;
+                    bytes.resize(bytes.len() + union_size - variant_ty.size(), 0);
+                    variant_ty.encode(inner, bytes);
+                }
+            }
+            _ => unreachable!("value was generated for a different type"),
+        }
+    }
+
+    /// Decode a value of this type from the start of `bytes`, advancing past it.  Returns `None`
+    /// if `bytes` doesn't hold a valid value of this type.
+    pub(crate) fn decode(&self, bytes: &mut &[u8]) -> Option<Value> {
+        let value = match self {
+            ParamType::Unit => {
+                take(bytes, 8)?;
+                Value::Unit
+            }
+            ParamType::Bool => Value::Bool(take_word(bytes)? != 0),
+            ParamType::U8 | ParamType::U16 | ParamType::U32 | ParamType::U64 => {
+                Value::Uint(take_word(bytes)?)
+            }
+            ParamType::B256 => Value::B256(take(bytes, 32)?.try_into().ok()?),
+            ParamType::Str(len) => {
+                let str_bytes = &take(bytes, word_aligned(*len))?[..*len];
+                Value::Str(String::from_utf8(str_bytes.to_vec()).ok()?)
+            }
+            ParamType::Array(elem_ty, len) => Value::Array(
+                (0..*len)
+                    .map(|_| elem_ty.decode(bytes))
+                    .collect::<Option<_>>()?,
+            ),
+            ParamType::Tuple(elem_tys) => Value::Tuple(
+                elem_tys
+                    .iter()
+                    .map(|elem_ty| elem_ty.decode(bytes))
+                    .collect::<Option<_>>()?,
+            ),
+            ParamType::Struct { fields, .. } => Value::Struct(
+                fields
+                    .iter()
+                    .map(|(_, field_ty)| field_ty.decode(bytes))
+                    .collect::<Option<_>>()?,
+            ),
+            ParamType::Enum { variants, .. } => {
+                let tag = usize::try_from(take_word(bytes)?).ok()?;
+                let (_, variant_ty) = variants.get(tag)?;
+                let union_size = Self::union_size(variants);
+                let inner = if union_size == 0 {
+                    Value::Unit
+                } else {
+                    take(bytes, union_size - variant_ty.size())?;
+                    variant_ty.decode(bytes)?
+                };
+                Value::Enum(tag, Box::new(inner))
+            }
+        };
+        Some(value)
+    }
+
+    /// Format a value of this type using Sway syntax.
+    pub(crate) fn display(&self, value: &Value) -> String {
+        DisplayValue(self, value).to_string()
+    }
+}
+
+/// Take the next `len` bytes from the start of `bytes`.
+fn take<'a>(bytes: &mut &'a [u8], len: usize) -> Option<&'a [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+    let (taken, rest) = bytes.split_at(len);
+    *bytes = rest;
+    Some(taken)
+}
+
+/// Take the next word from the start of `bytes`.
+fn take_word(bytes: &mut &[u8]) -> Option<u64> {
+    take(bytes, 8).map(|word| u64::from_be_bytes(word.try_into().unwrap()))
+}
+
+fn word_aligned(len: usize) -> usize {
+    (len + 7) / 8 * 8
+}
+
+/// Parse the length from a `str[<len>]` or `[_; <len>]` type.
+fn parse_len(type_field: &str) -> anyhow::Result<usize> {
+    type_field
+        .trim_end_matches(']')
+        .rsplit(['[', ' '])
+        .next()
+        .and_then(|len| len.parse().ok())
+        .ok_or_else(|| anyhow!("invalid length in type `{type_field}`"))
+}
+
+/// The name of a struct or enum without its keyword or path.
+fn type_name(type_field: &str) -> String {
+    let name = type_field
+        .split_once(' ')
+        .map_or(type_field, |(_, name)| name);
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
+/// Displays a value using Sway syntax.
+pub(crate) struct DisplayValue<'a>(pub(crate) &'a ParamType, pub(crate) &'a Value);
+
+impl fmt::Display for DisplayValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join =
+            |f: &mut fmt::Formatter<'_>, tys: &[&ParamType], values: &[Value]| -> fmt::Result {
+                for (idx, (ty, value)) in tys.iter().zip(values).enumerate() {
+                    if idx != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", DisplayValue(ty, value))?;
+                }
+                Ok(())
+            };
+        match (self.0, self.1) {
+            (_, Value::Unit) => write!(f, "()"),
+            (_, Value::Bool(b)) => write!(f, "{b}"),
+            (_, Value::Uint(n)) => write!(f, "{n}"),
+            (_, Value::B256(bytes)) => {
+                write!(f, "0x")?;
+                bytes.iter().try_for_each(|b| write!(f, "{b:02x}"))
+            }
+            (_, Value::Str(s)) => write!(f, "{s:?}"),
+            (ParamType::Array(elem_ty, _), Value::Array(elems)) => {
+                write!(f, "[")?;
+                join(f, &vec![elem_ty.as_ref(); elems.len()], elems)?;
+                write!(f, "]")
+            }
+            (ParamType::Tuple(elem_tys), Value::Tuple(elems)) => {
+                write!(f, "(")?;
+                join(f, &elem_tys.iter().collect::<Vec<_>>(), elems)?;
+                write!(f, ")")
+            }
+            (ParamType::Struct { name, fields }, Value::Struct(elems)) => {
+                write!(f, "{name} {{ ")?;
+                for (idx, ((field_name, field_ty), elem)) in fields.iter().zip(elems).enumerate() {
+                    if idx != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{field_name}: {}", DisplayValue(field_ty, elem))?;
+                }
+                write!(f, " }}")
+            }
+            (ParamType::Enum { name, variants }, Value::Enum(tag, inner)) => {
+                let (variant_name, variant_ty) = &variants[*tag];
+                write!(f, "{name}::{variant_name}")?;
+                if *variant_ty != ParamType::Unit {
+                    write!(f, "({})", DisplayValue(variant_ty, inner))?;
+                }
+                Ok(())
+            }
+            _ => write!(f, "{:?}", self.1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_encoded_values() {
+        let point_ty = ParamType::Struct {
+            name: "Point".to_string(),
+            fields: vec![
+                ("x".to_string(), ParamType::U64),
+                ("name".to_string(), ParamType::Str(3)),
+            ],
+        };
+        let error_ty = ParamType::Enum {
+            name: "Error".to_string(),
+            variants: vec![
+                ("NotOwner".to_string(), ParamType::Unit),
+                ("Invalid".to_string(), point_ty),
+            ],
+        };
+        let values = [
+            Value::Enum(0, Box::new(Value::Unit)),
+            Value::Enum(
+                1,
+                Box::new(Value::Struct(vec![
+                    Value::Uint(7),
+                    Value::Str("abc".to_string()),
+                ])),
+            ),
+        ];
+        for value in &values {
+            let mut bytes = vec![];
+            error_ty.encode(value, &mut bytes);
+            assert_eq!(bytes.len(), error_ty.size());
+            let mut remaining = &bytes[..];
+            assert_eq!(error_ty.decode(&mut remaining).as_ref(), Some(value));
+            assert!(remaining.is_empty());
+        }
+        assert_eq!(error_ty.display(&values[0]), "Error::NotOwner");
+        assert_eq!(
+            error_ty.display(&values[1]),
+            "Error::Invalid(Point { x: 7, name: \"abc\" })"
+        );
+        assert_eq!(error_ty.decode(&mut &[0u8; 4][..]), None);
+    }
+}
@@ -0,0 +1,123 @@
+//! The state of the simulated chain that tests are run against.
+//!
+//! A `ChainState` may be provided when running tests to configure the state shared by all of
+//! them, which each test may then extend with the chain state declared by its attributes.
+
+use forc_pkg as pkg;
+use fuel_tx as tx;
+use fuel_vm::{
+    self as vm,
+    storage::{ContractsAssetsStorage, InterpreterStorage},
+};
+use std::collections::HashMap;
+
+/// The state of the simulated chain that tests are run against.
+#[derive(Clone, Debug, Default)]
+pub struct ChainState {
+    /// The height of the block the tests are run in.  The block timestamp is derived from it.
+    pub block_height: Option<u32>,
+    /// The address owning the coins spent by the tests, i.e. the `msg_sender` of the contract
+    /// calls they make.  If `None`, the coins are owned by an address derived from a fixed key.
+    pub caller: Option<tx::Address>,
+    /// The coins owned by the caller and spendable by the tests, as `(asset id, amount)` pairs.
+    pub coins: Vec<(tx::AssetId, u64)>,
+    /// The state of the deployed contracts, i.e. the contract under test and its contract
+    /// dependencies, keyed by package name.
+    pub contracts: HashMap<String, ContractState>,
+}
+
+/// The state of a deployed contract, set after its deployment.
+#[derive(Clone, Debug, Default)]
+pub struct ContractState {
+    /// The balance of each asset held by the contract, as `(asset id, amount)` pairs.
+    pub balances: Vec<(tx::AssetId, u64)>,
+    /// The storage slots of the contract to override, as `(key, value)` pairs.
+    pub storage: Vec<(tx::Bytes32, tx::Bytes32)>,
+}
+
+impl ChainState {
+    /// Extend this chain state with the one declared by a test.  The balances and storage
+    /// declared by the test are those of the contract under test, i.e. `root_contract`.
+    pub(crate) fn with_test(&self, test: &pkg::TestChainState, root_contract: &str) -> Self {
+        let mut chain_state = self.clone();
+        if test.block_height.is_some() {
+            chain_state.block_height = test.block_height;
+        }
+        if test.caller.is_some() {
+            chain_state.caller = test.caller;
+        }
+        chain_state.coins.extend(test.coins.iter().cloned());
+        if !test.balances.is_empty() || !test.storage.is_empty() {
+            let contract = chain_state
+                .contracts
+                .entry(root_contract.to_string())
+                .or_default();
+            contract.balances.extend(test.balances.iter().cloned());
+            contract.storage.extend(test.storage.iter().cloned());
+        }
+        chain_state
+    }
+
+    /// The storage of a chain in this state, before any contract is deployed.
+    pub(crate) fn storage(&self) -> vm::storage::MemoryStorage {
+        match self.block_height {
+            Some(block_height) => {
+                vm::storage::MemoryStorage::new(block_height.into(), tx::Address::zeroed())
+            }
+            None => vm::storage::MemoryStorage::default(),
+        }
+    }
+
+    /// Set the balances and storage of a contract which has just been deployed.
+    pub(crate) fn apply_contract_state(
+        &self,
+        storage: &mut vm::storage::MemoryStorage,
+        contract_name: &str,
+        contract_id: &tx::ContractId,
+    ) -> anyhow::Result<()> {
+        let contract = match self.contracts.get(contract_name) {
+            Some(contract) => contract,
+            None => return Ok(()),
+        };
+        for (asset_id, amount) in &contract.balances {
+            storage.merkle_contract_asset_id_balance_insert(contract_id, asset_id, *amount)?;
+        }
+        for (key, value) in &contract.storage {
+            storage.merkle_contract_state_insert(contract_id, key, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extend_with_test_chain_state() {
+        let asset_id = tx::AssetId::from([1; 32]);
+        let chain_state = ChainState {
+            block_height: Some(10),
+            coins: vec![(asset_id, 5)],
+            ..Default::default()
+        };
+        let test = pkg::TestChainState {
+            caller: Some(tx::Address::from([2; 32])),
+            coins: vec![(asset_id, 7)],
+            balances: vec![(asset_id, 100)],
+            ..Default::default()
+        };
+        let extended = chain_state.with_test(&test, "my_contract");
+        assert_eq!(extended.block_height, Some(10));
+        assert_eq!(extended.caller, test.caller);
+        assert_eq!(extended.coins, vec![(asset_id, 5), (asset_id, 7)]);
+        assert_eq!(
+            extended.contracts["my_contract"].balances,
+            vec![(asset_id, 100)]
+        );
+        assert!(chain_state
+            .with_test(&pkg::TestChainState::default(), "my_contract")
+            .contracts
+            .is_empty());
+    }
+}
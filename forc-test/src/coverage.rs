@@ -0,0 +1,246 @@
+//! Line coverage of a package's sources by its tests.
+//!
+//! The instructions executed by each test are recorded by the coverage profiler of the VM, both
+//! within the test bytecode and within the contract under test, and are then mapped back to lines
+//! of the package's source files using the source maps of both.
+
+use crate::TestedPackage;
+use anyhow::Context;
+use fuel_tx as tx;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+use sway_core::source_map::SourceMap;
+
+#[cfg(feature = "coverage")]
+use crate::PROGRAM_START_INST_OFFSET;
+#[cfg(feature = "coverage")]
+use fuel_vm::prelude::Instruction;
+#[cfg(feature = "coverage")]
+pub(crate) use fuel_vm::profiler::CoverageProfilingData;
+
+/// Without the coverage profiler of the VM no instructions are ever recorded.
+#[cfg(not(feature = "coverage"))]
+pub(crate) type CoverageProfilingData = std::convert::Infallible;
+
+/// The instructions executed by a test, as instruction offsets into the bytecode they belong to.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstructionCoverage {
+    /// The instructions of the test bytecode which were executed.
+    pub test: BTreeSet<usize>,
+    /// The instructions of the contract under test which were executed, when testing a contract.
+    pub contract: BTreeSet<usize>,
+}
+
+/// The coverage of all source files of a package.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Coverage {
+    pub files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+/// The coverage of a single source file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FileCoverage {
+    /// The number of tests which executed each line with instructions, keyed by 1-based line
+    /// number.
+    pub lines: BTreeMap<usize, u64>,
+}
+
+impl InstructionCoverage {
+    /// Collect the instructions recorded by the coverage profiler while running the test whose
+    /// entry point is at the given instruction offset.  Instructions of contracts other than the
+    /// contract under test, if any, are left out.
+    #[cfg(feature = "coverage")]
+    pub(crate) fn from_profiler(
+        profiled: &CoverageProfilingData,
+        test_offset: u32,
+        contract_id: Option<tx::ContractId>,
+    ) -> Self {
+        let mut coverage = Self::default();
+        for location in profiled.iter() {
+            let inst_offset = (location.offset() / Instruction::SIZE as u64) as usize;
+            match location.context() {
+                // The patched jump to the test isn't part of the program.
+                None if inst_offset == PROGRAM_START_INST_OFFSET as usize
+                    && test_offset != PROGRAM_START_INST_OFFSET => {}
+                None => {
+                    coverage.test.insert(inst_offset);
+                }
+                Some(context) if Some(context) == contract_id => {
+                    coverage.contract.insert(inst_offset);
+                }
+                Some(_) => {}
+            }
+        }
+        coverage
+    }
+
+    #[cfg(not(feature = "coverage"))]
+    pub(crate) fn from_profiler(
+        profiled: &CoverageProfilingData,
+        _test_offset: u32,
+        _contract_id: Option<tx::ContractId>,
+    ) -> Self {
+        match *profiled {}
+    }
+}
+
+impl Coverage {
+    /// Compute the coverage of the sources of a package by all of its tests.  Sources outside the
+    /// package's directory, such as those of its dependencies, are left out.
+    pub fn from_tested_pkg(tested_pkg: &TestedPackage) -> anyhow::Result<Self> {
+        let pkg_dir = tested_pkg.built.descriptor.manifest_file.dir();
+        let mut sources = HashMap::new();
+        let test_lines = source_lines(tested_pkg.built.source_map(), pkg_dir, &mut sources)?;
+        let contract_lines = match tested_pkg.built.source_map_without_tests() {
+            Some(source_map) => source_lines(source_map, pkg_dir, &mut sources)?,
+            None => HashMap::new(),
+        };
+
+        let mut coverage = Coverage::default();
+        for (path, line) in test_lines.values().chain(contract_lines.values()) {
+            coverage
+                .files
+                .entry(path.clone())
+                .or_default()
+                .lines
+                .insert(*line, 0);
+        }
+        for executed in tested_pkg
+            .tests
+            .iter()
+            .filter_map(|test| test.coverage.as_ref())
+        {
+            let lines = executed
+                .test
+                .iter()
+                .filter_map(|inst_offset| test_lines.get(inst_offset))
+                .chain(
+                    executed
+                        .contract
+                        .iter()
+                        .filter_map(|inst_offset| contract_lines.get(inst_offset)),
+                )
+                .collect::<BTreeSet<_>>();
+            for (path, line) in lines {
+                *coverage
+                    .files
+                    .entry(path.clone())
+                    .or_default()
+                    .lines
+                    .entry(*line)
+                    .or_default() += 1;
+            }
+        }
+        Ok(coverage)
+    }
+
+    /// Render this coverage in the LCOV tracefile format.
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        for (path, file) in &self.files {
+            writeln!(lcov, "TN:").unwrap();
+            writeln!(lcov, "SF:{}", path.display()).unwrap();
+            for (line, count) in &file.lines {
+                writeln!(lcov, "DA:{line},{count}").unwrap();
+            }
+            writeln!(lcov, "LF:{}", file.lines_found()).unwrap();
+            writeln!(lcov, "LH:{}", file.lines_hit()).unwrap();
+            writeln!(lcov, "end_of_record").unwrap();
+        }
+        lcov
+    }
+
+    /// Write this coverage to the given file in the LCOV tracefile format.
+    pub fn write_lcov(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, self.to_lcov())
+            .with_context(|| format!("failed to write coverage {}", path.display()))
+    }
+}
+
+impl FileCoverage {
+    /// The number of lines with instructions.
+    pub fn lines_found(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// The number of lines with instructions which were executed at least once.
+    pub fn lines_hit(&self) -> usize {
+        self.lines.values().filter(|count| **count > 0).count()
+    }
+
+    /// The percentage of lines with instructions which were executed at least once.
+    pub fn percent_hit(&self) -> f64 {
+        match self.lines_found() {
+            0 => 100.0,
+            found => self.lines_hit() as f64 / found as f64 * 100.0,
+        }
+    }
+}
+
+/// The source file and 1-based line number of each instruction of a source map which belongs to a
+/// source file of the package in `pkg_dir`.  The source files which are read are cached in
+/// `sources`.
+fn source_lines(
+    source_map: &SourceMap,
+    pkg_dir: &Path,
+    sources: &mut HashMap<PathBuf, String>,
+) -> anyhow::Result<HashMap<usize, (PathBuf, usize)>> {
+    let mut lines = HashMap::new();
+    for inst_offset in source_map.addrs() {
+        let (path, range) = match source_map.addr_to_span(inst_offset) {
+            Some((path, range)) if path.starts_with(pkg_dir) => (path, range),
+            _ => continue,
+        };
+        if !sources.contains_key(&path) {
+            let source = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read source file {}", path.display()))?;
+            sources.insert(path.clone(), source);
+        }
+        let line = line_number(&sources[&path], range.start);
+        lines.insert(inst_offset, (path, line));
+    }
+    Ok(lines)
+}
+
+/// The 1-based line number of the given byte offset into a source file.
+pub(crate) fn line_number(source: &str, offset: usize) -> usize {
+    let offset = offset.min(source.len());
+    source.as_bytes()[..offset]
+        .iter()
+        .filter(|b| **b == b'\n')
+        .count()
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lcov() {
+        let source = "library;\n\nfn foo() {\n    bar();\n}\n";
+        assert_eq!(line_number(source, 0), 1);
+        assert_eq!(line_number(source, source.find("bar").unwrap()), 4);
+
+        let file = FileCoverage {
+            lines: [(3, 2), (4, 0)].into_iter().collect(),
+        };
+        assert_eq!(file.percent_hit(), 50.0);
+        let coverage = Coverage {
+            files: [(PathBuf::from("/pkg/src/lib.sw"), file)]
+                .into_iter()
+                .collect(),
+        };
+        assert_eq!(
+            coverage.to_lcov(),
+            "TN:\nSF:/pkg/src/lib.sw\nDA:3,2\nDA:4,0\nLF:2\nLH:1\nend_of_record\n"
+        );
+    }
+}
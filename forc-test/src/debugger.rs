@@ -0,0 +1,390 @@
+//! Step-through debugging of a single test.
+//!
+//! The test is executed by single stepping the VM.  The program counter is mapped back to the
+//! sources of the package using its source map, and the stack frames reserved by the functions
+//! being executed are tracked to find the values of their locals.
+//!
+//! The VM can't single step through the instructions of called contracts, so contract calls are
+//! stepped over by running the VM until the instruction following the call.
+
+use crate::{coverage::line_number, logs::LogDecoder, PROGRAM_START_INST_OFFSET};
+use anyhow::{anyhow, bail};
+use fuel_tx as tx;
+use fuel_vm::{self as vm, fuel_asm::RegId, prelude::Instruction};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+use sway_core::source_map::{FunctionLocals, SourceMap};
+
+/// The names of the reserved registers, indexed by register id.
+const RESERVED_REGISTER_NAMES: [&str; 16] = [
+    "zero", "one", "of", "pc", "ssp", "sp", "fp", "hp", "err", "ggas", "cgas", "bal", "is", "ret",
+    "retl", "flag",
+];
+
+/// A line of the sources of a package.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SourceLocation {
+    pub path: PathBuf,
+    /// The 1-based line number.
+    pub line: usize,
+}
+
+/// The reason execution of a debugged test stopped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DebugEvent {
+    /// A single instruction or line was stepped over.
+    Stepped,
+    /// A line with a breakpoint was reached.
+    Breakpoint(SourceLocation),
+    /// The test returned or reverted.
+    Finished,
+}
+
+/// The value of a local variable stored on the stack.
+#[derive(Clone, Debug)]
+pub struct LocalValue {
+    pub name: String,
+    pub address: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// The stack frame reserved by a function for its locals.
+#[derive(Debug)]
+struct Frame {
+    locals: FunctionLocals,
+    /// The address of the start of the frame.
+    base: u64,
+}
+
+/// A test paused between the execution of two instructions.
+///
+/// Contract calls are executed as a single step, as the instructions of called contracts aren't
+/// part of the test bytecode.
+pub struct DebugSession {
+    interpreter: vm::interpreter::Interpreter<vm::storage::MemoryStorage, tx::Script>,
+    state: vm::state::ProgramState,
+    source_map: SourceMap,
+    /// The source location of each instruction of the test bytecode with one.
+    locations: HashMap<usize, SourceLocation>,
+    test_offset: u32,
+    test_file: PathBuf,
+    breakpoints: BTreeSet<SourceLocation>,
+    /// The frames of the functions being executed which have locals, innermost last.
+    frames: Vec<Frame>,
+    log_decoder: LogDecoder,
+}
+
+impl DebugSession {
+    /// Start executing the given test transaction, pausing before its first instruction.
+    pub(crate) fn new(
+        tx: vm::checked_transaction::Checked<tx::Script>,
+        storage: vm::storage::MemoryStorage,
+        source_map: &SourceMap,
+        test_offset: u32,
+        test_file: &Path,
+        log_decoder: LogDecoder,
+    ) -> anyhow::Result<Self> {
+        let mut sources: HashMap<PathBuf, Option<String>> = HashMap::new();
+        let mut locations = HashMap::new();
+        for inst_offset in source_map.addrs() {
+            let (path, range) = match source_map.addr_to_span(inst_offset) {
+                Some(span) => span,
+                None => continue,
+            };
+            // Sources which can no longer be read are left without locations.
+            let source = sources
+                .entry(path.clone())
+                .or_insert_with(|| fs::read_to_string(&path).ok());
+            if let Some(source) = source {
+                let line = line_number(source, range.start);
+                locations.insert(inst_offset, SourceLocation { path, line });
+            }
+        }
+
+        let params = tx::ConsensusParameters::default();
+        let mut interpreter = vm::interpreter::Interpreter::with_storage(
+            storage,
+            params,
+            vm::gas::GasCosts::default(),
+        );
+        interpreter.set_single_stepping(true);
+        let state = *interpreter
+            .transact(tx)
+            .map_err(|err| anyhow!("failed to execute test: {err}"))?
+            .state();
+
+        Ok(Self {
+            interpreter,
+            state,
+            source_map: source_map.clone(),
+            locations,
+            test_offset,
+            test_file: test_file.to_path_buf(),
+            breakpoints: BTreeSet::new(),
+            frames: vec![],
+            log_decoder,
+        })
+    }
+
+    /// Whether the test has returned or reverted.
+    pub fn finished(&self) -> bool {
+        !matches!(
+            self.state,
+            vm::state::ProgramState::RunProgram(vm::state::DebugEval::Breakpoint(_))
+        )
+    }
+
+    /// The state of the program, i.e. how the test ended once it has finished.
+    pub fn state(&self) -> &vm::state::ProgramState {
+        &self.state
+    }
+
+    /// Execute the next instruction.
+    pub fn step_instruction(&mut self) -> anyhow::Result<DebugEvent> {
+        if self.finished() {
+            return Ok(DebugEvent::Finished);
+        }
+
+        // A function's frame starts at the stack pointer from before it is reserved.
+        if let Some(locals) = self
+            .inst_offset()
+            .and_then(|inst_offset| self.source_map.function_locals(inst_offset))
+        {
+            self.frames.push(Frame {
+                locals: locals.clone(),
+                base: self.register(RegId::SP),
+            });
+        }
+
+        // Step over contract calls by breaking on the next instruction of the test instead.
+        let after_call = match self.instruction() {
+            Some(Instruction::CALL(_)) => self
+                .inst_offset()
+                .map(|inst_offset| vm::state::Breakpoint::script(inst_offset as u64 + 1)),
+            _ => None,
+        };
+        if let Some(breakpoint) = after_call {
+            self.interpreter.set_breakpoint(breakpoint);
+            self.interpreter.set_single_stepping(false);
+        }
+        self.state = self
+            .interpreter
+            .resume()
+            .map_err(|err| anyhow!("failed to execute test: {err}"))?;
+        if let Some(breakpoint) = after_call {
+            self.interpreter.remove_breakpoint(&breakpoint);
+            self.interpreter.set_single_stepping(true);
+        }
+
+        // Frames are freed by functions returning to their caller.
+        let sp = self.register(RegId::SP);
+        while self.frames.last().map_or(false, |frame| frame.base >= sp) {
+            self.frames.pop();
+        }
+
+        match self.finished() {
+            true => Ok(DebugEvent::Finished),
+            false => Ok(DebugEvent::Stepped),
+        }
+    }
+
+    /// Execute instructions until one from another source line is reached, stepping into the
+    /// functions which are called.
+    pub fn step_line(&mut self) -> anyhow::Result<DebugEvent> {
+        let start = self.location();
+        loop {
+            if self.step_instruction()? == DebugEvent::Finished {
+                return Ok(DebugEvent::Finished);
+            }
+            match self.location() {
+                Some(location) if Some(&location) != start.as_ref() => {
+                    return Ok(DebugEvent::Stepped)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Execute instructions until a line with a breakpoint is entered, or the test finishes.
+    pub fn continue_execution(&mut self) -> anyhow::Result<DebugEvent> {
+        let mut location = self.location();
+        loop {
+            if self.step_instruction()? == DebugEvent::Finished {
+                return Ok(DebugEvent::Finished);
+            }
+            // Instructions without a location are considered part of the last line executed.
+            if let Some(next) = self.location() {
+                if Some(&next) != location.as_ref() && self.breakpoints.contains(&next) {
+                    return Ok(DebugEvent::Breakpoint(next));
+                }
+                location = Some(next);
+            }
+        }
+    }
+
+    /// Add a breakpoint to a line of a source file, which is the file declaring the test if
+    /// `path` is `None`, or otherwise the file whose path ends with `path`.
+    pub fn add_breakpoint(
+        &mut self,
+        path: Option<&Path>,
+        line: usize,
+    ) -> anyhow::Result<SourceLocation> {
+        let paths = self
+            .locations
+            .values()
+            .filter(|location| match path {
+                Some(path) => location.path.ends_with(path),
+                None => location.path == self.test_file,
+            })
+            .map(|location| &location.path)
+            .collect::<BTreeSet<_>>();
+        if paths.len() > 1 {
+            bail!(
+                "ambiguous path, it matches {}",
+                paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        let location = self
+            .locations
+            .values()
+            .find(|location| paths.contains(&location.path) && location.line == line)
+            .cloned()
+            .ok_or_else(|| {
+                let path = path.unwrap_or(&self.test_file);
+                anyhow!("no code at line {line} of {}", path.display())
+            })?;
+        self.breakpoints.insert(location.clone());
+        Ok(location)
+    }
+
+    /// Remove a breakpoint, returning whether it was set.
+    pub fn remove_breakpoint(&mut self, location: &SourceLocation) -> bool {
+        self.breakpoints.remove(location)
+    }
+
+    /// The lines with a breakpoint.
+    pub fn breakpoints(&self) -> impl Iterator<Item = &SourceLocation> + '_ {
+        self.breakpoints.iter()
+    }
+
+    /// The source line of the next instruction, if it has one.
+    pub fn location(&self) -> Option<SourceLocation> {
+        let inst_offset = self.inst_offset()?;
+        // The patched jump to the test isn't part of the program.
+        if inst_offset == PROGRAM_START_INST_OFFSET as usize
+            && self.test_offset != PROGRAM_START_INST_OFFSET
+        {
+            return None;
+        }
+        self.locations.get(&inst_offset).cloned()
+    }
+
+    /// The next instruction to be executed.
+    pub fn instruction(&self) -> Option<Instruction> {
+        if self.finished() {
+            return None;
+        }
+        let bytes = self.memory(self.register(RegId::PC), Instruction::SIZE as u64);
+        let bytes = <[u8; Instruction::SIZE]>::try_from(bytes.ok()?).ok()?;
+        Instruction::try_from(bytes).ok()
+    }
+
+    /// The name and value of each register.
+    pub fn registers(&self) -> Vec<(String, u64)> {
+        self.interpreter
+            .registers()
+            .iter()
+            .enumerate()
+            .map(|(id, value)| (register_name(id), *value))
+            .collect()
+    }
+
+    /// The bytes of memory in the range `addr..addr + len`.
+    pub fn memory(&self, addr: u64, len: u64) -> anyhow::Result<&[u8]> {
+        let memory = self.interpreter.memory();
+        let end = addr
+            .checked_add(len)
+            .filter(|end| *end <= memory.len() as u64)
+            .ok_or_else(|| anyhow!("memory range {addr:#x}..{addr:#x}+{len} is out of bounds"))?;
+        Ok(&memory[addr as usize..end as usize])
+    }
+
+    /// The address of the start of the stack, and its contents.
+    pub fn stack(&self) -> anyhow::Result<(u64, &[u8])> {
+        let ssp = self.register(RegId::SSP);
+        let sp = self.register(RegId::SP);
+        Ok((ssp, self.memory(ssp, sp.saturating_sub(ssp))?))
+    }
+
+    /// The name of the innermost function being executed which stores locals on the stack.
+    pub fn function(&self) -> Option<&str> {
+        self.frames
+            .last()
+            .map(|frame| frame.locals.function.as_str())
+    }
+
+    /// The values of the locals stored on the stack by the innermost function being executed
+    /// which has some.
+    pub fn locals(&self) -> anyhow::Result<Vec<LocalValue>> {
+        let frame = match self.frames.last() {
+            Some(frame) => frame,
+            None => return Ok(vec![]),
+        };
+        frame
+            .locals
+            .locals
+            .iter()
+            .map(|local| {
+                let address = frame.base + local.offset;
+                Ok(LocalValue {
+                    name: local.name.clone(),
+                    address,
+                    bytes: self.memory(address, local.size)?.to_vec(),
+                })
+            })
+            .collect()
+    }
+
+    /// The receipts produced by the test so far.
+    pub fn receipts(&self) -> &[tx::Receipt] {
+        self.interpreter.receipts()
+    }
+
+    /// The values logged by the test so far, decoded using the logged types of the ABI.
+    pub fn decoded_logs(&self) -> Vec<String> {
+        self.receipts()
+            .iter()
+            .filter_map(|receipt| self.log_decoder.decode(receipt))
+            .collect()
+    }
+
+    /// The instruction offset of the next instruction within the test bytecode, or `None` if it
+    /// belongs to a called contract.
+    fn inst_offset(&self) -> Option<usize> {
+        if self.finished() || self.register(RegId::FP) != 0 {
+            return None;
+        }
+        let pc = self.register(RegId::PC) - self.register(RegId::IS);
+        Some((pc / Instruction::SIZE as u64) as usize)
+    }
+
+    fn register(&self, id: RegId) -> u64 {
+        self.interpreter.registers()[id.to_u8() as usize]
+    }
+}
+
+/// The name of a register as written in Sway assembly.  The general purpose registers are
+/// numbered from the first one after the reserved registers.
+fn register_name(id: usize) -> String {
+    match RESERVED_REGISTER_NAMES.get(id) {
+        Some(name) => format!("${name}"),
+        None => format!("$r{}", id - RESERVED_REGISTER_NAMES.len()),
+    }
+}
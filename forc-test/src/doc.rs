@@ -0,0 +1,335 @@
+//! Doc tests: the Sway code blocks of the doc comments of a library package, compiled and run as
+//! the unit tests of a generated package depending on it.
+//!
+//! Each code block becomes a module of the generated package. Its `use` items are kept at the
+//! module level, while the rest of the block becomes the body of a test function named after the
+//! file and line the block is documented at, e.g. `hash_line_12`, with a numbered suffix if the
+//! name of another file gives the same one, e.g. `sub_dir_lib_line_3_2`.
+//!
+//! Code blocks are tested unless their info string names another language than `sway`, or
+//! contains `ignore`. Blocks marked `no_run` are only compiled, and those marked `should_revert`
+//! pass if they revert. Blocks which are whole programs, starting with a program kind like
+//! `script;`, aren't tested.
+//!
+//! Only library packages have doc tests, as other packages can't be depended on.
+use anyhow::{Context, Result};
+use forc_pkg::{
+    manifest::{Dependency, DependencyDetails},
+    PackageManifestFile,
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+use sway_core::language::parsed::TreeType;
+use sway_utils::constants;
+
+/// The name of the directory doc tests are generated in, within the output directory of a package.
+pub const DOC_TESTS_DIR_NAME: &str = "doc-tests";
+
+const SWAY_EXTENSION: &str = "sw";
+const CODE_BLOCK_FENCE: &str = "```";
+const PROGRAM_KINDS: [&str; 4] = ["library;", "script;", "contract;", "predicate;"];
+
+/// A Sway code block of a doc comment, to be run as a test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocTest {
+    /// The source file documenting the code block.
+    pub path: PathBuf,
+    /// The line of the source file opening the code block.
+    pub line: usize,
+    /// The name of the test running the code block.
+    pub name: String,
+    /// The code of the block, without the doc comment markers.
+    pub code: String,
+    pub kind: DocTestKind,
+}
+
+/// How a doc test is run, as given by the info string of its code block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocTestKind {
+    /// The code is run, and the test passes if it doesn't revert.
+    Run,
+    /// The code is run, and the test passes if it reverts.
+    ShouldRevert,
+    /// The code is only compiled.
+    NoRun,
+}
+
+/// The manifest of the package generated for the doc tests of a package.
+#[derive(Serialize)]
+struct DocTestManifest {
+    project: forc_pkg::manifest::Project,
+    dependencies: BTreeMap<String, Dependency>,
+}
+
+/// Collect the doc tests of the Sway source files of a library package.
+pub fn doc_tests(manifest: &PackageManifestFile) -> Result<Vec<DocTest>> {
+    manifest
+        .check_program_type(vec![TreeType::Library])
+        .context("doc tests can only be run for library packages")?;
+
+    let src_dir = manifest.dir().join(constants::SRC_DIR);
+    let mut paths = vec![];
+    sway_files(&src_dir, &mut paths)?;
+    paths.sort();
+
+    let mut doc_tests = vec![];
+    for path in paths {
+        let src = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let name_prefix = path
+            .strip_prefix(&src_dir)
+            .unwrap_or(&path)
+            .with_extension("")
+            .display()
+            .to_string();
+        doc_tests.extend(extract_doc_tests(&path, &name_prefix, &src));
+    }
+
+    // Distinct paths may give the same name, e.g. `sub_dir/lib.sw` and `sub_dir_lib.sw`.
+    let mut name_counts = HashMap::<String, usize>::new();
+    for doc_test in &mut doc_tests {
+        let count = name_counts.entry(doc_test.name.clone()).or_default();
+        *count += 1;
+        if *count > 1 {
+            doc_test.name = format!("{}_{count}", doc_test.name);
+        }
+    }
+    Ok(doc_tests)
+}
+
+/// Write a package running the given doc tests of a package to `dir`, replacing the tests of any
+/// previous run.
+pub fn write_doc_test_pkg(
+    manifest: &PackageManifestFile,
+    doc_tests: &[DocTest],
+    dir: &Path,
+) -> Result<()> {
+    let src_dir = dir.join(constants::SRC_DIR);
+    if src_dir.exists() {
+        fs::remove_dir_all(&src_dir)?;
+    }
+    fs::create_dir_all(&src_dir)?;
+
+    let mut lib = String::from("library;\n\n");
+    for doc_test in doc_tests {
+        lib.push_str(&format!("mod {};\n", doc_test.name));
+        fs::write(
+            src_dir.join(&doc_test.name).with_extension(SWAY_EXTENSION),
+            doc_test_module(doc_test),
+        )?;
+    }
+    fs::write(src_dir.join("lib.sw"), lib)?;
+
+    // The tests depend on the documented package, and may use its own dependencies.
+    let mut dependencies = manifest
+        .dependencies
+        .iter()
+        .flatten()
+        .map(|(name, dependency)| {
+            let dependency = match dependency {
+                Dependency::Detailed(details) => {
+                    let mut details = details.clone();
+                    details.path = details
+                        .path
+                        .map(|path| manifest.dir().join(path).display().to_string());
+                    Dependency::Detailed(details)
+                }
+                simple => simple.clone(),
+            };
+            (name.clone(), dependency)
+        })
+        .collect::<BTreeMap<_, _>>();
+    let mut documented_pkg = DependencyDetails::default();
+    documented_pkg.path = Some(manifest.dir().display().to_string());
+    dependencies.insert(
+        manifest.project.name.clone(),
+        Dependency::Detailed(documented_pkg),
+    );
+    let doc_test_manifest = DocTestManifest {
+        project: forc_pkg::manifest::Project {
+            authors: None,
+            name: format!("{}-doc-tests", manifest.project.name),
+            organization: None,
+            license: manifest.project.license.clone(),
+            entry: "lib.sw".to_string(),
+            implicit_std: manifest.project.implicit_std,
+            forc_version: None,
+        },
+        dependencies,
+    };
+    // Serialize through a `toml::Value`, which puts plain values before tables.
+    let toml = toml::Value::try_from(&doc_test_manifest)?.to_string();
+    fs::write(dir.join(constants::MANIFEST_FILE_NAME), toml)?;
+
+    Ok(())
+}
+
+/// Collect the paths of the Sway files within a directory and its subdirectories.
+fn sway_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            sway_files(&path, paths)?;
+        } else if path.extension().map_or(false, |ext| ext == SWAY_EXTENSION) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Extract the doc tests of the source code of a file, naming them after `name_prefix`.
+fn extract_doc_tests(path: &Path, name_prefix: &str, src: &str) -> Vec<DocTest> {
+    let name_prefix = name_prefix
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() {
+            true => c.to_ascii_lowercase(),
+            false => '_',
+        })
+        .collect::<String>();
+
+    let mut doc_tests = vec![];
+    // The line opening the code block being read, and its kind if it is to be tested.
+    let mut code_block: Option<(usize, Option<DocTestKind>)> = None;
+    let mut code = String::new();
+    for (ix, line) in src.lines().enumerate() {
+        let doc_line = line
+            .trim_start()
+            .strip_prefix("///")
+            .filter(|rest| !rest.starts_with('/'))
+            .or_else(|| line.trim_start().strip_prefix("//!"));
+        let Some(doc_line) = doc_line else {
+            // A code block left open ends with its doc comment.
+            code_block = None;
+            continue;
+        };
+        let doc_line = doc_line.strip_prefix(' ').unwrap_or(doc_line);
+        match (
+            &code_block,
+            doc_line.trim_start().strip_prefix(CODE_BLOCK_FENCE),
+        ) {
+            (None, Some(info)) => {
+                code_block = Some((ix + 1, doc_test_kind(info)));
+                code.clear();
+            }
+            (Some((line, kind)), Some(_)) => {
+                let is_program = code
+                    .lines()
+                    .map(str::trim)
+                    .find(|line| !line.is_empty() && !line.starts_with("//"))
+                    .map_or(false, |line| PROGRAM_KINDS.contains(&line));
+                if let (Some(kind), false) = (kind, is_program) {
+                    doc_tests.push(DocTest {
+                        path: path.to_path_buf(),
+                        line: *line,
+                        name: format!("{name_prefix}_line_{line}"),
+                        code: code.clone(),
+                        kind: *kind,
+                    });
+                }
+                code_block = None;
+            }
+            (Some(_), None) => {
+                code.push_str(doc_line);
+                code.push('\n');
+            }
+            (None, None) => {}
+        }
+    }
+    doc_tests
+}
+
+/// The kind of a doc test given the info string of its code block, or `None` if the code block
+/// isn't to be tested.
+fn doc_test_kind(info: &str) -> Option<DocTestKind> {
+    let mut kind = DocTestKind::Run;
+    for token in info
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+    {
+        match token {
+            "sway" => {}
+            "no_run" => kind = DocTestKind::NoRun,
+            "should_revert" => kind = DocTestKind::ShouldRevert,
+            _ => return None,
+        }
+    }
+    Some(kind)
+}
+
+/// The source code of the module running a doc test.
+fn doc_test_module(doc_test: &DocTest) -> String {
+    let mut uses = String::new();
+    let mut body = String::new();
+    let mut in_use = false;
+    for line in doc_test.code.lines() {
+        if in_use || line.trim_start().starts_with("use ") {
+            uses.push_str(line);
+            uses.push('\n');
+            in_use = !line.trim_end().ends_with(';');
+        } else if !line.trim().is_empty() {
+            body.push_str(&format!("    {line}\n"));
+        } else {
+            body.push('\n');
+        }
+    }
+    // The function of a test which is only compiled is public, so that it isn't warned about as
+    // never called.
+    let prefix = match doc_test.kind {
+        DocTestKind::Run => "#[test]\n",
+        DocTestKind::ShouldRevert => "#[test(should_revert)]\n",
+        DocTestKind::NoRun => "pub ",
+    };
+    format!(
+        "library;\n\n{uses}\n{prefix}fn {}() {{\n{body}}}\n",
+        doc_test.name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_doc_tests, DocTestKind};
+    use std::path::Path;
+
+    #[test]
+    fn test_extract_doc_tests() {
+        let src = r#"library;
+
+/// Returns the square of `x`.
+///
+/// ```sway
+/// use my_lib::square;
+///
+/// assert(square(3) == 9);
+/// ```
+///
+/// ```should_revert
+/// revert(0);
+/// ```
+///
+/// ```console
+/// forc test --doc
+/// ```
+///
+/// ```ignore
+/// square(true);
+/// ```
+pub fn square(x: u64) -> u64 {
+    x * x
+}
+"#;
+        let doc_tests = extract_doc_tests(Path::new("src/math.sw"), "math", src);
+        assert_eq!(2, doc_tests.len());
+        assert_eq!("math_line_5", doc_tests[0].name);
+        assert_eq!(
+            "use my_lib::square;\n\nassert(square(3) == 9);\n",
+            doc_tests[0].code
+        );
+        assert_eq!(DocTestKind::Run, doc_tests[0].kind);
+        assert_eq!("math_line_11", doc_tests[1].name);
+        assert_eq!(DocTestKind::ShouldRevert, doc_tests[1].kind);
+    }
+}
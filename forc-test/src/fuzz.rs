@@ -0,0 +1,422 @@
+//! Input generation and shrinking for parameterized tests, i.e. those declared with
+//! `#[test(fuzz)]`.
+//!
+//! The parameter types of a test are resolved from its JSON ABI.  Random values are generated for
+//! each parameter and encoded into the script data of the test transaction using the same memory
+//! layout the compiler uses for the arguments of a script's `main()`.
+
+use crate::abi::{DisplayValue, ParamType, Value};
+use anyhow::anyhow;
+use forc_pkg::program_abi::ProgramABI;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// The number of runs used for a parameterized test unless otherwise specified.
+pub const DEFAULT_FUZZ_RUNS: usize = 256;
+
+/// The seed used for input generation unless otherwise specified.
+pub const DEFAULT_FUZZ_SEED: u64 = 0xF022;
+
+/// The maximum number of successful shrinks applied to a failing input.
+const MAX_SHRINK_STEPS: usize = 512;
+
+/// The set of options for running parameterized tests.
+#[derive(Clone, Debug)]
+pub struct FuzzOpts {
+    /// The number of runs for each test which doesn't specify its own with
+    /// `#[test(fuzz = "<runs>")]`.
+    pub runs: usize,
+    /// The seed for the input generator.  Each test derives its own seed from this and its name,
+    /// so the generated inputs don't depend on the order the tests are run in.
+    pub seed: u64,
+}
+
+impl Default for FuzzOpts {
+    fn default() -> Self {
+        Self {
+            runs: DEFAULT_FUZZ_RUNS,
+            seed: DEFAULT_FUZZ_SEED,
+        }
+    }
+}
+
+/// The outcome of running a parameterized test.
+#[derive(Clone, Debug)]
+pub struct FuzzOutcome {
+    /// The number of runs executed, including any made while shrinking a failing input.
+    pub runs: usize,
+    /// The seed used to generate the inputs for this test.
+    pub seed: u64,
+    /// The minimal failing input found, formatted as a list of `name: value` pairs.
+    pub counterexample: Option<String>,
+}
+
+/// The parameters of a test function.
+#[derive(Clone, Debug)]
+pub(crate) struct TestParams {
+    params: Vec<(String, ParamType)>,
+}
+
+impl TestParams {
+    /// Resolve the parameters of the test function described by `abi`.
+    pub(crate) fn from_abi(abi: &ProgramABI) -> anyhow::Result<Self> {
+        let function = abi
+            .functions
+            .first()
+            .ok_or_else(|| anyhow!("missing test function in JSON ABI"))?;
+        let types = abi
+            .types
+            .iter()
+            .map(|decl| (decl.type_id, decl))
+            .collect::<HashMap<_, _>>();
+        let params = function
+            .inputs
+            .iter()
+            .map(|input| {
+                let ty = ParamType::resolve(&types, input, &HashMap::new())?;
+                Ok((input.name.clone(), ty))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { params })
+    }
+
+    /// Generate a random value for each parameter.
+    pub(crate) fn generate(&self, rng: &mut impl Rng) -> Vec<Value> {
+        self.params.iter().map(|(_, ty)| ty.generate(rng)).collect()
+    }
+
+    /// Encode the values of the parameters as script data.
+    pub(crate) fn encode(&self, values: &[Value]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        for ((_, ty), value) in self.params.iter().zip(values) {
+            ty.encode(value, &mut bytes);
+        }
+        bytes
+    }
+
+    /// Produce the candidates for a smaller input than `values`, simplest first.  Each candidate
+    /// shrinks the value of a single parameter.
+    pub(crate) fn shrink(&self, values: &[Value]) -> Vec<Vec<Value>> {
+        self.params
+            .iter()
+            .zip(values)
+            .enumerate()
+            .flat_map(|(idx, ((_, ty), value))| {
+                ty.shrink(value).into_iter().map(move |shrunk| {
+                    let mut candidate = values.to_vec();
+                    candidate[idx] = shrunk;
+                    candidate
+                })
+            })
+            .collect()
+    }
+
+    /// Format the values of the parameters as a list of `name: value` pairs.
+    pub(crate) fn display(&self, values: &[Value]) -> String {
+        self.params
+            .iter()
+            .zip(values)
+            .map(|((name, ty), value)| format!("{name}: {}", DisplayValue(ty, value)))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl ParamType {
+    /// The largest value of an unsigned integer type.
+    fn max_uint(&self) -> u64 {
+        match self {
+            ParamType::U8 => u8::MAX as u64,
+            ParamType::U16 => u16::MAX as u64,
+            ParamType::U32 => u32::MAX as u64,
+            _ => u64::MAX,
+        }
+    }
+
+    fn generate(&self, rng: &mut impl Rng) -> Value {
+        match self {
+            ParamType::Unit => Value::Unit,
+            ParamType::Bool => Value::Bool(rng.gen()),
+            ParamType::U8 | ParamType::U16 | ParamType::U32 | ParamType::U64 => {
+                // Bias the generator towards the edge cases which are most likely to find bugs.
+                let max = self.max_uint();
+                let value = match rng.gen_range(0..8) {
+                    0 => 0,
+                    1 => 1,
+                    2 => max,
+                    3 => max - 1,
+                    _ => rng.gen_range(0..=max),
+                };
+                Value::Uint(value)
+            }
+            ParamType::B256 => Value::B256(rng.gen()),
+            ParamType::Str(len) => Value::Str(
+                (0..*len)
+                    .map(|_| rng.sample(rand::distributions::Alphanumeric) as char)
+                    .collect(),
+            ),
+            ParamType::Array(elem_ty, len) => {
+                Value::Array((0..*len).map(|_| elem_ty.generate(rng)).collect())
+            }
+            ParamType::Tuple(elem_tys) => {
+                Value::Tuple(elem_tys.iter().map(|ty| ty.generate(rng)).collect())
+            }
+            ParamType::Struct { fields, .. } => {
+                Value::Struct(fields.iter().map(|(_, ty)| ty.generate(rng)).collect())
+            }
+            ParamType::Enum { variants, .. } => {
+                let tag = rng.gen_range(0..variants.len());
+                Value::Enum(tag, Box::new(variants[tag].1.generate(rng)))
+            }
+        }
+    }
+
+    /// The simplest value of this type, which every other value eventually shrinks to.
+    fn minimal(&self) -> Value {
+        match self {
+            ParamType::Unit => Value::Unit,
+            ParamType::Bool => Value::Bool(false),
+            ParamType::U8 | ParamType::U16 | ParamType::U32 | ParamType::U64 => Value::Uint(0),
+            ParamType::B256 => Value::B256([0; 32]),
+            ParamType::Str(len) => Value::Str("a".repeat(*len)),
+            ParamType::Array(elem_ty, len) => Value::Array(vec![elem_ty.minimal(); *len]),
+            ParamType::Tuple(elem_tys) => {
+                Value::Tuple(elem_tys.iter().map(ParamType::minimal).collect())
+            }
+            ParamType::Struct { fields, .. } => {
+                Value::Struct(fields.iter().map(|(_, ty)| ty.minimal()).collect())
+            }
+            ParamType::Enum { variants, .. } => Value::Enum(0, Box::new(variants[0].1.minimal())),
+        }
+    }
+
+    fn shrink(&self, value: &Value) -> Vec<Value> {
+        let mut candidates = Vec::new();
+        let minimal = self.minimal();
+        if *value != minimal {
+            candidates.push(minimal);
+        }
+
+        match (self, value) {
+            (ParamType::U8 | ParamType::U16 | ParamType::U32 | ParamType::U64, Value::Uint(n)) => {
+                for shrunk in [n / 2, n.saturating_sub(1)] {
+                    if shrunk != 0 && shrunk != *n && !candidates.contains(&Value::Uint(shrunk)) {
+                        candidates.push(Value::Uint(shrunk));
+                    }
+                }
+            }
+            (ParamType::B256, Value::B256(bytes)) => {
+                // Clear the most significant non-zero byte.
+                if let Some(idx) = bytes.iter().position(|b| *b != 0) {
+                    let mut shrunk = *bytes;
+                    shrunk[idx] = 0;
+                    if shrunk != [0; 32] {
+                        candidates.push(Value::B256(shrunk));
+                    }
+                }
+            }
+            (ParamType::Array(elem_ty, _), Value::Array(elems)) => {
+                candidates.extend(
+                    shrink_elems(elems, |idx| elem_ty.as_ref().shrink(&elems[idx]))
+                        .into_iter()
+                        .map(Value::Array),
+                );
+            }
+            (ParamType::Tuple(elem_tys), Value::Tuple(elems)) => {
+                candidates.extend(
+                    shrink_elems(elems, |idx| elem_tys[idx].shrink(&elems[idx]))
+                        .into_iter()
+                        .map(Value::Tuple),
+                );
+            }
+            (ParamType::Struct { fields, .. }, Value::Struct(elems)) => {
+                candidates.extend(
+                    shrink_elems(elems, |idx| fields[idx].1.shrink(&elems[idx]))
+                        .into_iter()
+                        .map(Value::Struct),
+                );
+            }
+            (ParamType::Enum { variants, .. }, Value::Enum(tag, inner)) => {
+                // Earlier variants are considered simpler.
+                for (simpler_tag, (_, variant_ty)) in variants.iter().enumerate().take(*tag) {
+                    let simpler = Value::Enum(simpler_tag, Box::new(variant_ty.minimal()));
+                    if !candidates.contains(&simpler) {
+                        candidates.push(simpler);
+                    }
+                }
+                candidates.extend(
+                    variants[*tag]
+                        .1
+                        .shrink(inner)
+                        .into_iter()
+                        .map(|shrunk| Value::Enum(*tag, Box::new(shrunk))),
+                );
+            }
+            _ => (),
+        }
+        candidates
+    }
+}
+
+/// Produce the candidates for shrinking a sequence of values, each shrinking a single element.
+fn shrink_elems(elems: &[Value], shrink_elem: impl Fn(usize) -> Vec<Value>) -> Vec<Vec<Value>> {
+    (0..elems.len())
+        .flat_map(|idx| {
+            shrink_elem(idx).into_iter().map(move |shrunk| {
+                let mut candidate = elems.to_vec();
+                candidate[idx] = shrunk;
+                candidate
+            })
+        })
+        .collect()
+}
+
+/// Shrink a failing input, where `fails` runs the test with a candidate input and returns
+/// whether it still fails.  Returns the smallest failing input found.
+pub(crate) fn shrink(
+    params: &TestParams,
+    mut values: Vec<Value>,
+    mut fails: impl FnMut(&[Value]) -> anyhow::Result<bool>,
+) -> anyhow::Result<Vec<Value>> {
+    for _ in 0..MAX_SHRINK_STEPS {
+        let mut shrunk = None;
+        for candidate in params.shrink(&values) {
+            if fails(&candidate)? {
+                shrunk = Some(candidate);
+                break;
+            }
+        }
+        match shrunk {
+            Some(candidate) => values = candidate,
+            None => break,
+        }
+    }
+    Ok(values)
+}
+
+/// Derive the seed for the inputs of a single test from the seed for the whole run.
+pub(crate) fn test_seed(seed: u64, test_name: &str) -> u64 {
+    // FNV-1a, which is stable across platforms and releases, unlike the std hasher.
+    test_name
+        .bytes()
+        .fold(seed ^ 0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forc_pkg::program_abi::{ABIFunction, TypeApplication, TypeDeclaration};
+
+    fn decl(
+        type_id: usize,
+        type_field: &str,
+        components: Option<Vec<TypeApplication>>,
+    ) -> TypeDeclaration {
+        TypeDeclaration {
+            type_id,
+            type_field: type_field.to_string(),
+            components,
+            type_parameters: None,
+        }
+    }
+
+    fn app(name: &str, type_id: usize) -> TypeApplication {
+        TypeApplication {
+            name: name.to_string(),
+            type_id,
+            type_arguments: None,
+        }
+    }
+
+    /// The ABI of `fn test(p: Point, o: Option<u8>, flag: bool)`.
+    fn test_abi() -> ProgramABI {
+        let mut option = decl(3, "enum Option", Some(vec![app("None", 0), app("Some", 4)]));
+        option.type_parameters = Some(vec![4]);
+        ProgramABI {
+            types: vec![
+                decl(0, "()", None),
+                decl(1, "u64", None),
+                decl(2, "struct Point", Some(vec![app("x", 1), app("y", 1)])),
+                option,
+                decl(4, "generic T", None),
+                decl(5, "u8", None),
+                decl(6, "bool", None),
+            ],
+            functions: vec![ABIFunction {
+                inputs: vec![
+                    app("p", 2),
+                    TypeApplication {
+                        name: "o".to_string(),
+                        type_id: 3,
+                        type_arguments: Some(vec![app("", 5)]),
+                    },
+                    app("flag", 6),
+                ],
+                name: "test".to_string(),
+                output: app("", 0),
+                attributes: None,
+            }],
+            logged_types: None,
+            messages_types: None,
+            configurables: None,
+        }
+    }
+
+    #[test]
+    fn resolve_params() {
+        let params = TestParams::from_abi(&test_abi()).unwrap();
+        let option_ty = ParamType::Enum {
+            name: "Option".to_string(),
+            variants: vec![
+                ("None".to_string(), ParamType::Unit),
+                ("Some".to_string(), ParamType::U8),
+            ],
+        };
+        assert_eq!(params.params[1], ("o".to_string(), option_ty));
+        assert_eq!(params.params[2], ("flag".to_string(), ParamType::Bool));
+    }
+
+    #[test]
+    fn encode_params() {
+        let params = TestParams::from_abi(&test_abi()).unwrap();
+        let values = vec![
+            Value::Struct(vec![Value::Uint(1), Value::Uint(2)]),
+            Value::Enum(1, Box::new(Value::Uint(3))),
+            Value::Bool(true),
+        ];
+        let words = params
+            .encode(&values)
+            .chunks(8)
+            .map(|word| u64::from_be_bytes(word.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(words, vec![1, 2, 1, 3, 1]);
+        assert_eq!(
+            params.display(&values),
+            "p: Point { x: 1, y: 2 }, o: Option::Some(3), flag: true"
+        );
+    }
+
+    #[test]
+    fn shrink_to_minimal_counterexample() {
+        let params = TestParams::from_abi(&test_abi()).unwrap();
+        let values = vec![
+            Value::Struct(vec![Value::Uint(1000), Value::Uint(12345)]),
+            Value::Enum(1, Box::new(Value::Uint(200))),
+            Value::Bool(true),
+        ];
+        // Fails whenever `p.x` is at least 10.
+        let shrunk = shrink(&params, values, |candidate| {
+            Ok(match &candidate[0] {
+                Value::Struct(fields) => matches!(fields[0], Value::Uint(x) if x >= 10),
+                _ => false,
+            })
+        })
+        .unwrap();
+        assert_eq!(
+            params.display(&shrunk),
+            "p: Point { x: 10, y: 0 }, o: Option::None, flag: false"
+        );
+    }
+}
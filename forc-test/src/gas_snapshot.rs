@@ -0,0 +1,216 @@
+//! Snapshots of the gas used by each test in a package, used to catch gas regressions.
+//!
+//! A snapshot is stored in a `.gas-snapshot` file next to the package manifest, with one line per
+//! test in the form `<test name> (gas: <gas used>)`, sorted by test name so that it diffs cleanly.
+
+use crate::TestedPackage;
+use anyhow::{anyhow, Context};
+use std::{collections::BTreeMap, fmt, fs, path::Path, str::FromStr};
+
+/// The name of the file a package's gas snapshot is stored in.
+pub const GAS_SNAPSHOT_FILE_NAME: &str = ".gas-snapshot";
+
+/// The gas used by each test in a package.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasSnapshot {
+    /// The gas used by each test, keyed by test name.
+    pub gas_used: BTreeMap<String, u64>,
+}
+
+/// The change in gas used by a single test between two snapshots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GasDiff {
+    /// The name of the test.
+    pub name: String,
+    /// The gas used in the previous snapshot, or `None` if the test is new.
+    pub previous: Option<u64>,
+    /// The gas used in the current snapshot, or `None` if the test was removed.
+    pub current: Option<u64>,
+}
+
+impl GasSnapshot {
+    /// Take a snapshot of the gas used by the tests of a package.
+    ///
+    /// Parameterized tests are left out, as the gas they use depends on their generated inputs.
+    pub fn from_tested_pkg(tested_pkg: &TestedPackage) -> Self {
+        let gas_used = tested_pkg
+            .tests
+            .iter()
+            .filter(|test| test.fuzz.is_none())
+            .map(|test| (test.name.clone(), test.gas_used))
+            .collect();
+        Self { gas_used }
+    }
+
+    /// Read a snapshot from the given file.
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read gas snapshot {}", path.display()))?;
+        contents
+            .parse()
+            .with_context(|| format!("failed to parse gas snapshot {}", path.display()))
+    }
+
+    /// Write this snapshot to the given file.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, self.to_string())
+            .with_context(|| format!("failed to write gas snapshot {}", path.display()))
+    }
+
+    /// Remove the tests for which `selected` returns `false` from this snapshot, e.g. those left
+    /// out of a run by a test filter.
+    pub fn retain(&mut self, selected: impl Fn(&str) -> bool) {
+        self.gas_used.retain(|name, _| selected(name));
+    }
+
+    /// Update this snapshot with the gas used by the tests of `other`, keeping the tests `other`
+    /// doesn't contain.
+    pub fn merge(&mut self, other: GasSnapshot) {
+        self.gas_used.extend(other.gas_used);
+    }
+
+    /// Compare this snapshot against a `previous` one, returning the tests whose gas usage
+    /// changed, along with any which were added or removed.
+    pub fn diff(&self, previous: &GasSnapshot) -> Vec<GasDiff> {
+        let mut names = previous
+            .gas_used
+            .keys()
+            .chain(self.gas_used.keys())
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let previous = previous.gas_used.get(name).copied();
+                let current = self.gas_used.get(name).copied();
+                (previous != current).then(|| GasDiff {
+                    name: name.clone(),
+                    previous,
+                    current,
+                })
+            })
+            .collect()
+    }
+}
+
+impl GasDiff {
+    /// The change in gas used as a percentage of the previous gas used, if the test was in both
+    /// snapshots.
+    pub fn percent_change(&self) -> Option<f64> {
+        match (self.previous, self.current) {
+            (Some(0), Some(_)) | (None, _) | (_, None) => None,
+            (Some(previous), Some(current)) => {
+                Some((current as f64 - previous as f64) / previous as f64 * 100.0)
+            }
+        }
+    }
+
+    /// Whether the gas used by the test grew by more than `tolerance` percent.  New and removed
+    /// tests are never considered regressions.
+    pub fn is_regression(&self, tolerance: u64) -> bool {
+        match (self.previous, self.current) {
+            (Some(previous), Some(current)) => {
+                current as u128 * 100 > previous as u128 * (100 + tolerance as u128)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for GasSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, gas_used) in &self.gas_used {
+            writeln!(f, "{name} (gas: {gas_used})")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GasSnapshot {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let gas_used = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                line.trim()
+                    .strip_suffix(')')
+                    .and_then(|line| line.rsplit_once(" (gas: "))
+                    .and_then(|(name, gas_used)| Some((name.to_string(), gas_used.parse().ok()?)))
+                    .ok_or_else(|| anyhow!("invalid entry on line {}: `{line}`", idx + 1))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { gas_used })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(entries: &[(&str, u64)]) -> GasSnapshot {
+        GasSnapshot {
+            gas_used: entries
+                .iter()
+                .map(|(name, gas_used)| (name.to_string(), *gas_used))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parse_snapshot() {
+        let snapshot = snapshot(&[("test_bam", 123), ("test_bum", 4567)]);
+        let contents = snapshot.to_string();
+        assert_eq!(contents, "test_bam (gas: 123)\ntest_bum (gas: 4567)\n");
+        assert_eq!(contents.parse::<GasSnapshot>().unwrap(), snapshot);
+        assert!("test_bam 123".parse::<GasSnapshot>().is_err());
+    }
+
+    #[test]
+    fn diff_snapshots() {
+        let previous = snapshot(&[
+            ("removed", 1),
+            ("same", 10),
+            ("grown", 100),
+            ("shrunk", 100),
+        ]);
+        let current = snapshot(&[("added", 1), ("same", 10), ("grown", 105), ("shrunk", 90)]);
+        let diffs = current.diff(&previous);
+        let names = diffs
+            .iter()
+            .map(|diff| diff.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["added", "grown", "removed", "shrunk"]);
+
+        let regressions = |tolerance| {
+            diffs
+                .iter()
+                .filter(|diff| diff.is_regression(tolerance))
+                .map(|diff| diff.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(regressions(0), vec!["grown"]);
+        assert_eq!(regressions(5), Vec::<&str>::new());
+        assert_eq!(diffs[1].percent_change(), Some(5.0));
+    }
+
+    #[test]
+    fn merge_filtered_snapshot() {
+        // Only the tests containing `bam` were run, and `test_bam_removed` no longer exists.
+        let mut previous = snapshot(&[
+            ("test_bam", 100),
+            ("test_bam_removed", 100),
+            ("test_bum", 200),
+        ]);
+        let current = snapshot(&[("test_bam", 110), ("test_bam_added", 10)]);
+        previous.retain(|name| !name.contains("bam"));
+        previous.merge(current);
+        assert_eq!(
+            previous,
+            snapshot(&[("test_bam", 110), ("test_bam_added", 10), ("test_bum", 200)])
+        );
+    }
+}
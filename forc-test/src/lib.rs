@@ -1,9 +1,22 @@
+mod abi;
+mod chain_state;
+mod coverage;
+#[cfg(feature = "debugger")]
+mod debugger;
+mod doc;
+mod fuzz;
+mod gas_snapshot;
+mod logs;
+mod report;
+
+use coverage::CoverageProfilingData;
 use forc_pkg as pkg;
 use fuel_abi_types::error_codes::ErrorSignal;
 use fuel_tx as tx;
 use fuel_vm::checked_transaction::builder::TransactionBuilderExt;
 use fuel_vm::gas::GasCosts;
 use fuel_vm::{self as vm, fuel_asm, prelude::Instruction};
+use logs::LogDecoder;
 use pkg::TestPassCondition;
 use pkg::{Built, BuiltPackage};
 use rand::{Rng, SeedableRng};
@@ -12,6 +25,17 @@ use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
 use sway_core::BuildTarget;
 use sway_types::Span;
 
+pub use chain_state::{ChainState, ContractState};
+pub use coverage::{Coverage, FileCoverage, InstructionCoverage};
+#[cfg(feature = "debugger")]
+pub use debugger::{DebugEvent, DebugSession, LocalValue, SourceLocation};
+pub use doc::{doc_tests, write_doc_test_pkg, DocTest, DocTestKind, DOC_TESTS_DIR_NAME};
+pub use fuzz::{FuzzOpts, FuzzOutcome, DEFAULT_FUZZ_RUNS, DEFAULT_FUZZ_SEED};
+pub use gas_snapshot::{GasDiff, GasSnapshot, GAS_SNAPSHOT_FILE_NAME};
+pub use report::{
+    FuzzReport, PackageReport, SpanReport, TestCaseReport, TestReport, REPORT_SCHEMA_VERSION,
+};
+
 /// The result of a `forc test` invocation.
 #[derive(Debug)]
 pub enum Tested {
@@ -36,6 +60,7 @@ pub struct TestDetails {
 }
 
 /// The filter to be used to only run matching tests.
+#[derive(Clone, Copy, Debug)]
 pub struct TestFilter<'a> {
     /// The phrase used for filtering, a `&str` searched/matched with test name.
     pub filter_phrase: &'a str,
@@ -44,6 +69,20 @@ pub struct TestFilter<'a> {
     pub exact_match: bool,
 }
 
+/// The set of options for running tests.
+#[derive(Clone, Debug, Default)]
+pub struct RunOpts {
+    /// Options for running parameterized tests.
+    pub fuzz: FuzzOpts,
+    /// Collect the instructions executed by each test, to report the coverage of the package.
+    pub coverage: bool,
+    /// Also run the tests declared with `#[test(ignore)]`.
+    pub include_ignored: bool,
+    /// The state of the simulated chain the tests are run against, extended by the chain state
+    /// declared by each test.
+    pub chain_state: ChainState,
+}
+
 /// The result of executing a single test within a single package.
 #[derive(Debug)]
 pub struct TestResult {
@@ -61,8 +100,21 @@ pub struct TestResult {
     pub condition: pkg::TestPassCondition,
     /// Emitted `Recipt`s during the execution of the test.
     pub logs: Vec<fuel_tx::Receipt>,
+    /// The values logged by the test, decoded using the logged types of the package.
+    pub decoded_logs: Vec<String>,
+    /// The receipts the test is expected to emit.
+    pub expected_receipts: pkg::ExpectedReceipts,
+    /// The number of `Transfer` and `TransferOut` receipts emitted by the test.
+    pub transfers: usize,
+    /// The number of `MessageOut` receipts emitted by the test, i.e. messages sent with `smo`.
+    pub messages: usize,
     /// Gas used while executing this test.
     pub gas_used: u64,
+    /// The outcome of a parameterized test, i.e. one declared with `#[test(fuzz)]`.  The rest of
+    /// this result describes its last failing run, or its last run if all of them passed.
+    pub fuzz: Option<FuzzOutcome>,
+    /// The instructions executed by the test, if coverage was requested.
+    pub coverage: Option<InstructionCoverage>,
 }
 
 const TEST_METADATA_SEED: u64 = 0x7E57u64;
@@ -171,6 +223,7 @@ pub struct Opts {
 pub struct TestPrintOpts {
     pub pretty_print: bool,
     pub print_logs: bool,
+    pub raw_logs: bool,
 }
 
 /// The storage and the contract id (if a contract is being tested) for a test.
@@ -240,6 +293,15 @@ impl TestedPackage {
     pub fn tests_passed(&self) -> bool {
         self.tests.iter().all(|test| test.passed())
     }
+
+    /// The path of the gas snapshot file for this package, next to its manifest.
+    pub fn gas_snapshot_path(&self) -> PathBuf {
+        self.built
+            .descriptor
+            .manifest_file
+            .dir()
+            .join(GAS_SNAPSHOT_FILE_NAME)
+    }
 }
 
 impl PackageWithDeploymentToTest {
@@ -265,28 +327,55 @@ impl PackageWithDeploymentToTest {
         }
     }
 
+    /// The ids of the contracts deployed for the tests, along with their ABIs.
+    fn deployed_contract_abis(
+        &self,
+    ) -> Vec<(tx::ContractId, &sway_core::asm_generation::ProgramABI)> {
+        let mut contract_abis = self
+            .contract_dependencies()
+            .map(|built_pkg| {
+                let contract_id = contract_id(built_pkg, &built_pkg.bytecode);
+                (contract_id, &built_pkg.program_abi)
+            })
+            .collect::<Vec<_>>();
+        if let PackageWithDeploymentToTest::Contract(contract_to_test) = self {
+            if let Some(abi) = &contract_to_test.pkg.program_abi_without_tests {
+                let contract_id = contract_id(
+                    &contract_to_test.pkg,
+                    &contract_to_test.without_tests_bytecode,
+                );
+                contract_abis.push((contract_id, abi));
+            }
+        }
+        contract_abis
+    }
+
     /// Deploy the contract dependencies for packages that require deployment.
     ///
     /// For scripts deploys all contract dependencies.
     /// For contract deploys all contract dependencies and the root contract itself.
-    fn deploy(&self) -> anyhow::Result<TestSetup> {
+    /// The contracts are deployed to a chain in the given state, and their balances and storage
+    /// are then set as configured by it.
+    fn deploy(&self, chain_state: &ChainState) -> anyhow::Result<TestSetup> {
         // Setup the interpreter for deployment.
         let params = tx::ConsensusParameters::default();
-        let storage = vm::storage::MemoryStorage::default();
+        let storage = chain_state.storage();
         let mut interpreter =
             vm::interpreter::Interpreter::with_storage(storage, params, GasCosts::default());
 
         // Iterate and create deployment transactions for contract dependencies of the root
         // contract.
-        let contract_dependency_setups = self
-            .contract_dependencies()
-            .map(|built_pkg| deployment_transaction(built_pkg, &built_pkg.bytecode, params));
+        let contract_dependency_setups = self.contract_dependencies().map(|built_pkg| {
+            let (contract_id, tx) = deployment_transaction(built_pkg, &built_pkg.bytecode, params);
+            (&built_pkg.descriptor.name, contract_id, tx)
+        });
 
         // Deploy contract dependencies of the root contract and collect their ids.
         let contract_dependency_ids = contract_dependency_setups
-            .map(|(contract_id, tx)| {
+            .map(|(name, contract_id, tx)| {
                 // Transact the deployment transaction constructed for this contract dependency.
                 interpreter.transact(tx)?;
+                chain_state.apply_contract_state(interpreter.as_mut(), name, &contract_id)?;
                 Ok(contract_id)
             })
             .collect::<anyhow::Result<Vec<_>>>()?;
@@ -302,6 +391,11 @@ impl PackageWithDeploymentToTest {
             );
             // Deploy the root contract.
             interpreter.transact(root_contract_tx)?;
+            chain_state.apply_contract_state(
+                interpreter.as_mut(),
+                &contract_to_test.pkg.descriptor.name,
+                &root_contract_id,
+            )?;
             let storage = interpreter.as_ref().clone();
             DeploymentSetup::Contract(ContractTestSetup {
                 storage,
@@ -400,8 +494,10 @@ impl<'a> PackageTests {
         &self,
         test_runners: &rayon::ThreadPool,
         test_filter: Option<&TestFilter>,
+        run_opts: &RunOpts,
     ) -> anyhow::Result<TestedPackage> {
         let pkg_with_tests = self.built_pkg_with_tests();
+        let log_decoder = self.log_decoder();
         let tests = test_runners.install(|| {
             pkg_with_tests
                 .bytecode
@@ -416,47 +512,8 @@ impl<'a> PackageTests {
                         None => true,
                     }
                 })
-                .map(|(entry, test_entry)| {
-                    let offset = u32::try_from(entry.finalized.imm)
-                        .expect("test instruction offset out of range");
-                    let name = entry.finalized.fn_name.clone();
-                    let test_setup = self.setup()?;
-                    let (state, duration, receipts) =
-                        exec_test(&pkg_with_tests.bytecode.bytes, offset, test_setup);
-
-                    let gas_used = *receipts
-                        .iter()
-                        .find_map(|receipt| match receipt {
-                            tx::Receipt::ScriptResult { gas_used, .. } => Some(gas_used),
-                            _ => None,
-                        })
-                        .ok_or_else(|| {
-                            anyhow::anyhow!("missing used gas information from test execution")
-                        })?;
-
-                    // Only retain `Log` and `LogData` receipts.
-                    let logs = receipts
-                        .into_iter()
-                        .filter(|receipt| {
-                            matches!(receipt, fuel_tx::Receipt::Log { .. })
-                                || matches!(receipt, fuel_tx::Receipt::LogData { .. })
-                        })
-                        .collect();
-
-                    let span = test_entry.span.clone();
-                    let file_path = test_entry.file_path.clone();
-                    let condition = test_entry.pass_condition.clone();
-                    Ok(TestResult {
-                        name,
-                        file_path,
-                        duration,
-                        span,
-                        state,
-                        condition,
-                        logs,
-                        gas_used,
-                    })
-                })
+                .filter(|(_, test_entry)| run_opts.include_ignored || !test_entry.ignored)
+                .map(|(entry, test_entry)| self.run_test(entry, test_entry, run_opts, &log_decoder))
                 .collect::<anyhow::Result<_>>()
         })?;
 
@@ -467,23 +524,259 @@ impl<'a> PackageTests {
         Ok(tested_pkg)
     }
 
+    /// Run a single test.
+    fn run_test(
+        &self,
+        entry: &pkg::PkgEntry,
+        test_entry: &pkg::PkgTestEntry,
+        run_opts: &RunOpts,
+        log_decoder: &LogDecoder,
+    ) -> anyhow::Result<TestResult> {
+        match &test_entry.fuzz {
+            Some(fuzz_entry) => {
+                let (result, _) =
+                    self.run_fuzz_test(entry, test_entry, fuzz_entry, run_opts, log_decoder)?;
+                Ok(result)
+            }
+            None => self.run_test_with_data(entry, test_entry, vec![], run_opts, log_decoder),
+        }
+    }
+
+    /// Run a parameterized test with a new set of generated inputs each time, until either one
+    /// fails or the requested number of runs is reached.  A failing input is then shrunk to the
+    /// smallest one found which still fails.
+    ///
+    /// Returns the result of the last failing run, or the last run if all of them passed, along
+    /// with the script data it was run with.
+    fn run_fuzz_test(
+        &self,
+        entry: &pkg::PkgEntry,
+        test_entry: &pkg::PkgTestEntry,
+        fuzz_entry: &pkg::FuzzTestEntry,
+        run_opts: &RunOpts,
+        log_decoder: &LogDecoder,
+    ) -> anyhow::Result<(TestResult, Vec<u8>)> {
+        let params = fuzz::TestParams::from_abi(&fuzz_entry.abi)?;
+        let runs = fuzz_entry.runs.unwrap_or(run_opts.fuzz.runs).max(1);
+        let seed = fuzz::test_seed(run_opts.fuzz.seed, &entry.finalized.fn_name);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+
+        let mut total_runs = 0;
+        let mut duration = std::time::Duration::ZERO;
+        let mut last_run = None;
+        let mut failure = None;
+        for _ in 0..runs {
+            let values = params.generate(&mut rng);
+            let result = self.run_test_with_data(
+                entry,
+                test_entry,
+                params.encode(&values),
+                run_opts,
+                log_decoder,
+            )?;
+            total_runs += 1;
+            duration += result.duration;
+            if !result.passed() {
+                failure = Some((values, result));
+                break;
+            }
+            last_run = Some((values, result));
+        }
+
+        let (values, mut result, counterexample) = match failure {
+            Some((values, mut failed_result)) => {
+                let values = fuzz::shrink(&params, values, |candidate| {
+                    let result = self.run_test_with_data(
+                        entry,
+                        test_entry,
+                        params.encode(candidate),
+                        run_opts,
+                        log_decoder,
+                    )?;
+                    total_runs += 1;
+                    duration += result.duration;
+                    let failed = !result.passed();
+                    if failed {
+                        failed_result = result;
+                    }
+                    Ok(failed)
+                })?;
+                let counterexample = params.display(&values);
+                (values, failed_result, Some(counterexample))
+            }
+            None => {
+                let (values, result) = last_run.expect("parameterized tests are run at least once");
+                (values, result, None)
+            }
+        };
+        result.duration = duration;
+        result.fuzz = Some(FuzzOutcome {
+            runs: total_runs,
+            seed,
+            counterexample,
+        });
+        Ok((result, params.encode(&values)))
+    }
+
+    /// Run a single test once, passing it the given script data, and collecting its coverage if
+    /// requested.
+    fn run_test_with_data(
+        &self,
+        entry: &pkg::PkgEntry,
+        test_entry: &pkg::PkgTestEntry,
+        script_data: Vec<u8>,
+        run_opts: &RunOpts,
+        log_decoder: &LogDecoder,
+    ) -> anyhow::Result<TestResult> {
+        let pkg_with_tests = self.built_pkg_with_tests();
+        let name = entry.finalized.fn_name.clone();
+        let chain_state = self.chain_state(test_entry, run_opts);
+        let test_setup = self.setup(&chain_state)?;
+        let contract_id = test_setup.root_contract_id();
+        let (state, duration, receipts, profiled) = exec_test(
+            &pkg_with_tests.bytecode.bytes,
+            test_offset(entry),
+            test_setup,
+            &chain_state,
+            script_data,
+            run_opts.coverage,
+        );
+        let coverage = profiled.map(|profiled| {
+            InstructionCoverage::from_profiler(&profiled, test_offset(entry), contract_id)
+        });
+
+        let gas_used = *receipts
+            .iter()
+            .find_map(|receipt| match receipt {
+                tx::Receipt::ScriptResult { gas_used, .. } => Some(gas_used),
+                _ => None,
+            })
+            .ok_or_else(|| anyhow::anyhow!("missing used gas information from test execution"))?;
+
+        let transfers = receipts
+            .iter()
+            .filter(|receipt| {
+                matches!(
+                    receipt,
+                    tx::Receipt::Transfer { .. } | tx::Receipt::TransferOut { .. }
+                )
+            })
+            .count();
+        let messages = receipts
+            .iter()
+            .filter(|receipt| matches!(receipt, tx::Receipt::MessageOut { .. }))
+            .count();
+        let decoded_logs = receipts
+            .iter()
+            .filter_map(|receipt| log_decoder.decode(receipt))
+            .collect();
+
+        // Only retain `Log` and `LogData` receipts.
+        let logs = receipts
+            .into_iter()
+            .filter(|receipt| {
+                matches!(receipt, fuel_tx::Receipt::Log { .. })
+                    || matches!(receipt, fuel_tx::Receipt::LogData { .. })
+            })
+            .collect();
+
+        let span = test_entry.span.clone();
+        let file_path = test_entry.file_path.clone();
+        let condition = test_entry.pass_condition.clone();
+        let expected_receipts = test_entry.expected_receipts.clone();
+        Ok(TestResult {
+            name,
+            file_path,
+            duration,
+            span,
+            state,
+            condition,
+            logs,
+            decoded_logs,
+            expected_receipts,
+            transfers,
+            messages,
+            gas_used,
+            fuzz: None,
+            coverage,
+        })
+    }
+
+    /// Start debugging a single test, paused before its first instruction.
+    ///
+    /// Parameterized tests are debugged with the inputs of their first run.
+    #[cfg(feature = "debugger")]
+    fn debug_test(
+        &self,
+        entry: &pkg::PkgEntry,
+        test_entry: &pkg::PkgTestEntry,
+        run_opts: &RunOpts,
+    ) -> anyhow::Result<DebugSession> {
+        let script_data = match &test_entry.fuzz {
+            Some(fuzz_entry) => {
+                let params = fuzz::TestParams::from_abi(&fuzz_entry.abi)?;
+                let seed = fuzz::test_seed(run_opts.fuzz.seed, &entry.finalized.fn_name);
+                let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+                params.encode(&params.generate(&mut rng))
+            }
+            None => vec![],
+        };
+        let pkg_with_tests = self.built_pkg_with_tests();
+        let chain_state = self.chain_state(test_entry, run_opts);
+        let test_setup = self.setup(&chain_state)?;
+        let tx = test_transaction(
+            &pkg_with_tests.bytecode.bytes,
+            test_offset(entry),
+            &test_setup,
+            &chain_state,
+            script_data,
+        );
+        DebugSession::new(
+            tx,
+            test_setup.storage().clone(),
+            pkg_with_tests.source_map(),
+            test_offset(entry),
+            &test_entry.file_path,
+            self.log_decoder(),
+        )
+    }
+
+    /// Create a decoder for the values logged by the tests of this package, and by the contracts
+    /// they call.
+    fn log_decoder(&self) -> LogDecoder {
+        let contract_abis = match self {
+            PackageTests::Contract(pkg) | PackageTests::Script(pkg) => pkg.deployed_contract_abis(),
+            PackageTests::Predicate(_) | PackageTests::Library(_) => vec![],
+        };
+        LogDecoder::new(&self.built_pkg_with_tests().program_abi, contract_abis)
+    }
+
+    /// The state of the chain a test is run against, i.e. the one provided to run all tests
+    /// extended with the one declared by the test.
+    fn chain_state(&self, test_entry: &pkg::PkgTestEntry, run_opts: &RunOpts) -> ChainState {
+        let pkg_name = &self.built_pkg_with_tests().descriptor.name;
+        run_opts
+            .chain_state
+            .with_test(&test_entry.chain_state, pkg_name)
+    }
+
     /// Setup the storage for a test and returns a contract id for testing contracts.
     ///
     /// For testing contracts, storage returned from this function contains the deployed contract.
-    /// For other types, default storage is returned.
-    fn setup(&self) -> anyhow::Result<TestSetup> {
+    /// For other types, the storage of a chain in the given state is returned.
+    fn setup(&self, chain_state: &ChainState) -> anyhow::Result<TestSetup> {
         match self {
             PackageTests::Contract(contract_to_test) => {
-                let test_setup = contract_to_test.deploy()?;
+                let test_setup = contract_to_test.deploy(chain_state)?;
                 Ok(test_setup)
             }
             PackageTests::Script(script_to_test) => {
-                let test_setup = script_to_test.deploy()?;
+                let test_setup = script_to_test.deploy(chain_state)?;
                 Ok(test_setup)
             }
-            PackageTests::Predicate(_) | PackageTests::Library(_) => Ok(
-                TestSetup::WithoutDeployment(vm::storage::MemoryStorage::default()),
-            ),
+            PackageTests::Predicate(_) | PackageTests::Library(_) => {
+                Ok(TestSetup::WithoutDeployment(chain_state.storage()))
+            }
         }
     }
 }
@@ -512,17 +805,73 @@ impl Opts {
 impl TestResult {
     /// Whether or not the test passed.
     pub fn passed(&self) -> bool {
-        match &self.condition {
+        let condition_met = match &self.condition {
             TestPassCondition::ShouldRevert(revert_code) => match revert_code {
                 Some(revert_code) => self.state == vm::state::ProgramState::Revert(*revert_code),
                 None => matches!(self.state, vm::state::ProgramState::Revert(_)),
             },
+            TestPassCondition::ShouldRevertWithVariant(variant) => {
+                matches!(self.state, vm::state::ProgramState::Revert(_))
+                    && self.logged_variant(variant)
+            }
             TestPassCondition::ShouldNotRevert => {
                 !matches!(self.state, vm::state::ProgramState::Revert(_))
             }
+        };
+        condition_met && self.unmet_expectations().is_empty()
+    }
+
+    /// Whether the last value logged by the test is the given enum variant, e.g.
+    /// `Error::NotOwner`, as logged by `require` before reverting.
+    fn logged_variant(&self, variant: &str) -> bool {
+        // Logged values are displayed without the path of their type, so only compare the enum
+        // and variant names.
+        let variant = match variant.rmatch_indices("::").nth(1) {
+            Some((idx, _)) => &variant[idx + 2..],
+            None => variant,
+        };
+        match self.decoded_logs.last() {
+            Some(log) => {
+                log == variant
+                    || log
+                        .strip_prefix(variant)
+                        .map_or(false, |rest| rest.starts_with('('))
+            }
+            None => false,
         }
     }
 
+    /// Describe each of the expectations on the receipts emitted by the test which are not met.
+    pub fn unmet_expectations(&self) -> Vec<String> {
+        let mut unmet = vec![];
+        if let Some(expected_logs) = &self.expected_receipts.logs {
+            if *expected_logs != self.decoded_logs {
+                unmet.push(format!(
+                    "expected logs [{}], found [{}]",
+                    expected_logs.join(", "),
+                    self.decoded_logs.join(", ")
+                ));
+            }
+        }
+        if let Some(expected_transfers) = self.expected_receipts.transfers {
+            if expected_transfers != self.transfers {
+                unmet.push(format!(
+                    "expected {expected_transfers} transfers, found {}",
+                    self.transfers
+                ));
+            }
+        }
+        if let Some(expected_messages) = self.expected_receipts.messages {
+            if expected_messages != self.messages {
+                unmet.push(format!(
+                    "expected {expected_messages} messages, found {}",
+                    self.messages
+                ));
+            }
+        }
+        unmet
+    }
+
     /// Return the revert code for this `TestResult` if the test is reverted.
     pub fn revert_code(&self) -> Option<u64> {
         match self.state {
@@ -565,10 +914,13 @@ pub enum TestRunnerCount {
 pub struct TestCount {
     pub total: usize,
     pub ignored: usize,
+    /// The number of tests matching the filter which are marked with `#[test(ignore)]`.
+    pub marked_ignored: usize,
 }
 
 impl<'a> TestFilter<'a> {
-    fn filter(&self, fn_name: &str) -> bool {
+    /// Whether the test with the given name is selected by this filter.
+    pub fn filter(&self, fn_name: &str) -> bool {
         if self.exact_match {
             fn_name == self.filter_phrase
         } else {
@@ -592,37 +944,70 @@ impl BuiltTests {
                     .iter()
                     .filter_map(|entry| entry.kind.test().map(|test| (entry, test)))
             })
-            .fold(TestCount::default(), |acc, (pkg_entry, _)| {
-                let num_ignored = match &test_filter {
-                    Some(filter) => {
-                        if filter.filter(&pkg_entry.finalized.fn_name) {
-                            acc.ignored
-                        } else {
-                            acc.ignored + 1
-                        }
-                    }
-                    None => acc.ignored,
+            .fold(TestCount::default(), |acc, (pkg_entry, test_entry)| {
+                let filtered_out = match &test_filter {
+                    Some(filter) => !filter.filter(&pkg_entry.finalized.fn_name),
+                    None => false,
+                };
+                let num_ignored = if filtered_out {
+                    acc.ignored + 1
+                } else {
+                    acc.ignored
+                };
+                let num_marked_ignored = if !filtered_out && test_entry.ignored {
+                    acc.marked_ignored + 1
+                } else {
+                    acc.marked_ignored
                 };
                 TestCount {
                     total: acc.total + 1,
                     ignored: num_ignored,
+                    marked_ignored: num_marked_ignored,
                 }
             })
     }
 
+    /// Start debugging the test with the given name, paused before its first instruction.
+    #[cfg(feature = "debugger")]
+    pub fn debug(&self, test_name: &str, run_opts: &RunOpts) -> anyhow::Result<DebugSession> {
+        let pkgs: Vec<&PackageTests> = match self {
+            BuiltTests::Package(pkg) => vec![pkg],
+            BuiltTests::Workspace(workspace) => workspace.iter().collect(),
+        };
+        let mut tests = pkgs.into_iter().flat_map(|pkg| {
+            pkg.built_pkg_with_tests()
+                .bytecode
+                .entries
+                .iter()
+                .filter(move |entry| entry.finalized.fn_name == test_name)
+                .filter_map(move |entry| entry.kind.test().map(|test| (pkg, entry, test)))
+        });
+        let (pkg, entry, test_entry) = tests
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no test named `{test_name}`"))?;
+        if tests.next().is_some() {
+            anyhow::bail!("more than one package has a test named `{test_name}`");
+        }
+        pkg.debug_test(entry, test_entry, run_opts)
+    }
+
     /// Run all built tests, return the result.
     pub fn run(
         self,
         test_runner_count: TestRunnerCount,
         test_filter: Option<TestFilter>,
+        run_opts: RunOpts,
     ) -> anyhow::Result<Tested> {
+        if run_opts.coverage && !cfg!(feature = "coverage") {
+            anyhow::bail!("collecting coverage requires the `coverage` feature of forc-test");
+        }
         let test_runners = match test_runner_count {
             TestRunnerCount::Manual(runner_count) => rayon::ThreadPoolBuilder::new()
                 .num_threads(runner_count)
                 .build(),
             TestRunnerCount::Auto => rayon::ThreadPoolBuilder::new().build(),
         }?;
-        run_tests(self, &test_runners, test_filter)
+        run_tests(self, &test_runners, test_filter, &run_opts)
     }
 }
 
@@ -667,11 +1052,9 @@ fn deployment_transaction(
     let mut storage_slots = built_pkg.storage_slots.clone();
     storage_slots.sort();
     let bytecode = &without_tests_bytecode.bytes;
-    let contract = tx::Contract::from(bytecode.clone());
-    let root = contract.root();
     let state_root = tx::Contract::initial_state_root(storage_slots.iter());
     let salt = tx::Salt::zeroed();
-    let contract_id = contract.id(&salt, &root, &state_root);
+    let contract_id = contract_id(built_pkg, without_tests_bytecode);
 
     // Create the deployment transaction.
     let mut rng = rand::rngs::StdRng::seed_from_u64(TEST_METADATA_SEED);
@@ -694,6 +1077,19 @@ fn deployment_transaction(
     (contract_id, tx)
 }
 
+/// The id of the provided contract once deployed for tests, i.e. with a zeroed salt.
+fn contract_id(
+    built_pkg: &pkg::BuiltPackage,
+    without_tests_bytecode: &pkg::BuiltPackageBytecode,
+) -> tx::ContractId {
+    let mut storage_slots = built_pkg.storage_slots.clone();
+    storage_slots.sort();
+    let contract = tx::Contract::from(without_tests_bytecode.bytes.clone());
+    let root = contract.root();
+    let state_root = tx::Contract::initial_state_root(storage_slots.iter());
+    contract.id(&tx::Salt::zeroed(), &root, &state_root)
+}
+
 /// Build the given package and run its tests after applying the filter provided.
 ///
 /// Returns the result of test execution.
@@ -701,22 +1097,27 @@ fn run_tests(
     built: BuiltTests,
     test_runners: &rayon::ThreadPool,
     test_filter: Option<TestFilter>,
+    run_opts: &RunOpts,
 ) -> anyhow::Result<Tested> {
     match built {
         BuiltTests::Package(pkg) => {
-            let tested_pkg = pkg.run_tests(test_runners, test_filter.as_ref())?;
+            let tested_pkg = pkg.run_tests(test_runners, test_filter.as_ref(), run_opts)?;
             Ok(Tested::Package(Box::new(tested_pkg)))
         }
         BuiltTests::Workspace(workspace) => {
             let tested_pkgs = workspace
                 .into_iter()
-                .map(|pkg| pkg.run_tests(test_runners, test_filter.as_ref()))
+                .map(|pkg| pkg.run_tests(test_runners, test_filter.as_ref(), run_opts))
                 .collect::<anyhow::Result<Vec<TestedPackage>>>()?;
             Ok(Tested::Workspace(tested_pkgs))
         }
     }
 }
 
+/// The instruction offset of the start of a program, following the data section setup.
+// TODO: Standardize this or add metadata to bytecode.
+const PROGRAM_START_INST_OFFSET: u32 = 6;
+
 /// Given some bytecode and an instruction offset for some test's desired entry point, patch the
 /// bytecode with a `JI` (jump) instruction to jump to the desired test.
 ///
@@ -735,8 +1136,6 @@ fn run_tests(
 /// [6] <first-entry-point>           ; This is where we want to jump from to our test code!
 /// ```
 fn patch_test_bytecode(bytecode: &[u8], test_offset: u32) -> std::borrow::Cow<[u8]> {
-    // TODO: Standardize this or add metadata to bytecode.
-    const PROGRAM_START_INST_OFFSET: u32 = 6;
     const PROGRAM_START_BYTE_OFFSET: usize = PROGRAM_START_INST_OFFSET as usize * Instruction::SIZE;
 
     // If our desired entry point is the program start, no need to jump.
@@ -754,23 +1153,24 @@ fn patch_test_bytecode(bytecode: &[u8], test_offset: u32) -> std::borrow::Cow<[u
     std::borrow::Cow::Owned(patched)
 }
 
-// Execute the test whose entry point is at the given instruction offset as if it were a script.
-fn exec_test(
+/// The instruction offset of a test's entry point.
+fn test_offset(entry: &pkg::PkgEntry) -> u32 {
+    u32::try_from(entry.finalized.imm).expect("test instruction offset out of range")
+}
+
+/// Create the transaction which executes the test whose entry point is at the given instruction
+/// offset as if it were a script, passing it the given script data.
+fn test_transaction(
     bytecode: &[u8],
     test_offset: u32,
-    test_setup: TestSetup,
-) -> (
-    vm::state::ProgramState,
-    std::time::Duration,
-    Vec<fuel_tx::Receipt>,
-) {
-    let storage = test_setup.storage().clone();
-
+    test_setup: &TestSetup,
+    chain_state: &ChainState,
+    script_input_data: Vec<u8>,
+) -> vm::checked_transaction::Checked<tx::Script> {
     // Patch the bytecode to jump to the relevant test.
     let bytecode = patch_test_bytecode(bytecode, test_offset).into_owned();
 
     // Create a transaction to execute the test function.
-    let script_input_data = vec![];
     let mut rng = rand::rngs::StdRng::seed_from_u64(TEST_METADATA_SEED);
 
     // Prepare the transaction metadata.
@@ -782,20 +1182,47 @@ fn exec_test(
     let tx_pointer = rng.gen();
     let block_height = (u32::MAX >> 1).into();
 
-    let params = tx::ConsensusParameters::default();
     let mut tx = tx::TransactionBuilder::script(bytecode, script_input_data)
-        .add_unsigned_coin_input(
-            secret_key,
-            utxo_id,
-            amount,
-            asset_id,
-            tx_pointer,
-            0u32.into(),
-        )
         .gas_limit(tx::ConsensusParameters::DEFAULT.max_gas_per_tx)
         .maturity(maturity)
         .clone();
-    let mut output_index = 1;
+    // The coin paying for the test comes first, followed by the coins of the chain state.
+    let mut coins = vec![(utxo_id, asset_id, amount)];
+    for (coin_asset_id, coin_amount) in &chain_state.coins {
+        coins.push((rng.gen(), *coin_asset_id, *coin_amount));
+    }
+    // Contract inputs follow the coin inputs.
+    let mut output_index = u8::try_from(coins.len()).expect("too many coins for a test");
+    match chain_state.caller {
+        // Coins owned by an arbitrary address can't be signed for, so they are all spent with an
+        // empty witness, and the signatures of the transaction are left unchecked below.
+        Some(caller) => {
+            tx.add_witness(tx::Witness::default());
+            for (utxo_id, asset_id, amount) in coins {
+                tx.add_input(tx::Input::coin_signed(
+                    utxo_id,
+                    caller,
+                    amount,
+                    asset_id,
+                    tx_pointer,
+                    0,
+                    0u32.into(),
+                ));
+            }
+        }
+        None => {
+            for (utxo_id, asset_id, amount) in coins {
+                tx.add_unsigned_coin_input(
+                    secret_key,
+                    utxo_id,
+                    amount,
+                    asset_id,
+                    tx_pointer,
+                    0u32.into(),
+                );
+            }
+        }
+    }
     // Insert contract ids into tx input
     for contract_id in test_setup.contract_ids() {
         tx.add_input(tx::Input::contract(
@@ -812,8 +1239,39 @@ fn exec_test(
         });
         output_index += 1;
     }
-    let tx = tx.finalize_checked(block_height, &GasCosts::default());
+    match chain_state.caller {
+        Some(_) => tx.finalize_checked_basic(block_height),
+        None => tx.finalize_checked(block_height, &GasCosts::default()),
+    }
+}
 
+// Execute the test whose entry point is at the given instruction offset as if it were a script,
+// passing it the given script data.  If `coverage` is set, the instructions executed are returned
+// along with the result, as recorded by the coverage profiler.
+#[cfg_attr(not(feature = "coverage"), allow(unused_variables))]
+fn exec_test(
+    bytecode: &[u8],
+    test_offset: u32,
+    test_setup: TestSetup,
+    chain_state: &ChainState,
+    script_input_data: Vec<u8>,
+    coverage: bool,
+) -> (
+    vm::state::ProgramState,
+    std::time::Duration,
+    Vec<fuel_tx::Receipt>,
+    Option<CoverageProfilingData>,
+) {
+    let storage = test_setup.storage().clone();
+    let tx = test_transaction(
+        bytecode,
+        test_offset,
+        &test_setup,
+        chain_state,
+        script_input_data,
+    );
+
+    let params = tx::ConsensusParameters::default();
     let mut interpreter =
         vm::interpreter::Interpreter::with_storage(storage, params, GasCosts::default());
 
@@ -823,8 +1281,12 @@ fn exec_test(
     let duration = start.elapsed();
     let state = *transition.state();
     let receipts = transition.receipts().to_vec();
+    #[cfg(feature = "coverage")]
+    let profiled = coverage.then(|| interpreter.profiler().data().coverage().clone());
+    #[cfg(not(feature = "coverage"))]
+    let profiled = None;
 
-    (state, duration, receipts)
+    (state, duration, receipts, profiled)
 }
 
 #[cfg(test)]
@@ -838,18 +1300,27 @@ mod tests {
     const TEST_DATA_FOLDER_NAME: &str = "test_data";
     /// Name of the library package in the "CARGO_MANIFEST_DIR/TEST_DATA_FOLDER_NAME".
     const TEST_LIBRARY_PACKAGE_NAME: &str = "test_library";
-
-    /// Build the tests in the test library located at
-    /// "CARGO_MANIFEST_DIR/TEST_DATA_FOLDER_NAME/TEST_LIBRARY_PACKAGE_NAME".
-    fn test_library_built_tests() -> anyhow::Result<BuiltTests> {
-        let cargo_manifest_dir = env!("CARGO_MANIFEST_DIR");
-        let library_package_dir = PathBuf::from(cargo_manifest_dir)
+    /// Name of the contract package in the "CARGO_MANIFEST_DIR/TEST_DATA_FOLDER_NAME".
+    const TEST_CONTRACT_PACKAGE_NAME: &str = "test_contract";
+    /// Name of the library package with doc tests in the "CARGO_MANIFEST_DIR/TEST_DATA_FOLDER_NAME".
+    const TEST_DOC_LIBRARY_PACKAGE_NAME: &str = "test_doc_library";
+
+    /// The directory of the package with the given name located in
+    /// "CARGO_MANIFEST_DIR/TEST_DATA_FOLDER_NAME".
+    fn test_data_package_dir(package_name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .join(TEST_DATA_FOLDER_NAME)
-            .join(TEST_LIBRARY_PACKAGE_NAME);
-        let library_package_dir_string = library_package_dir.to_string_lossy().to_string();
+            .join(package_name)
+    }
+
+    /// Build the tests in the package with the given name located in
+    /// "CARGO_MANIFEST_DIR/TEST_DATA_FOLDER_NAME".
+    fn test_data_built_tests(package_name: &str) -> anyhow::Result<BuiltTests> {
+        let package_dir = test_data_package_dir(package_name);
+        let package_dir_string = package_dir.to_string_lossy().to_string();
         let build_options = Opts {
             pkg: forc_pkg::PkgOpts {
-                path: Some(library_package_dir_string),
+                path: Some(package_dir_string),
                 ..Default::default()
             },
             ..Default::default()
@@ -857,12 +1328,18 @@ mod tests {
         build(build_options)
     }
 
+    /// Build the tests in the test library located at
+    /// "CARGO_MANIFEST_DIR/TEST_DATA_FOLDER_NAME/TEST_LIBRARY_PACKAGE_NAME".
+    fn test_library_built_tests() -> anyhow::Result<BuiltTests> {
+        test_data_built_tests(TEST_LIBRARY_PACKAGE_NAME)
+    }
+
     fn test_library_test_results(
         test_filter: Option<TestFilter>,
     ) -> anyhow::Result<Vec<TestResult>> {
         let built_tests = test_library_built_tests()?;
         let test_runner_count = crate::TestRunnerCount::Auto;
-        let tested = built_tests.run(test_runner_count, test_filter)?;
+        let tested = built_tests.run(test_runner_count, test_filter, Default::default())?;
         match tested {
             crate::Tested::Package(tested_pkg) => Ok(tested_pkg.tests),
             crate::Tested::Workspace(_) => {
@@ -949,4 +1426,111 @@ mod tests {
 
         assert_eq!(tested_package_test_count, 2)
     }
+
+    #[cfg(feature = "coverage")]
+    #[test]
+    fn test_coverage() {
+        let built_tests = test_data_built_tests(TEST_CONTRACT_PACKAGE_NAME).unwrap();
+        let run_opts = crate::RunOpts {
+            coverage: true,
+            ..Default::default()
+        };
+        let tested = built_tests
+            .run(crate::TestRunnerCount::Auto, None, run_opts)
+            .unwrap();
+        let tested_pkg = match tested {
+            crate::Tested::Package(tested_pkg) => tested_pkg,
+            crate::Tested::Workspace(_) => {
+                unreachable!("test_contract is a package, not a workspace.")
+            }
+        };
+        let coverage = crate::Coverage::from_tested_pkg(&tested_pkg).unwrap();
+        let (path, file) = coverage.files.iter().next().unwrap();
+        assert_eq!(coverage.files.len(), 1);
+        assert!(path.ends_with("src/main.sw"));
+        // Line 19 is the body of the ABI method, only executed within the deployed contract.
+        let lines = [(19, 1), (25, 1), (29, 1), (31, 1)].into_iter().collect();
+        assert_eq!(file.lines, lines);
+    }
+
+    #[cfg(feature = "debugger")]
+    #[test]
+    fn test_debug_breakpoint() {
+        let built_tests = test_data_built_tests(TEST_CONTRACT_PACKAGE_NAME).unwrap();
+        let mut session = built_tests
+            .debug("test_sum_up_to", &Default::default())
+            .unwrap();
+        assert!(!session.finished());
+
+        // The line calling into the contract.
+        let breakpoint = session.add_breakpoint(None, 31).unwrap();
+        assert_eq!(breakpoint.line, 31);
+        assert!(session.add_breakpoint(None, 2).is_err());
+
+        let event = session.continue_execution().unwrap();
+        assert_eq!(event, crate::DebugEvent::Breakpoint(breakpoint));
+        assert_eq!(
+            session.continue_execution().unwrap(),
+            crate::DebugEvent::Finished
+        );
+        assert!(session.finished());
+        assert!(matches!(
+            session.state(),
+            fuel_vm::state::ProgramState::Return(_)
+        ));
+    }
+
+    #[cfg(feature = "debugger")]
+    #[test]
+    fn test_debug_unknown_test() {
+        let built_tests = test_library_built_tests().unwrap();
+        assert!(built_tests
+            .debug("test_missing", &Default::default())
+            .is_err());
+    }
+
+    #[test]
+    fn test_doc_tests() {
+        let package_dir = test_data_package_dir(TEST_DOC_LIBRARY_PACKAGE_NAME);
+        let manifest = forc_pkg::PackageManifestFile::from_dir(&package_dir).unwrap();
+        let doc_tests = crate::doc_tests(&manifest).unwrap();
+        let names = doc_tests
+            .iter()
+            .map(|doc_test| doc_test.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "lib_line_8",
+                "lib_line_14",
+                "lib_line_20",
+                "sub_dir_lib_line_3",
+                "sub_dir_lib_line_3_2",
+            ],
+            names
+        );
+
+        let doc_tests_dir = package_dir.join("out").join(crate::DOC_TESTS_DIR_NAME);
+        crate::write_doc_test_pkg(&manifest, &doc_tests, &doc_tests_dir).unwrap();
+        let test_results = test_data_built_tests(&format!(
+            "{TEST_DOC_LIBRARY_PACKAGE_NAME}/out/{}",
+            crate::DOC_TESTS_DIR_NAME
+        ))
+        .unwrap()
+        .run(crate::TestRunnerCount::Auto, None, Default::default())
+        .unwrap();
+        let test_results = match test_results {
+            crate::Tested::Package(tested_pkg) => tested_pkg.tests,
+            crate::Tested::Workspace(_) => unreachable!("doc tests are run in a package"),
+        };
+        // The `no_run` code block is only compiled.
+        assert_eq!(4, test_results.len());
+        assert!(test_results.iter().all(TestResult::passed));
+    }
+
+    #[test]
+    fn test_doc_tests_of_contract() {
+        let package_dir = test_data_package_dir(TEST_CONTRACT_PACKAGE_NAME);
+        let manifest = forc_pkg::PackageManifestFile::from_dir(&package_dir).unwrap();
+        assert!(crate::doc_tests(&manifest).is_err());
+    }
 }
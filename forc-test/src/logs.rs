@@ -0,0 +1,97 @@
+//! Decoding of the values logged by tests, using the logged types of the package's JSON ABI.
+
+use crate::abi::ParamType;
+use forc_pkg::program_abi::ProgramABI;
+use fuel_tx::{ContractId, Receipt};
+use std::collections::HashMap;
+use sway_core::asm_generation::ProgramABI as BuiltProgramABI;
+
+/// The type of the values logged with each log id.
+type LoggedTypes = HashMap<u64, ParamType>;
+
+/// Decodes `Log` and `LogData` receipts into the values they log, formatted using Sway syntax.
+///
+/// Log ids are only unique within a single program, so receipts are decoded using the logged
+/// types of the program which emitted them: those of the test itself, or those of the deployed
+/// contract it called.
+#[derive(Debug, Default)]
+pub(crate) struct LogDecoder {
+    /// The logged types of the tests.
+    test_logged_types: LoggedTypes,
+    /// The logged types of each deployed contract.
+    contract_logged_types: HashMap<ContractId, LoggedTypes>,
+}
+
+impl LogDecoder {
+    /// Create a decoder for the values logged by tests built with the given ABI, and by the
+    /// deployed contracts with the given ids and ABIs.
+    pub(crate) fn new<'a>(
+        test_abi: &BuiltProgramABI,
+        contract_abis: impl IntoIterator<Item = (ContractId, &'a BuiltProgramABI)>,
+    ) -> Self {
+        let contract_logged_types = contract_abis
+            .into_iter()
+            .map(|(contract_id, abi)| (contract_id, logged_types(abi)))
+            .collect();
+        Self {
+            test_logged_types: logged_types(test_abi),
+            contract_logged_types,
+        }
+    }
+
+    /// Decode the value logged by a receipt, or `None` if the receipt doesn't log a value.
+    pub(crate) fn decode(&self, receipt: &Receipt) -> Option<String> {
+        let decoded = match receipt {
+            Receipt::Log { id, ra, rb, .. } => self
+                .decode_bytes(id, *rb, &ra.to_be_bytes())
+                .unwrap_or_else(|| ra.to_string()),
+            Receipt::LogData { id, rb, .. } => {
+                let data = receipt.data().unwrap_or_default();
+                self.decode_bytes(id, *rb, data).unwrap_or_else(|| {
+                    let hex = data.iter().map(|b| format!("{b:02x}")).collect::<String>();
+                    format!("0x{hex}")
+                })
+            }
+            _ => return None,
+        };
+        Some(decoded)
+    }
+
+    fn decode_bytes(&self, id: &ContractId, log_id: u64, mut bytes: &[u8]) -> Option<String> {
+        // Values logged by the test itself are emitted with a zeroed contract id.
+        let logged_types = match *id == ContractId::zeroed() {
+            true => &self.test_logged_types,
+            false => self.contract_logged_types.get(id)?,
+        };
+        let ty = logged_types.get(&log_id)?;
+        let value = ty.decode(&mut bytes)?;
+        Some(ty.display(&value))
+    }
+}
+
+/// The logged types of a built program.  Programs built for other targets than the Fuel VM have
+/// none.
+fn logged_types(abi: &BuiltProgramABI) -> LoggedTypes {
+    match abi {
+        BuiltProgramABI::Fuel(abi) => logged_types_from_abi(abi),
+        BuiltProgramABI::Evm(_) | BuiltProgramABI::MidenVM(_) => LoggedTypes::default(),
+    }
+}
+
+/// The logged types of the given JSON ABI.  Logged types which can't be decoded are left out, and
+/// values logged with them are formatted as raw data.
+fn logged_types_from_abi(abi: &ProgramABI) -> LoggedTypes {
+    let types = abi
+        .types
+        .iter()
+        .map(|decl| (decl.type_id, decl))
+        .collect::<HashMap<_, _>>();
+    abi.logged_types
+        .iter()
+        .flatten()
+        .filter_map(|logged_type| {
+            let ty = ParamType::resolve(&types, &logged_type.application, &HashMap::new()).ok()?;
+            Some((logged_type.log_id, ty))
+        })
+        .collect()
+}
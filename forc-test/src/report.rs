@@ -0,0 +1,255 @@
+//! Machine-readable reports of test results, for CI dashboards and other tools.
+//!
+//! Reports can be rendered as JSON, whose schema is versioned by `REPORT_SCHEMA_VERSION`, or as
+//! JUnit XML.
+
+use crate::{TestResult, TestedPackage};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// The version of the schema of JSON reports.  It is incremented whenever a field is removed or
+/// changes meaning, while fields may be added without changing it.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// The results of testing a package or a workspace.
+#[derive(Clone, Debug, Serialize)]
+pub struct TestReport {
+    pub schema_version: u32,
+    pub packages: Vec<PackageReport>,
+}
+
+/// The results of testing a single package.
+#[derive(Clone, Debug, Serialize)]
+pub struct PackageReport {
+    pub name: String,
+    pub passed: usize,
+    pub failed: usize,
+    pub tests: Vec<TestCaseReport>,
+}
+
+/// The result of a single test.
+#[derive(Clone, Debug, Serialize)]
+pub struct TestCaseReport {
+    pub name: String,
+    pub package: String,
+    pub file_path: String,
+    /// The 1-based line of the function declaring the test.
+    pub line: usize,
+    pub span: SpanReport,
+    pub passed: bool,
+    pub duration_ns: u64,
+    pub gas_used: u64,
+    pub revert_code: Option<u64>,
+    pub decoded_logs: Vec<String>,
+    pub unmet_expectations: Vec<String>,
+    /// Only present for parameterized tests.
+    pub fuzz: Option<FuzzReport>,
+}
+
+/// The byte range of the function declaring a test within its source file.
+#[derive(Clone, Debug, Serialize)]
+pub struct SpanReport {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// The outcome of a parameterized test.
+#[derive(Clone, Debug, Serialize)]
+pub struct FuzzReport {
+    pub runs: usize,
+    pub seed: u64,
+    pub counterexample: Option<String>,
+}
+
+impl TestReport {
+    /// Create a report of the results of the given tested packages.
+    pub fn from_tested_pkgs(tested_pkgs: &[TestedPackage]) -> anyhow::Result<Self> {
+        let packages = tested_pkgs
+            .iter()
+            .map(PackageReport::from_tested_pkg)
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self {
+            schema_version: REPORT_SCHEMA_VERSION,
+            packages,
+        })
+    }
+
+    /// Render this report as JSON.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Render this report as JUnit XML, with a `testsuite` per package.
+    pub fn to_junit(&self) -> String {
+        let secs = |duration_ns: u64| duration_ns as f64 / 1e9;
+        let tests = |pkg: &PackageReport| pkg.passed + pkg.failed;
+        let duration_ns =
+            |pkg: &PackageReport| -> u64 { pkg.tests.iter().map(|test| test.duration_ns).sum() };
+
+        let mut xml = String::new();
+        writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+        writeln!(
+            xml,
+            r#"<testsuites tests="{}" failures="{}" time="{:.6}">"#,
+            self.packages.iter().map(tests).sum::<usize>(),
+            self.packages.iter().map(|pkg| pkg.failed).sum::<usize>(),
+            secs(self.packages.iter().map(duration_ns).sum()),
+        )
+        .unwrap();
+        for pkg in &self.packages {
+            writeln!(
+                xml,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" time="{:.6}">"#,
+                xml_escape(&pkg.name),
+                tests(pkg),
+                pkg.failed,
+                secs(duration_ns(pkg)),
+            )
+            .unwrap();
+            for test in &pkg.tests {
+                writeln!(
+                    xml,
+                    r#"    <testcase name="{}" classname="{}" file="{}" line="{}" time="{:.6}">"#,
+                    xml_escape(&test.name),
+                    xml_escape(&test.package),
+                    xml_escape(&test.file_path),
+                    test.line,
+                    secs(test.duration_ns),
+                )
+                .unwrap();
+                if !test.passed {
+                    let message = match test.revert_code {
+                        Some(revert_code) => format!("reverted with code {revert_code:x}"),
+                        None => "test failed".to_string(),
+                    };
+                    let mut details = test.unmet_expectations.clone();
+                    if let Some(counterexample) = test
+                        .fuzz
+                        .as_ref()
+                        .and_then(|fuzz| fuzz.counterexample.as_ref())
+                    {
+                        details.push(format!("minimal failing input: {counterexample}"));
+                    }
+                    writeln!(
+                        xml,
+                        r#"      <failure message="{}">{}</failure>"#,
+                        xml_escape(&message),
+                        xml_escape(&details.join("\n")),
+                    )
+                    .unwrap();
+                }
+                if !test.decoded_logs.is_empty() {
+                    writeln!(
+                        xml,
+                        "      <system-out>{}</system-out>",
+                        xml_escape(&test.decoded_logs.join("\n"))
+                    )
+                    .unwrap();
+                }
+                writeln!(xml, "    </testcase>").unwrap();
+            }
+            writeln!(xml, "  </testsuite>").unwrap();
+        }
+        writeln!(xml, "</testsuites>").unwrap();
+        xml
+    }
+}
+
+impl PackageReport {
+    fn from_tested_pkg(tested_pkg: &TestedPackage) -> anyhow::Result<Self> {
+        let name = tested_pkg.built.descriptor.name.clone();
+        let tests = tested_pkg
+            .tests
+            .iter()
+            .map(|test| TestCaseReport::from_test_result(test, &name))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let passed = tests.iter().filter(|test| test.passed).count();
+        Ok(Self {
+            failed: tests.len() - passed,
+            name,
+            passed,
+            tests,
+        })
+    }
+}
+
+impl TestCaseReport {
+    fn from_test_result(test: &TestResult, package: &str) -> anyhow::Result<Self> {
+        let details = test.details()?;
+        Ok(Self {
+            name: test.name.clone(),
+            package: package.to_string(),
+            file_path: test.file_path.display().to_string(),
+            line: details.line_number + 1,
+            span: SpanReport {
+                start: test.span.start(),
+                end: test.span.end(),
+            },
+            passed: test.passed(),
+            duration_ns: u64::try_from(test.duration.as_nanos()).unwrap_or(u64::MAX),
+            gas_used: test.gas_used,
+            revert_code: test.revert_code(),
+            decoded_logs: test.decoded_logs.clone(),
+            unmet_expectations: test.unmet_expectations(),
+            fuzz: test.fuzz.as_ref().map(|fuzz| FuzzReport {
+                runs: fuzz.runs,
+                seed: fuzz.seed,
+                counterexample: fuzz.counterexample.clone(),
+            }),
+        })
+    }
+}
+
+/// Escape the characters with special meaning in XML text and attribute values.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn junit_report() {
+        let test = |name: &str, passed| TestCaseReport {
+            name: name.to_string(),
+            package: "my_pkg".to_string(),
+            file_path: "/my_pkg/src/main.sw".to_string(),
+            line: 3,
+            span: SpanReport { start: 10, end: 20 },
+            passed,
+            duration_ns: 1_500_000,
+            gas_used: 42,
+            revert_code: (!passed).then_some(0xffff_ffff_ffff_0000),
+            decoded_logs: vec!["Error::NotOwner".to_string()],
+            unmet_expectations: vec![],
+            fuzz: None,
+        };
+        let report = TestReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            packages: vec![PackageReport {
+                name: "my_pkg".to_string(),
+                passed: 1,
+                failed: 1,
+                tests: vec![test("test_ok", true), test("test_<fail>", false)],
+            }],
+        };
+        let junit = report.to_junit();
+        assert!(junit.contains(r#"<testsuites tests="2" failures="1" time="0.003000">"#));
+        assert!(junit.contains(r#"<testcase name="test_&lt;fail&gt;" classname="my_pkg""#));
+        assert!(junit.contains(r#"<failure message="reverted with code ffffffffffff0000">"#));
+        assert_eq!(junit.matches("<failure").count(), 1);
+        assert!(report.to_json().unwrap().contains(r#""schema_version": 1"#));
+    }
+}
@@ -0,0 +1,32 @@
+contract;
+
+abi Sum {
+    fn sum_up_to(n: u64) -> u64;
+}
+
+fn sum(n: u64) -> u64 {
+    let mut total = 0;
+    let mut i = 0;
+    while i < n {
+        total += i;
+        i += 1;
+    }
+    total
+}
+
+impl Sum for Contract {
+    fn sum_up_to(n: u64) -> u64 {
+        sum(n)
+    }
+}
+
+#[test]
+fn test_sum() {
+    assert(sum(4) == 6);
+}
+
+#[test]
+fn test_sum_up_to() {
+    let caller = abi(Sum, CONTRACT_ID);
+    assert(caller.sum_up_to(4) == 6);
+}
@@ -0,0 +1,27 @@
+library;
+
+pub mod sub_dir;
+pub mod sub_dir_lib;
+
+/// Returns the square of `x`.
+///
+/// ```sway
+/// use test_doc_library::square;
+///
+/// assert(square(3) == 9);
+/// ```
+///
+/// ```sway,should_revert
+/// use test_doc_library::square;
+///
+/// require(square(3) == 10, 0);
+/// ```
+///
+/// ```no_run
+/// use test_doc_library::square;
+///
+/// let _ = square(u64::max());
+/// ```
+pub fn square(x: u64) -> u64 {
+    x * x
+}
@@ -0,0 +1,10 @@
+library;
+
+/// ```
+/// use test_doc_library::sub_dir::lib::one;
+///
+/// assert(one() == 1);
+/// ```
+pub fn one() -> u64 {
+    1
+}
@@ -0,0 +1,10 @@
+library;
+
+/// ```
+/// use test_doc_library::sub_dir_lib::two;
+///
+/// assert(two() == 2);
+/// ```
+pub fn two() -> u64 {
+    2
+}
@@ -174,14 +174,30 @@ pub fn format_log_receipts(receipts: &[fuel_tx::Receipt], pretty_print: bool) ->
 }
 
 /// Continually go down in the file tree until a Forc manifest file is found.
+///
+/// The build output directory of the package at `starter_path` is skipped, as the packages forc
+/// generates, e.g. to run doc tests, are written there.
 pub fn find_nested_manifest_dir(starter_path: &Path) -> Option<PathBuf> {
-    find_nested_dir_with_file(starter_path, constants::MANIFEST_FILE_NAME)
+    let output_dir = default_output_directory(starter_path);
+    find_nested_dir_with_file_in(starter_path, constants::MANIFEST_FILE_NAME, |dir| {
+        dir != output_dir
+    })
 }
 
 /// Continually go down in the file tree until a specified file is found.
 ///
 /// Starts the search from child dirs of `starter_path`.
 pub fn find_nested_dir_with_file(starter_path: &Path, file_name: &str) -> Option<PathBuf> {
+    find_nested_dir_with_file_in(starter_path, file_name, |_| true)
+}
+
+/// Continually go down in the file tree until a specified file is found, only within the dirs for
+/// which `search_dir` returns true.
+fn find_nested_dir_with_file_in(
+    starter_path: &Path,
+    file_name: &str,
+    search_dir: impl Fn(&Path) -> bool,
+) -> Option<PathBuf> {
     use walkdir::WalkDir;
     let starter_dir = if starter_path.is_dir() {
         starter_path
@@ -190,6 +206,7 @@ pub fn find_nested_dir_with_file(starter_path: &Path, file_name: &str) -> Option
     };
     WalkDir::new(starter_path)
         .into_iter()
+        .filter_entry(|entry| !entry.file_type().is_dir() || search_dir(entry.path()))
         .filter_map(|e| e.ok())
         .filter(|entry| entry.path() != starter_dir.join(file_name))
         .filter(|entry| entry.file_name().to_string_lossy() == file_name)
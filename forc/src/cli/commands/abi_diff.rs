@@ -0,0 +1,42 @@
+use crate::{
+    cli::shared::{BuildProfile, Pkg},
+    ops::forc_abi_diff,
+};
+use clap::Parser;
+use forc_util::{ForcError, ForcResult};
+use std::path::PathBuf;
+
+/// Compare the JSON ABI and storage slots of a program to those of a previous version, and report
+/// the changes which break compatibility with the clients of the previous version.
+///
+/// Breaking changes are removed or renamed functions, changed parameter or return types, changed
+/// struct fields, removed, renamed or reordered enum variants, variants added to returned enums,
+/// and storage slots which are no longer used, e.g. because a storage field was removed or
+/// reordered. The command fails if there are any.
+///
+/// Storage fields are compared through the keys of their slots, so a field changing type in place
+/// and the fields of types such as `StorageMap`, which have no initial slot, aren't checked.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The JSON ABI of the previous version, e.g. `out/debug/<project-name>-abi.json` as it was
+    /// built then.
+    pub old_abi: PathBuf,
+    /// The storage slots of the previous version, e.g.
+    /// `out/debug/<project-name>-storage_slots.json` as it was built then.
+    #[clap(long)]
+    pub old_storage_slots: Option<PathBuf>,
+    #[clap(flatten)]
+    pub pkg: Pkg,
+    #[clap(flatten)]
+    pub build_profile: BuildProfile,
+}
+
+pub(crate) fn exec(cmd: Command) -> ForcResult<()> {
+    let changes = forc_abi_diff::abi_diff(cmd)?;
+    forc_abi_diff::print_changes(&changes);
+    if changes.iter().any(|change| change.breaking) {
+        let forc_error: ForcError = "The ABI has breaking changes.".into();
+        return Err(forc_error);
+    }
+    Ok(())
+}
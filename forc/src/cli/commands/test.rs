@@ -2,8 +2,15 @@ use crate::cli;
 use ansi_term::Colour;
 use clap::Parser;
 use forc_pkg as pkg;
-use forc_test::{TestFilter, TestRunnerCount, TestedPackage};
-use forc_util::{format_log_receipts, ForcError, ForcResult};
+use forc_test::{
+    Coverage, FuzzOpts, GasSnapshot, RunOpts, TestFilter, TestReport, TestResult, TestRunnerCount,
+    TestedPackage, DOC_TESTS_DIR_NAME,
+};
+use forc_tracing::println_yellow_err;
+use forc_util::{default_output_directory, format_log_receipts, ForcError, ForcResult};
+use std::path::PathBuf;
+use term_table::row::Row;
+use term_table::table_cell::{Alignment, TableCell};
 use tracing::info;
 
 /// Run the Sway unit tests for the current project.
@@ -23,6 +30,18 @@ use tracing::info;
 /// Upon successful compilation, test scripts are executed to their completion. A test is
 /// considered a failure in the case that a revert (`rvrt`) instruction is encountered during
 /// execution. Otherwise, it is considered a success.
+///
+/// Unit tests decorated with the `#[test(fuzz)]` attribute may declare parameters. Such tests are
+/// run many times with randomly generated arguments, and the smallest failing arguments found are
+/// reported.
+///
+/// Unit tests decorated with the `#[test(ignore)]` attribute are skipped unless
+/// `--include-ignored` is specified.
+///
+/// A single test may be stepped through interactively with `--debug <name>`.
+///
+/// With `--doc`, the Sway code blocks of the doc comments of the package are run as tests instead,
+/// so that the examples of its documentation keep compiling and passing.
 #[derive(Debug, Parser)]
 pub struct Command {
     #[clap(flatten)]
@@ -38,6 +57,61 @@ pub struct Command {
     /// Number of threads to utilize when running the tests. By default, this is the number of
     /// threads available in your system.
     pub test_threads: Option<usize>,
+    #[clap(long, default_value_t = forc_test::DEFAULT_FUZZ_RUNS)]
+    /// Number of times to run each `#[test(fuzz)]` test which doesn't specify its own number of
+    /// runs.
+    pub fuzz_runs: usize,
+    #[clap(long, default_value_t = forc_test::DEFAULT_FUZZ_SEED)]
+    /// Seed for the arguments generated for `#[test(fuzz)]` tests. Runs with the same seed
+    /// generate the same arguments.
+    pub fuzz_seed: u64,
+    #[clap(flatten)]
+    pub gas_snapshot: GasSnapshotOpts,
+    #[clap(long)]
+    /// Collect the lines of the package executed by the tests. A summary is printed for each
+    /// source file, and an LCOV report is written to `out/coverage/lcov.info`.
+    pub coverage: bool,
+    #[clap(long)]
+    /// Also run the tests marked with `#[test(ignore)]`.
+    pub include_ignored: bool,
+    #[clap(long, value_enum, default_value_t = TestOutputFormat::Human)]
+    /// The format to report test results in. The `json` and `junit` formats print a report of all
+    /// tests to stdout once they have run, in place of the human-readable output.
+    pub format: TestOutputFormat,
+    #[clap(long, value_name = "TEST", conflicts_with_all = &["coverage", "format"])]
+    /// Run the test with the given name in an interactive debugger, which steps through it by
+    /// instruction or source line and prints its registers, memory and locals.
+    pub debug: Option<String>,
+    #[clap(long)]
+    /// Run the Sway code blocks of the doc comments of the package as tests, in place of its unit
+    /// tests. Blocks marked `ignore` are skipped, those marked `no_run` are only compiled, and
+    /// those marked `should_revert` pass if they revert.
+    pub doc: bool,
+}
+
+/// The formats `forc test` can report test results in.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum TestOutputFormat {
+    #[default]
+    Human,
+    Json,
+    Junit,
+}
+
+/// The set of options for recording and checking the gas used by tests.
+#[derive(Parser, Debug, Clone)]
+pub struct GasSnapshotOpts {
+    /// Write the gas used by each test to a `.gas-snapshot` file next to the package manifest.
+    #[clap(long = "gas-snapshot")]
+    pub write: bool,
+    /// Compare the gas used by each test against the package's `.gas-snapshot` file, failing if
+    /// any test uses more gas than recorded.
+    #[clap(long = "check-gas-snapshot", conflicts_with = "write")]
+    pub check: bool,
+    /// The percentage by which the gas used by a test may grow before `--check-gas-snapshot`
+    /// fails.
+    #[clap(long = "gas-tolerance", default_value_t = 0)]
+    pub tolerance: u64,
 }
 
 /// The set of options provided for controlling output of a test.
@@ -46,9 +120,12 @@ pub struct TestPrintOpts {
     #[clap(long = "pretty-print", short = 'r')]
     /// Pretty-print the logs emiited from tests.
     pub pretty_print: bool,
-    /// Print `Log` and `LogData` receipts for tests.
+    /// Print the values logged by tests, decoded using the logged types of the package's ABI.
     #[clap(long = "logs", short = 'l')]
     pub print_logs: bool,
+    /// Print the `Log` and `LogData` receipts of tests rather than the values they log.
+    #[clap(long = "raw-logs")]
+    pub raw_logs: bool,
 }
 
 pub(crate) fn exec(cmd: Command) -> ForcResult<()> {
@@ -58,51 +135,276 @@ pub(crate) fn exec(cmd: Command) -> ForcResult<()> {
     };
 
     let test_print_opts = cmd.test_print.clone();
+    let gas_snapshot_opts = cmd.gas_snapshot.clone();
     let test_filter_phrase = cmd.filter.clone();
     let test_filter = test_filter_phrase.as_ref().map(|filter_phrase| TestFilter {
         filter_phrase,
         exact_match: cmd.filter_exact,
     });
-    let opts = opts_from_cmd(cmd);
+    let run_opts = RunOpts {
+        fuzz: FuzzOpts {
+            runs: cmd.fuzz_runs,
+            seed: cmd.fuzz_seed,
+        },
+        coverage: cmd.coverage,
+        include_ignored: cmd.include_ignored,
+        chain_state: Default::default(),
+    };
+    let format = cmd.format;
+    let debug = cmd.debug.clone();
+    let doc = cmd.doc;
+    let mut opts = opts_from_cmd(cmd);
+    if format != TestOutputFormat::Human {
+        // Keep the output of the build from getting mixed with the report.
+        opts.pkg.terse = true;
+    }
+    if doc {
+        // The doc tests are run as the unit tests of a package generated next to the build output.
+        let pkg_dir = match &opts.pkg.path {
+            Some(path) => PathBuf::from(path),
+            None => std::env::current_dir()?,
+        };
+        let manifest = pkg::PackageManifestFile::from_dir(&pkg_dir)?;
+        let doc_tests = forc_test::doc_tests(&manifest)?;
+        if doc_tests.is_empty() {
+            info!("   No doc tests found in {}", manifest.project.name);
+            return Ok(());
+        }
+        let doc_tests_dir = default_output_directory(manifest.dir()).join(DOC_TESTS_DIR_NAME);
+        forc_test::write_doc_test_pkg(&manifest, &doc_tests, &doc_tests_dir)?;
+        opts.pkg.path = Some(doc_tests_dir.display().to_string());
+    }
     let built_tests = forc_test::build(opts)?;
+    if let Some(test_name) = debug {
+        #[cfg(feature = "debugger")]
+        {
+            let session = built_tests.debug(&test_name, &run_opts)?;
+            crate::ops::forc_test_debug::debug(session)?;
+            return Ok(());
+        }
+        #[cfg(not(feature = "debugger"))]
+        return Err(anyhow::anyhow!(
+            "Cannot debug `{test_name}`: forc was built without the `debugger` feature."
+        )
+        .into());
+    }
     let start = std::time::Instant::now();
     let test_count = built_tests.test_count(test_filter.as_ref());
-    let num_tests_running = test_count.total - test_count.ignored;
+    let num_tests_marked_ignored = match run_opts.include_ignored {
+        true => 0,
+        false => test_count.marked_ignored,
+    };
+    let num_tests_running = test_count.total - test_count.ignored - num_tests_marked_ignored;
     let num_tests_ignored = test_count.ignored;
-    info!(
-        "   Running {} {}, filtered {} {}",
-        num_tests_running,
-        formatted_test_count_string(&num_tests_running),
-        num_tests_ignored,
-        formatted_test_count_string(&num_tests_ignored)
-    );
-    let tested = built_tests.run(test_runner_count, test_filter)?;
+    let human = format == TestOutputFormat::Human;
+    if human {
+        info!(
+            "   Running {} {}, filtered {} {}, ignored {}",
+            num_tests_running,
+            formatted_test_count_string(&num_tests_running),
+            num_tests_ignored,
+            formatted_test_count_string(&num_tests_ignored),
+            num_tests_marked_ignored
+        );
+    }
+    let tested = built_tests.run(test_runner_count, test_filter, run_opts.clone())?;
     let duration = start.elapsed();
 
     // Eventually we'll print this in a fancy manner, but this will do for testing.
-    let all_tests_passed = match tested {
-        forc_test::Tested::Workspace(pkgs) => {
+    let tested_pkgs = match tested {
+        forc_test::Tested::Workspace(pkgs) if human => {
             for pkg in &pkgs {
                 let built = &pkg.built.descriptor.name;
                 info!("\n   tested -- {built}\n");
                 print_tested_pkg(pkg, &test_print_opts)?;
             }
             info!("\n   Finished in {:?}", duration);
-            pkgs.iter().all(|pkg| pkg.tests_passed())
+            pkgs
         }
-        forc_test::Tested::Package(pkg) => {
+        forc_test::Tested::Package(pkg) if human => {
             print_tested_pkg(&pkg, &test_print_opts)?;
-            pkg.tests_passed()
+            vec![*pkg]
         }
+        forc_test::Tested::Workspace(pkgs) => pkgs,
+        forc_test::Tested::Package(pkg) => vec![*pkg],
     };
+    match format {
+        TestOutputFormat::Human => (),
+        TestOutputFormat::Json => {
+            let report = TestReport::from_tested_pkgs(&tested_pkgs)?;
+            println!("{}", report.to_json()?);
+        }
+        TestOutputFormat::Junit => {
+            let report = TestReport::from_tested_pkgs(&tested_pkgs)?;
+            print!("{}", report.to_junit());
+        }
+    }
+    let all_tests_passed = tested_pkgs.iter().all(|pkg| pkg.tests_passed());
 
-    if all_tests_passed {
-        Ok(())
-    } else {
+    if run_opts.coverage {
+        for pkg in &tested_pkgs {
+            print_coverage(pkg)?;
+        }
+    }
+
+    let mut gas_within_snapshot = true;
+    for pkg in &tested_pkgs {
+        gas_within_snapshot &= gas_snapshot_pkg(pkg, &gas_snapshot_opts, test_filter)?;
+    }
+
+    const FAILING_UNIT_TESTS_EXIT_CODE: u8 = 101;
+    if !all_tests_passed {
         let forc_error: ForcError = "Some tests failed.".into();
-        const FAILING_UNIT_TESTS_EXIT_CODE: u8 = 101;
         Err(forc_error.exit_code(FAILING_UNIT_TESTS_EXIT_CODE))
+    } else if !gas_within_snapshot {
+        let forc_error: ForcError = "Some tests use more gas than recorded in the snapshot.".into();
+        Err(forc_error.exit_code(FAILING_UNIT_TESTS_EXIT_CODE))
+    } else {
+        Ok(())
+    }
+}
+
+/// Print a summary of the coverage of a tested package per source file, and write its LCOV report.
+fn print_coverage(pkg: &TestedPackage) -> ForcResult<()> {
+    let coverage = Coverage::from_tested_pkg(pkg)?;
+    let pkg_dir = pkg.built.descriptor.manifest_file.dir();
+    let lcov_path = default_output_directory(pkg_dir)
+        .join("coverage")
+        .join("lcov.info");
+    coverage.write_lcov(&lcov_path)?;
+
+    let mut table = term_table::Table::new();
+    table.separate_rows = false;
+    table.style = term_table::TableStyle::empty();
+    table.add_row(Row::new(vec![
+        TableCell::new("file"),
+        TableCell::new("lines"),
+        TableCell::new("hit"),
+        TableCell::new("coverage"),
+    ]));
+    for (path, file) in &coverage.files {
+        let path = path.strip_prefix(pkg_dir).unwrap_or(path);
+        table.add_row(Row::new(vec![
+            TableCell::new(path.display()),
+            TableCell::new_with_alignment(file.lines_found(), 1, Alignment::Right),
+            TableCell::new_with_alignment(file.lines_hit(), 1, Alignment::Right),
+            TableCell::new_with_alignment(
+                format!("{:.2}%", file.percent_hit()),
+                1,
+                Alignment::Right,
+            ),
+        ]));
     }
+    info!(
+        "\n   Coverage: {}\n{}   Wrote LCOV report to {}",
+        pkg.built.descriptor.name,
+        table.render(),
+        lcov_path.display()
+    );
+    Ok(())
+}
+
+/// Write or check the gas snapshot of a tested package, as requested.
+///
+/// The tests left out by the test filter keep their entry in the snapshot, and aren't checked.
+///
+/// Returns `false` if the snapshot was checked and the gas used by some test grew beyond the
+/// tolerance.
+fn gas_snapshot_pkg(
+    pkg: &TestedPackage,
+    opts: &GasSnapshotOpts,
+    test_filter: Option<TestFilter>,
+) -> ForcResult<bool> {
+    let path = pkg.gas_snapshot_path();
+    let snapshot = GasSnapshot::from_tested_pkg(pkg);
+    let selected = |name: &str| test_filter.map_or(true, |filter| filter.filter(name));
+    if opts.write {
+        if !pkg.tests_passed() {
+            println_yellow_err(&format!(
+                "   Not writing gas snapshot to {} as some tests failed",
+                path.display()
+            ));
+            return Ok(true);
+        }
+        let snapshot = match (test_filter, path.exists()) {
+            (Some(_), true) => {
+                let mut previous = GasSnapshot::read(&path)?;
+                previous.retain(|name| !selected(name));
+                previous.merge(snapshot);
+                previous
+            }
+            _ => snapshot,
+        };
+        snapshot.write(&path)?;
+        info!("   Wrote gas snapshot to {}", path.display());
+        return Ok(true);
+    }
+    if !opts.check {
+        return Ok(true);
+    }
+
+    if !path.exists() {
+        return Err(anyhow::anyhow!(
+            "No gas snapshot found at {}. Run `forc test --gas-snapshot` to create one.",
+            path.display()
+        )
+        .into());
+    }
+    let mut previous = GasSnapshot::read(&path)?;
+    previous.retain(selected);
+    let diffs = snapshot.diff(&previous);
+    let regressions = diffs
+        .iter()
+        .filter(|diff| diff.is_regression(opts.tolerance))
+        .count();
+    if diffs.is_empty() {
+        info!("   Gas snapshot: {}", Colour::Green.paint("unchanged"));
+        return Ok(true);
+    }
+
+    let gas_str = |gas: Option<u64>| gas.map_or("-".to_string(), |gas| gas.to_string());
+    let mut table = term_table::Table::new();
+    table.separate_rows = false;
+    table.style = term_table::TableStyle::empty();
+    table.add_row(Row::new(vec![
+        TableCell::new("test"),
+        TableCell::new("snapshot"),
+        TableCell::new("current"),
+        TableCell::new("change"),
+        TableCell::new(""),
+    ]));
+    for diff in &diffs {
+        let change = match (diff.previous, diff.current, diff.percent_change()) {
+            (None, _, _) => "new".to_string(),
+            (_, None, _) => "removed".to_string(),
+            (_, _, Some(percent)) => format!("{percent:+.2}%"),
+            (Some(_), Some(current), None) => format!("+{current}"),
+        };
+        let status = match diff.is_regression(opts.tolerance) {
+            true => "REGRESSED",
+            false => "",
+        };
+        table.add_row(Row::new(vec![
+            TableCell::new(&diff.name),
+            TableCell::new_with_alignment(gas_str(diff.previous), 1, Alignment::Right),
+            TableCell::new_with_alignment(gas_str(diff.current), 1, Alignment::Right),
+            TableCell::new_with_alignment(change, 1, Alignment::Right),
+            TableCell::new(status),
+        ]));
+    }
+    let (state, color) = match regressions {
+        0 => ("OK", Colour::Green),
+        _ => ("REGRESSED", Colour::Red),
+    };
+    info!(
+        "\n   Gas snapshot: {}. {} changed, {} regressed (tolerance {}%).\n{}",
+        color.paint(state),
+        diffs.len(),
+        regressions,
+        opts.tolerance,
+        table.render()
+    );
+    Ok(regressions == 0)
 }
 
 fn print_tested_pkg(pkg: &TestedPackage, test_print_opts: &TestPrintOpts) -> ForcResult<()> {
@@ -115,19 +417,27 @@ fn print_tested_pkg(pkg: &TestedPackage, test_print_opts: &TestPrintOpts) -> For
             true => ("ok", Colour::Green),
             false => ("FAILED", Colour::Red),
         };
-        info!(
-            "      test {} ... {} ({:?}, {} gas)",
-            test.name,
-            color.paint(state),
-            test.duration,
-            test.gas_used
-        );
+        match &test.fuzz {
+            Some(fuzz) => info!(
+                "      test {} ... {} ({:?}, {} gas, {} runs)",
+                test.name,
+                color.paint(state),
+                test.duration,
+                test.gas_used,
+                fuzz.runs
+            ),
+            None => info!(
+                "      test {} ... {} ({:?}, {} gas)",
+                test.name,
+                color.paint(state),
+                test.duration,
+                test.gas_used
+            ),
+        }
 
         // If logs are enabled, print them.
         if test_print_opts.print_logs {
-            let logs = &test.logs;
-            let formatted_logs = format_log_receipts(logs, test_print_opts.pretty_print)?;
-            info!("{}", formatted_logs);
+            info!("{}", format_test_logs(test, test_print_opts)?);
         }
 
         // If the test is failing, save the test result for printing the details later on.
@@ -146,8 +456,7 @@ fn print_tested_pkg(pkg: &TestedPackage, test_print_opts: &TestPrintOpts) -> For
             let failed_test_details = failed_test.details()?;
             let path = &*failed_test_details.file_path;
             let line_number = failed_test_details.line_number;
-            let logs = &failed_test.logs;
-            let formatted_logs = format_log_receipts(logs, test_print_opts.pretty_print)?;
+            let formatted_logs = format_test_logs(failed_test, test_print_opts)?;
             info!(
                 "      - test {}, {:?}:{} ",
                 failed_test_name, path, line_number
@@ -162,6 +471,19 @@ fn print_tested_pkg(pkg: &TestedPackage, test_print_opts: &TestPrintOpts) -> For
                 }
                 info!("{failed_info_str}");
             }
+            if let pkg::TestPassCondition::ShouldRevertWithVariant(variant) = &failed_test.condition
+            {
+                info!("        expected revert with: {variant}");
+            }
+            for unmet in failed_test.unmet_expectations() {
+                info!("        {unmet}");
+            }
+            if let Some(fuzz) = &failed_test.fuzz {
+                if let Some(counterexample) = &fuzz.counterexample {
+                    info!("        minimal failing input: {counterexample}");
+                }
+                info!("        fuzz seed: {}", fuzz.seed);
+            }
             info!("        Logs: {}", formatted_logs);
         }
         info!("\n");
@@ -183,6 +505,17 @@ fn print_tested_pkg(pkg: &TestedPackage, test_print_opts: &TestPrintOpts) -> For
     Ok(())
 }
 
+/// Format the logs of a test, either as the values it logged or as its raw receipts.
+fn format_test_logs(test: &TestResult, test_print_opts: &TestPrintOpts) -> ForcResult<String> {
+    if test_print_opts.raw_logs {
+        return Ok(format_log_receipts(
+            &test.logs,
+            test_print_opts.pretty_print,
+        )?);
+    }
+    Ok(format!("[{}]", test.decoded_logs.join(", ")))
+}
+
 fn opts_from_cmd(cmd: Command) -> forc_test::Opts {
     forc_test::Opts {
         pkg: pkg::PkgOpts {
@@ -200,6 +533,7 @@ fn opts_from_cmd(cmd: Command) -> forc_test::Opts {
             finalized_asm: cmd.build.print.finalized_asm,
             intermediate_asm: cmd.build.print.intermediate_asm,
             ir: cmd.build.print.ir,
+            ir_after: cmd.build.print.ir_after,
         },
         time_phases: cmd.build.print.time_phases,
         metrics_outfile: cmd.build.print.metrics_outfile,
@@ -1,9 +1,10 @@
 use std::str::FromStr;
 
 use self::commands::{
-    addr2line, build, check, clean, completions, contract_id, init, new, parse_bytecode, plugins,
-    predicate_root, template, test, update,
+    abi_diff, addr2line, build, check, clean, completions, contract_id, init, new, parse_bytecode,
+    plugins, predicate_root, template, test, update,
 };
+pub(crate) use abi_diff::Command as AbiDiffCommand;
 use addr2line::Command as Addr2LineCommand;
 use anyhow::anyhow;
 pub use build::Command as BuildCommand;
@@ -12,7 +13,7 @@ use clap::{Parser, Subcommand};
 pub use clean::Command as CleanCommand;
 pub use completions::Command as CompletionsCommand;
 pub(crate) use contract_id::Command as ContractIdCommand;
-use forc_tracing::{init_tracing_subscriber, TracingSubscriberOptions};
+use forc_tracing::{init_tracing_subscriber, TracingSubscriberOptions, TracingWriterMode};
 use forc_util::ForcResult;
 pub use init::Command as InitCommand;
 pub use new::Command as NewCommand;
@@ -67,6 +68,7 @@ enum Forc {
     Template(TemplateCommand),
     ContractId(ContractIdCommand),
     PredicateRoot(PredicateRootCommand),
+    AbiDiff(AbiDiffCommand),
     /// This is a catch-all for unknown subcommands and their arguments.
     ///
     /// When we receive an unknown subcommand, we check for a plugin exe named
@@ -85,7 +87,13 @@ pub async fn run_cli() -> ForcResult<()> {
         verbosity: Some(opt.verbose),
         silent: Some(opt.silent),
         log_level: opt.log_level,
-        ..Default::default()
+        writer_mode: match &opt.command {
+            // Keep stdout free for the report, so that it can be parsed as is.
+            Forc::Test(command) if command.format != test::TestOutputFormat::Human => {
+                Some(TracingWriterMode::Stderr)
+            }
+            _ => None,
+        },
     };
 
     init_tracing_subscriber(tracing_options);
@@ -105,6 +113,7 @@ pub async fn run_cli() -> ForcResult<()> {
         Forc::Template(command) => template::exec(command),
         Forc::ContractId(command) => contract_id::exec(command),
         Forc::PredicateRoot(command) => predicate_root::exec(command),
+        Forc::AbiDiff(command) => abi_diff::exec(command),
         Forc::Plugin(args) => {
             let output = plugin::execute_external_subcommand(args)?;
             let code = output
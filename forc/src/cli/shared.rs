@@ -82,6 +82,11 @@ pub struct Print {
     /// Print the generated Sway IR (Intermediate Representation).
     #[clap(long)]
     pub ir: bool,
+    /// Print the generated Sway IR after the named IR pass has run.
+    ///
+    /// May be given multiple times.  Use `all` to print the IR after every pass.
+    #[clap(long, value_name = "PASS")]
+    pub ir_after: Vec<String>,
     /// Output the time elapsed over each part of the compilation process.
     #[clap(long)]
     pub time_phases: bool,
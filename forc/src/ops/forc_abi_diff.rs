@@ -0,0 +1,533 @@
+//! Comparison of the JSON ABI and storage slots of a program to those of a previous version.
+use crate::cli::AbiDiffCommand;
+use anyhow::{bail, Context, Result};
+use forc_pkg::{self as pkg, build_with_options, program_abi};
+use forc_tracing::{println_green, println_red};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::Path,
+};
+use sway_core::{asm_generation::ProgramABI, fuel_prelude::fuel_tx::StorageSlot};
+use tracing::info;
+
+/// A change of the interface of a program.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AbiChange {
+    /// Whether the change breaks the clients of the previous version.
+    pub breaking: bool,
+    pub description: String,
+}
+impl AbiChange {
+    fn breaking(description: String) -> Self {
+        Self {
+            breaking: true,
+            description,
+        }
+    }
+    fn compatible(description: String) -> Self {
+        Self {
+            breaking: false,
+            description,
+        }
+    }
+}
+
+/// Build the package and compare its JSON ABI and storage slots to the previous version given.
+pub fn abi_diff(command: AbiDiffCommand) -> Result<Vec<AbiChange>> {
+    let old_abi: program_abi::ProgramABI = read_json(&command.old_abi)?;
+    let old_storage_slots: Option<Vec<StorageSlot>> = command
+        .old_storage_slots
+        .as_deref()
+        .map(read_json)
+        .transpose()?;
+
+    let built = build_with_options(build_opts_from_cmd(&command))?;
+    let built_pkg = match built {
+        pkg::Built::Package(built_pkg) => built_pkg,
+        pkg::Built::Workspace(_) => bail!(
+            "`forc abi-diff` compares a single package, please select a member of the workspace \
+             with `--path`"
+        ),
+    };
+    let new_abi = match &built_pkg.program_abi {
+        ProgramABI::Fuel(abi) => abi,
+        _ => bail!("only the ABIs of programs built for the Fuel VM can be compared"),
+    };
+
+    let mut changes = diff_abis(&old_abi, new_abi);
+    if let Some(old_storage_slots) = old_storage_slots {
+        changes.extend(diff_storage_slots(
+            &old_storage_slots,
+            &built_pkg.storage_slots,
+        ));
+    }
+    Ok(changes)
+}
+
+/// Print the breaking changes, then the compatible ones.
+pub fn print_changes(changes: &[AbiChange]) {
+    if changes.is_empty() {
+        println_green("  No changes to the ABI");
+        return;
+    }
+    for (breaking, title) in [(true, "Breaking changes:"), (false, "Compatible changes:")] {
+        let mut changes = changes
+            .iter()
+            .filter(|change| change.breaking == breaking)
+            .peekable();
+        if changes.peek().is_none() {
+            continue;
+        }
+        match breaking {
+            true => println_red(&format!("  {title}")),
+            false => println_green(&format!("  {title}")),
+        }
+        for change in changes {
+            info!("    - {}", change.description);
+        }
+    }
+}
+
+/// Compare two versions of the JSON ABI of a program.
+///
+/// Types are compared by their names and structure, as their ids differ between builds.
+pub fn diff_abis(
+    old_abi: &program_abi::ProgramABI,
+    new_abi: &program_abi::ProgramABI,
+) -> Vec<AbiChange> {
+    let old_types = Types::new(old_abi);
+    let new_types = Types::new(new_abi);
+    let mut changes = vec![];
+
+    for old_fn in &old_abi.functions {
+        let name = &old_fn.name;
+        let Some(new_fn) = new_abi.functions.iter().find(|new_fn| new_fn.name == *name) else {
+            changes.push(AbiChange::breaking(format!(
+                "function `{name}` was removed or renamed"
+            )));
+            continue;
+        };
+
+        let old_inputs = old_types.params(&old_fn.inputs);
+        let new_inputs = new_types.params(&new_fn.inputs);
+        let input_types = |inputs: &[(String, String)]| {
+            inputs.iter().map(|(_, ty)| ty.clone()).collect::<Vec<_>>()
+        };
+        if input_types(&old_inputs) != input_types(&new_inputs) {
+            changes.push(AbiChange::breaking(format!(
+                "the parameters of function `{name}` changed from `({})` to `({})`",
+                join_params(&old_inputs),
+                join_params(&new_inputs)
+            )));
+        } else if old_inputs != new_inputs {
+            changes.push(AbiChange::compatible(format!(
+                "the parameters of function `{name}` were renamed from `({})` to `({})`",
+                join_params(&old_inputs),
+                join_params(&new_inputs)
+            )));
+        }
+
+        let old_output = old_types.name(&old_fn.output);
+        let new_output = new_types.name(&new_fn.output);
+        if old_output != new_output {
+            changes.push(AbiChange::breaking(format!(
+                "the return type of function `{name}` changed from `{old_output}` to `{new_output}`"
+            )));
+        }
+
+        for attribute in ["payable", "storage"] {
+            let old_args = attribute_args(old_fn, attribute);
+            let new_args = attribute_args(new_fn, attribute);
+            if old_args == new_args {
+                continue;
+            }
+            let description = format!(
+                "the `{attribute}` attribute of function `{name}` changed from {} to {}",
+                describe_attribute(attribute, &old_args),
+                describe_attribute(attribute, &new_args)
+            );
+            // Calls forwarding coins to a function which is no longer payable revert.
+            match attribute == "payable" && old_args.is_some() {
+                true => changes.push(AbiChange::breaking(description)),
+                false => changes.push(AbiChange::compatible(description)),
+            }
+        }
+    }
+    for new_fn in &new_abi.functions {
+        if !old_abi
+            .functions
+            .iter()
+            .any(|old_fn| old_fn.name == new_fn.name)
+        {
+            changes.push(AbiChange::compatible(format!(
+                "function `{}` was added",
+                new_fn.name
+            )));
+        }
+    }
+
+    // Structs and enums are encoded by position, so their fields and variants must stay in order.
+    let returned_type_fields = new_types.returned_type_fields(new_abi);
+    for (type_field, old_components) in old_types.declared_components() {
+        let Some(new_components) = new_types
+            .declared_components()
+            .find(|(new_type_field, _)| *new_type_field == type_field)
+            .map(|(_, components)| components)
+        else {
+            continue;
+        };
+        if old_components == new_components {
+            continue;
+        }
+        let description = format!(
+            "`{type_field}` changed from `{{ {} }}` to `{{ {} }}`",
+            join_params(&old_components),
+            join_params(&new_components)
+        );
+        // Variants may be added to the end of an enum, as the existing ones keep their index, as
+        // long as the enum is never returned, since the decoders of the clients don't know them.
+        match type_field.starts_with("enum ")
+            && new_components.starts_with(&old_components)
+            && !returned_type_fields.contains(type_field)
+        {
+            true => changes.push(AbiChange::compatible(description)),
+            false => changes.push(AbiChange::breaking(description)),
+        }
+    }
+
+    changes
+}
+
+/// Compare two versions of the storage slots of a contract. Since storage keys are derived from
+/// the storage fields, a slot which is no longer used means the data stored in it is lost.
+///
+/// Only the keys of the slots are compared: a field changing type in place keeps its key, and the
+/// fields of types such as `StorageMap` have no initial slot, so such changes aren't reported.
+pub fn diff_storage_slots(
+    old_storage_slots: &[StorageSlot],
+    new_storage_slots: &[StorageSlot],
+) -> Vec<AbiChange> {
+    let old_keys = old_storage_slots
+        .iter()
+        .map(|slot| slot.key())
+        .collect::<BTreeSet<_>>();
+    let new_keys = new_storage_slots
+        .iter()
+        .map(|slot| slot.key())
+        .collect::<BTreeSet<_>>();
+    old_keys
+        .difference(&new_keys)
+        .map(|key| {
+            AbiChange::breaking(format!(
+                "storage slot 0x{key} is no longer used, a storage field was removed or reordered"
+            ))
+        })
+        .chain(
+            new_keys
+                .difference(&old_keys)
+                .map(|key| AbiChange::compatible(format!("storage slot 0x{key} was added"))),
+        )
+        .collect()
+}
+
+/// The type declarations of a JSON ABI, by id.
+struct Types<'a>(HashMap<usize, &'a program_abi::TypeDeclaration>);
+impl<'a> Types<'a> {
+    fn new(abi: &'a program_abi::ProgramABI) -> Self {
+        Self(abi.types.iter().map(|decl| (decl.type_id, decl)).collect())
+    }
+    /// The name of the type of an application, e.g. `struct Vec<u64>` or `(u64, bool)`.
+    fn name(&self, application: &program_abi::TypeApplication) -> String {
+        let Some(decl) = self.0.get(&application.type_id) else {
+            return format!("<unknown type {}>", application.type_id);
+        };
+        // The elements of tuples and arrays are placeholders, e.g. `(_, _)` or `[_; 2]`.
+        let components = || {
+            decl.components
+                .iter()
+                .flatten()
+                .map(|component| self.name(component))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let array_len = decl
+            .type_field
+            .strip_prefix('[')
+            .and_then(|field| field.strip_suffix(']'))
+            .and_then(|field| field.rsplit_once(';'))
+            .and_then(|(_, len)| len.trim().parse::<usize>().ok());
+        let name = match array_len {
+            Some(len) => format!("[{}; {len}]", components()),
+            None if decl.type_field.starts_with('(') => format!("({})", components()),
+            None => decl.type_field.clone(),
+        };
+        match &application.type_arguments {
+            Some(type_arguments) if !type_arguments.is_empty() => format!(
+                "{name}<{}>",
+                type_arguments
+                    .iter()
+                    .map(|type_argument| self.name(type_argument))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            _ => name,
+        }
+    }
+    /// The names and types of a list of parameters, fields or variants.
+    fn params(&self, applications: &[program_abi::TypeApplication]) -> Vec<(String, String)> {
+        applications
+            .iter()
+            .map(|application| (application.name.clone(), self.name(application)))
+            .collect()
+    }
+    /// The type fields of the types returned by the functions of the ABI, along with those of
+    /// their fields, variants, elements and type arguments.
+    fn returned_type_fields(&self, abi: &program_abi::ProgramABI) -> BTreeSet<&'a str> {
+        let mut type_fields = BTreeSet::new();
+        let mut applications = abi
+            .functions
+            .iter()
+            .map(|function| &function.output)
+            .collect::<Vec<_>>();
+        let mut visited = BTreeSet::new();
+        while let Some(application) = applications.pop() {
+            applications.extend(application.type_arguments.iter().flatten());
+            if !visited.insert(application.type_id) {
+                continue;
+            }
+            if let Some(decl) = self.0.get(&application.type_id) {
+                type_fields.insert(decl.type_field.as_str());
+                applications.extend(decl.components.iter().flatten());
+            }
+        }
+        type_fields
+    }
+    /// The fields or variants of the structs and enums declared by the ABI.
+    fn declared_components(&self) -> impl Iterator<Item = (&'a str, Vec<(String, String)>)> + '_ {
+        let mut decls = self
+            .0
+            .values()
+            .filter(|decl| {
+                decl.type_field.starts_with("struct ") || decl.type_field.starts_with("enum ")
+            })
+            .collect::<Vec<_>>();
+        decls.sort_by_key(|decl| decl.type_id);
+        decls.into_iter().map(|decl| {
+            (
+                decl.type_field.as_str(),
+                self.params(decl.components.as_deref().unwrap_or_default()),
+            )
+        })
+    }
+}
+
+fn join_params(params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .map(|(name, ty)| format!("{name}: {ty}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The arguments of an attribute of a function, or `None` if it doesn't have it.
+fn attribute_args(function: &program_abi::ABIFunction, name: &str) -> Option<Vec<String>> {
+    function
+        .attributes
+        .iter()
+        .flatten()
+        .find(|attribute| attribute.name == name)
+        .map(|attribute| attribute.arguments.clone())
+}
+
+fn describe_attribute(name: &str, args: &Option<Vec<String>>) -> String {
+    match args {
+        None => "none".to_string(),
+        Some(args) if args.is_empty() => format!("`#[{name}]`"),
+        Some(args) => format!("`#[{name}({})]`", args.join(", ")),
+    }
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+fn build_opts_from_cmd(cmd: &AbiDiffCommand) -> pkg::BuildOpts {
+    pkg::BuildOpts {
+        pkg: pkg::PkgOpts {
+            path: cmd.pkg.path.clone(),
+            offline: cmd.pkg.offline,
+            terse: cmd.pkg.terse,
+            locked: cmd.pkg.locked,
+            output_directory: cmd.pkg.output_directory.clone(),
+            json_abi_with_callpaths: cmd.pkg.json_abi_with_callpaths,
+        },
+        build_profile: cmd.build_profile.build_profile.clone(),
+        release: cmd.build_profile.release,
+        error_on_warnings: cmd.build_profile.error_on_warnings,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use program_abi::{ABIFunction, ProgramABI, TypeApplication, TypeDeclaration};
+    use sway_core::fuel_prelude::fuel_types::Bytes32;
+
+    /// The name, parameters and return type of a function.
+    type Function<'a> = (&'a str, &'a [(&'a str, usize)], usize);
+
+    fn decl(type_id: usize, type_field: &str, components: &[(&str, usize)]) -> TypeDeclaration {
+        TypeDeclaration {
+            type_id,
+            type_field: type_field.to_string(),
+            components: (!components.is_empty())
+                .then(|| components.iter().map(|(name, id)| app(name, *id)).collect()),
+            type_parameters: None,
+        }
+    }
+
+    fn app(name: &str, type_id: usize) -> TypeApplication {
+        TypeApplication {
+            name: name.to_string(),
+            type_id,
+            type_arguments: None,
+        }
+    }
+
+    fn abi(types: Vec<TypeDeclaration>, functions: &[Function]) -> ProgramABI {
+        ProgramABI {
+            types,
+            functions: functions
+                .iter()
+                .map(|(name, inputs, output)| ABIFunction {
+                    inputs: inputs.iter().map(|(name, id)| app(name, *id)).collect(),
+                    name: name.to_string(),
+                    output: app("", *output),
+                    attributes: None,
+                })
+                .collect(),
+            logged_types: None,
+            messages_types: None,
+            configurables: None,
+        }
+    }
+
+    #[test]
+    fn functions() {
+        let types = || {
+            vec![
+                decl(0, "()", &[]),
+                decl(1, "u64", &[]),
+                decl(2, "bool", &[]),
+            ]
+        };
+        let old = abi(
+            types(),
+            &[
+                ("transfer", &[("amount", 1)], 0),
+                ("balance", &[], 1),
+                ("owner", &[], 1),
+            ],
+        );
+        // The ids of the types differ between builds.
+        let mut new_types = types();
+        new_types.iter_mut().for_each(|decl| decl.type_id += 10);
+        let new = abi(
+            new_types,
+            &[
+                ("transfer", &[("value", 11)], 10),
+                ("balance", &[], 12),
+                ("mint", &[], 10),
+            ],
+        );
+        let changes = diff_abis(&old, &new);
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| change.breaking)
+                .collect::<Vec<_>>(),
+            [false, true, true, false]
+        );
+        assert!(changes[0].description.contains("renamed"));
+        assert!(changes[1]
+            .description
+            .contains("return type of function `balance`"));
+        assert!(changes[2].description.contains("`owner` was removed"));
+        assert!(changes[3].description.contains("`mint` was added"));
+    }
+
+    #[test]
+    fn enum_variants() {
+        let color = |variants: &[&str]| {
+            let variants = variants
+                .iter()
+                .map(|variant| (*variant, 0))
+                .collect::<Vec<_>>();
+            vec![decl(0, "()", &[]), decl(1, "enum Color", &variants)]
+        };
+        let paint = |variants| abi(color(variants), &[("paint", &[("color", 1)], 0)]);
+        let color_of = |variants| abi(color(variants), &[("color_of", &[], 1)]);
+
+        // Appending a variant to an enum only taken as parameter is compatible.
+        let changes = diff_abis(&paint(&["Red", "Green"]), &paint(&["Red", "Green", "Blue"]));
+        assert_eq!(changes.len(), 1);
+        assert!(!changes[0].breaking);
+        // But not if the enum is returned, as the clients can't decode the new variant.
+        let changes = diff_abis(
+            &color_of(&["Red", "Green"]),
+            &color_of(&["Red", "Green", "Blue"]),
+        );
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].breaking);
+        // Reordering the variants changes their index.
+        let changes = diff_abis(&paint(&["Red", "Green"]), &paint(&["Green", "Red"]));
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].breaking);
+    }
+
+    #[test]
+    fn type_names() {
+        let abi = abi(
+            vec![
+                decl(0, "u64", &[]),
+                decl(1, "struct Token_Id", &[("value", 0)]),
+                decl(
+                    2,
+                    "(_, _)",
+                    &[("__tuple_element", 1), ("__tuple_element", 0)],
+                ),
+                decl(3, "[_; 2]", &[("__array_element", 2)]),
+            ],
+            &[],
+        );
+        let types = Types::new(&abi);
+        assert_eq!(types.name(&app("", 2)), "(struct Token_Id, u64)");
+        assert_eq!(types.name(&app("", 3)), "[(struct Token_Id, u64); 2]");
+    }
+
+    #[test]
+    fn storage_slots() {
+        let slot = |key: u8| StorageSlot::new(Bytes32::new([key; 32]), Bytes32::zeroed());
+        let changes = diff_storage_slots(&[slot(1), slot(2)], &[slot(1), slot(3)]);
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| change.breaking)
+                .collect::<Vec<_>>(),
+            [true, false]
+        );
+        assert!(changes[0]
+            .description
+            .contains(&format!("0x{}", "02".repeat(32))));
+        assert!(changes[0].description.contains("no longer used"));
+        assert!(changes[1]
+            .description
+            .contains(&format!("0x{}", "03".repeat(32))));
+        assert!(changes[1].description.contains("was added"));
+        assert!(diff_storage_slots(&[slot(1)], &[slot(1)]).is_empty());
+    }
+}
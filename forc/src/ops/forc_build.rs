@@ -25,6 +25,7 @@ fn opts_from_cmd(cmd: BuildCommand) -> pkg::BuildOpts {
             finalized_asm: cmd.build.print.finalized_asm,
             intermediate_asm: cmd.build.print.intermediate_asm,
             ir: cmd.build.print.ir,
+            ir_after: cmd.build.print.ir_after,
         },
         time_phases: cmd.build.print.time_phases,
         metrics_outfile: cmd.build.print.metrics_outfile,
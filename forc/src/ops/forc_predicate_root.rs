@@ -29,6 +29,7 @@ fn build_opts_from_cmd(cmd: PredicateRootCommand) -> pkg::BuildOpts {
             finalized_asm: cmd.print.finalized_asm,
             intermediate_asm: cmd.print.intermediate_asm,
             ir: cmd.print.ir,
+            ir_after: cmd.print.ir_after,
         },
         time_phases: cmd.print.time_phases,
         metrics_outfile: cmd.print.metrics_outfile,
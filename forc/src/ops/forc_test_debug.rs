@@ -0,0 +1,248 @@
+use anyhow::{anyhow, bail, Result};
+use forc_test::{DebugEvent, DebugSession, SourceLocation};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, BufRead, Write},
+    path::PathBuf,
+};
+
+const HELP: &str = "\
+Commands:
+  s, step [n]            execute the next instruction, or the next n instructions
+  n, next                execute until the next source line
+  c, continue            execute until a breakpoint is reached or the test finishes
+  b, break [file:]line   set a breakpoint, or list the breakpoints if no line is given
+  d, delete [file:]line  remove a breakpoint
+  r, registers           print the registers
+  m, memory addr len     print len bytes of memory starting at addr
+  stack                  print the stack, from $ssp to $sp
+  l, locals              print the locals of the current function
+  p, print name          print a local or a register, e.g. `p x` or `p $sp`
+  logs                   print the values logged so far
+  h, help                print this message
+  q, quit                stop debugging";
+
+/// Debug a test interactively, reading commands from stdin until the user quits.
+pub fn debug(mut session: DebugSession) -> Result<()> {
+    let mut sources = Sources::default();
+    println!("Type `help` for the list of commands.");
+    print_stop(&session, &mut sources, &DebugEvent::Stepped);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("(debug) ");
+        io::stdout().flush()?;
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        let args = line.split_whitespace().collect::<Vec<_>>();
+        let (command, args) = match args.split_first() {
+            Some((command, args)) => (*command, args),
+            None => continue,
+        };
+        if matches!(command, "q" | "quit") {
+            return Ok(());
+        }
+        if let Err(err) = exec_command(&mut session, &mut sources, command, args) {
+            println!("error: {err}");
+        }
+    }
+}
+
+fn exec_command(
+    session: &mut DebugSession,
+    sources: &mut Sources,
+    command: &str,
+    args: &[&str],
+) -> Result<()> {
+    match command {
+        "s" | "step" => {
+            let count = match args.first() {
+                Some(count) => count.parse::<usize>()?,
+                None => 1,
+            };
+            let mut event = DebugEvent::Stepped;
+            for _ in 0..count {
+                event = session.step_instruction()?;
+                if event == DebugEvent::Finished {
+                    break;
+                }
+            }
+            print_stop(session, sources, &event);
+        }
+        "n" | "next" => {
+            let event = session.step_line()?;
+            print_stop(session, sources, &event);
+        }
+        "c" | "continue" => {
+            let event = session.continue_execution()?;
+            print_stop(session, sources, &event);
+        }
+        "b" | "break" => match args.first() {
+            Some(arg) => {
+                let (path, line) = parse_line(arg)?;
+                let location = session.add_breakpoint(path.as_deref(), line)?;
+                println!("Breakpoint set at {}", format_location(&location));
+            }
+            None => {
+                for location in session.breakpoints() {
+                    println!("{}", format_location(location));
+                }
+            }
+        },
+        "d" | "delete" => {
+            let arg = args.first().ok_or_else(|| anyhow!("expected a line"))?;
+            let (path, line) = parse_line(arg)?;
+            let location = session
+                .breakpoints()
+                .find(|location| {
+                    location.line == line
+                        && path
+                            .as_ref()
+                            .map_or(true, |path| location.path.ends_with(path))
+                })
+                .cloned()
+                .ok_or_else(|| anyhow!("no breakpoint at {arg}"))?;
+            session.remove_breakpoint(&location);
+        }
+        "r" | "registers" => {
+            for (name, value) in session.registers() {
+                if value != 0 {
+                    println!("{name:>6} = {value:#x} ({value})");
+                }
+            }
+        }
+        "m" | "memory" => {
+            let (addr, len) = match args {
+                [addr, len] => (parse_u64(addr)?, parse_u64(len)?),
+                _ => bail!("expected an address and a length"),
+            };
+            print_memory(addr, session.memory(addr, len)?);
+        }
+        "stack" => {
+            let (ssp, stack) = session.stack()?;
+            print_memory(ssp, stack);
+        }
+        "l" | "locals" => {
+            if let Some(function) = session.function() {
+                println!("In function `{function}`:");
+            }
+            for local in session.locals()? {
+                println!(
+                    "  {} @ {:#x} = {}",
+                    local.name,
+                    local.address,
+                    format_bytes(&local.bytes)
+                );
+            }
+        }
+        "p" | "print" => {
+            let name = args.first().ok_or_else(|| anyhow!("expected a name"))?;
+            if name.starts_with('$') {
+                let (_, value) = session
+                    .registers()
+                    .into_iter()
+                    .find(|(register, _)| register.as_str() == *name)
+                    .ok_or_else(|| anyhow!("no register named {name}"))?;
+                println!("{name} = {value:#x} ({value})");
+            } else {
+                let local = session
+                    .locals()?
+                    .into_iter()
+                    .find(|local| local.name == *name)
+                    .ok_or_else(|| anyhow!("no local named `{name}` in the current function"))?;
+                println!("{name} = {}", format_bytes(&local.bytes));
+            }
+        }
+        "logs" => {
+            for log in session.decoded_logs() {
+                println!("{log}");
+            }
+        }
+        "h" | "help" => println!("{HELP}"),
+        _ => bail!("unknown command `{command}`, type `help` for the list of commands"),
+    }
+    Ok(())
+}
+
+/// Print where execution stopped, or how the test ended.
+fn print_stop(session: &DebugSession, sources: &mut Sources, event: &DebugEvent) {
+    if let DebugEvent::Breakpoint(location) = event {
+        println!("Breakpoint reached at {}", format_location(location));
+    }
+    if session.finished() {
+        println!("Test finished: {:?}", session.state());
+        return;
+    }
+    match session.location() {
+        Some(location) => println!(
+            "{}: {}",
+            format_location(&location),
+            sources.line(&location).unwrap_or_default().trim()
+        ),
+        None => println!("<no source location>"),
+    }
+    if let Some(instruction) = session.instruction() {
+        println!("  next instruction: {instruction:?}");
+    }
+}
+
+/// Print memory starting at `addr`, a word per row.
+fn print_memory(addr: u64, bytes: &[u8]) {
+    for (ix, word) in bytes.chunks(8).enumerate() {
+        println!("{:#010x}: {}", addr + ix as u64 * 8, hex::encode(word));
+    }
+}
+
+/// Format the bytes of a value, along with the integer they represent if they fit in a word.
+fn format_bytes(bytes: &[u8]) -> String {
+    match <[u8; 8]>::try_from(bytes) {
+        Ok(word) => format!("{} (0x{})", u64::from_be_bytes(word), hex::encode(word)),
+        Err(_) => format!("0x{}", hex::encode(bytes)),
+    }
+}
+
+fn format_location(location: &SourceLocation) -> String {
+    format!("{}:{}", location.path.display(), location.line)
+}
+
+/// Parse a `[file:]line` argument.
+fn parse_line(arg: &str) -> Result<(Option<PathBuf>, usize)> {
+    let (path, line) = match arg.rsplit_once(':') {
+        Some((path, line)) => (Some(PathBuf::from(path)), line),
+        None => (None, arg),
+    };
+    let line = line
+        .parse()
+        .map_err(|_| anyhow!("invalid line number `{line}`"))?;
+    Ok((path, line))
+}
+
+/// Parse a decimal or `0x` prefixed hexadecimal integer.
+fn parse_u64(arg: &str) -> Result<u64> {
+    let parsed = match arg.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => arg.parse(),
+    };
+    parsed.map_err(|_| anyhow!("invalid number `{arg}`"))
+}
+
+/// The source files read so far, to print the lines being executed.
+#[derive(Default)]
+struct Sources {
+    files: HashMap<PathBuf, Option<String>>,
+}
+
+impl Sources {
+    fn line(&mut self, location: &SourceLocation) -> Option<&str> {
+        self.files
+            .entry(location.path.clone())
+            .or_insert_with(|| fs::read_to_string(&location.path).ok())
+            .as_deref()?
+            .lines()
+            .nth(location.line - 1)
+    }
+}
@@ -1,3 +1,4 @@
+pub mod forc_abi_diff;
 pub mod forc_build;
 pub mod forc_check;
 pub mod forc_clean;
@@ -5,4 +6,6 @@ pub mod forc_contract_id;
 pub mod forc_init;
 pub mod forc_predicate_root;
 pub mod forc_template;
+#[cfg(feature = "debugger")]
+pub mod forc_test_debug;
 pub mod forc_update;
@@ -58,13 +58,18 @@ pub fn generate_json_abi_program(
                 configurables: Some(configurables),
             }
         }
-        _ => program_abi::ProgramABI {
-            types: vec![],
-            functions: vec![],
-            logged_types: None,
-            messages_types: None,
-            configurables: None,
-        },
+        TyProgramKind::Library { .. } => {
+            // Libraries have no entry points, but the values logged by their tests are decoded
+            // using their logged types.
+            let logged_types = generate_json_logged_types(ctx, type_engine, decl_engine, types);
+            program_abi::ProgramABI {
+                types: types.to_vec(),
+                functions: vec![],
+                logged_types: Some(logged_types),
+                messages_types: None,
+                configurables: None,
+            }
+        }
     }
 }
 
@@ -875,7 +880,7 @@ fn call_path_display(ctx: &mut JsonAbiContext, call_path: &CallPath) -> String {
 }
 
 impl TyFunctionDecl {
-    pub(self) fn generate_json_abi_function(
+    pub fn generate_json_abi_function(
         &self,
         ctx: &mut JsonAbiContext,
         type_engine: &TypeEngine,
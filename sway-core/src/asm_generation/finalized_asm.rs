@@ -7,12 +7,13 @@ use super::{
 use crate::asm_lang::allocated_ops::{AllocatedOp, AllocatedOpcode};
 use crate::decl_engine::DeclRefFunction;
 use crate::error::*;
-use crate::source_map::SourceMap;
+use crate::source_map::{FunctionLocals, SourceMap};
 
 use etk_asm::asm::Assembler;
 use sway_error::error::CompileError;
 use sway_types::span::Span;
 use sway_types::SourceEngine;
+use sway_utils::RegisterAllocationMetric;
 
 use either::Either;
 use std::{collections::BTreeMap, fmt};
@@ -26,6 +27,11 @@ pub struct FinalizedAsm {
     pub program_kind: ProgramKind,
     pub entries: Vec<FinalizedEntry>,
     pub abi: Option<ProgramABI>,
+    /// Register allocation metrics for each function, only populated for the FuelVM.
+    pub register_allocation_metrics: Vec<RegisterAllocationMetric>,
+    /// The locals stored in the stack frame of each function, with the span of the function owning
+    /// the op which reserves the frame.  Only populated for the FuelVM.
+    pub function_locals: Vec<(Span, FunctionLocals)>,
 }
 
 #[derive(Clone, Debug)]
@@ -55,9 +61,13 @@ impl FinalizedAsm {
         source_engine: &SourceEngine,
     ) -> CompileResult<CompiledBytecode> {
         match &self.program_section {
-            InstructionSet::Fuel { ops } => {
-                to_bytecode_mut(ops, &mut self.data_section, source_map, source_engine)
-            }
+            InstructionSet::Fuel { ops } => to_bytecode_mut(
+                ops,
+                &mut self.data_section,
+                &self.function_locals,
+                source_map,
+                source_engine,
+            ),
             InstructionSet::Evm { ops } => {
                 let mut assembler = Assembler::new();
                 if let Err(e) = assembler.push_all(ops.clone()) {
@@ -106,6 +116,7 @@ impl fmt::Display for FinalizedAsm {
 fn to_bytecode_mut(
     ops: &Vec<AllocatedOp>,
     data_section: &mut DataSection,
+    function_locals: &[(Span, FunctionLocals)],
     source_map: &mut SourceMap,
     source_engine: &SourceEngine,
 ) -> CompileResult<CompiledBytecode> {
@@ -140,9 +151,22 @@ fn to_bytecode_mut(
     // each op is four bytes, so the length of the buf is the number of ops times four.
     let mut buf = vec![0; (ops.len() * 4) + 4];
 
+    // Generic functions are compiled once per set of type arguments, so several functions may
+    // share a span.  Their frames are reserved in the order they were compiled.
+    let mut function_locals = function_locals.iter().collect::<Vec<_>>();
+
     let mut half_word_ix = 0;
     for op in ops.iter() {
         let span = op.owning_span.clone();
+        if let (AllocatedOpcode::CFEI(_), Some(span)) = (&op.opcode, &span) {
+            if let Some(idx) = function_locals
+                .iter()
+                .position(|(fn_span, _)| fn_span == span)
+            {
+                let (_, locals) = function_locals.remove(idx);
+                source_map.insert_function_locals(half_word_ix, locals.clone());
+            }
+        }
         let op = op.to_fuel_asm(offset_to_data_section_in_bytes, data_section);
         match op {
             Either::Right(data) => {
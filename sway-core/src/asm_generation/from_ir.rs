@@ -108,7 +108,7 @@ fn compile_module_to_asm(
     let result = builder.finalize();
     let final_program = match result {
         AsmBuilderResult::Fuel(result) => {
-            let (data_section, reg_seqr, entries, non_entries) = result;
+            let (data_section, reg_seqr, entries, non_entries, function_locals) = result;
             let entries = entries
                 .into_iter()
                 .map(|(func, label, ops, test_decl_ref)| {
@@ -124,8 +124,14 @@ fn compile_module_to_asm(
                 })
                 .collect();
 
-            let abstract_program =
-                AbstractProgram::new(kind, data_section, entries, non_entries, reg_seqr);
+            let abstract_program = AbstractProgram::new(
+                kind,
+                data_section,
+                entries,
+                non_entries,
+                reg_seqr,
+                function_locals,
+            );
 
             if build_config
                 .map(|cfg| cfg.print_intermediate_asm)
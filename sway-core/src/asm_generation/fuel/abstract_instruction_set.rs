@@ -1,6 +1,7 @@
 use crate::{
     asm_generation::fuel::{
-        allocated_abstract_instruction_set::AllocatedAbstractInstructionSet, register_allocator,
+        allocated_abstract_instruction_set::AllocatedAbstractInstructionSet,
+        register_allocator::{self, Coloring},
     },
     asm_lang::{
         allocated_ops::{AllocatedOp, AllocatedOpcode},
@@ -8,10 +9,15 @@ use crate::{
     },
 };
 
+use rustc_hash::FxHashSet;
 use sway_error::error::CompileError;
 use sway_types::Span;
+use sway_utils::RegisterAllocationMetric;
 
-use std::{collections::HashSet, fmt};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt,
+};
 
 use either::Either;
 
@@ -160,58 +166,114 @@ impl AbstractInstructionSet {
     }
 
     /// Assigns an allocatable register to each virtual register used by some instruction in the
-    /// list `self.ops`. The algorithm used is Chaitin's graph-coloring register allocation
-    /// algorithm (https://en.wikipedia.org/wiki/Chaitin%27s_algorithm). The individual steps of
-    /// the algorithm are thoroughly explained in register_allocator.rs.
+    /// list `self.ops`. The algorithm used is iterated register coalescing, an extension of
+    /// Chaitin's graph-coloring register allocation algorithm
+    /// (https://en.wikipedia.org/wiki/Chaitin%27s_algorithm). When the registers can't all be
+    /// colored some are spilled to the stack, or rematerialized if they hold constants, and the
+    /// allocation is attempted again. The individual steps of the algorithm are thoroughly
+    /// explained in register_allocator.rs.
+    ///
+    /// Also returns some metrics describing the allocation for this function.
     ///
     pub(crate) fn allocate_registers(
         self,
-    ) -> Result<AllocatedAbstractInstructionSet, CompileError> {
-        // Step 1: Liveness Analysis.
-        let live_out = register_allocator::liveness_analysis(&self.ops);
-
-        // Step 2: Construct the interference graph.
-        let (mut interference_graph, mut reg_to_node_ix) =
-            register_allocator::create_interference_graph(&self.ops, &live_out);
-
-        // Step 3: Remove redundant MOVE instructions using the interference graph.
-        let reduced_ops = register_allocator::coalesce_registers(
-            &self.ops,
-            &mut interference_graph,
-            &mut reg_to_node_ix,
-        );
-
-        // Step 4: Simplify - i.e. color the interference graph and return a stack that contains
-        // each colorable node and its neighbors.
-        let mut stack = register_allocator::color_interference_graph(&mut interference_graph);
-
-        // Uncomment the following to get some idea of which function is failing to complete
-        // register allocation.  The last comment printed will indicate the current function name.
-        // This will be unnecessary once we have the new register allocator, coming very soon!
-        //
-        //let comment = self.ops.iter().find_map(|op| {
-        //    if let Either::Right(crate::asm_lang::ControlFlowOp::Label(_)) = op.opcode {
-        //        Some(op.comment.clone())
-        //    } else {
-        //        None
-        //    }
-        //});
-        //dbg!(comment);
-
-        // Step 5: Use the stack to assign a register for each virtual register.
-        let pool = register_allocator::assign_registers(&mut stack)?;
-
-        // Step 6: Update all instructions to use the resulting register pool.
+    ) -> Result<(AllocatedAbstractInstructionSet, RegisterAllocationMetric), CompileError> {
+        let function = self
+            .ops
+            .iter()
+            .find_map(|op| match &op.opcode {
+                Either::Right(OrganizationalOp::Label(_)) => Some(
+                    op.comment
+                        .trim_start_matches("--- start of function: ")
+                        .trim_end_matches(" ---")
+                        .to_owned(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        let rematerializable = register_allocator::rematerializable_registers(&self.ops);
+        let mut spilled = FxHashSet::default();
+        let mut ops = self.ops;
+        let (pool, coalesced_moves) = loop {
+            // Step 1: Liveness Analysis.
+            let live_out = register_allocator::liveness_analysis(&ops);
+
+            // Step 2: Construct the interference graph.
+            let (interference_graph, _) =
+                register_allocator::create_interference_graph(&ops, &live_out);
+
+            // Step 3: Estimate the cost of spilling each virtual register.
+            let spill_costs =
+                register_allocator::compute_spill_costs(&ops, &rematerializable, &spilled);
+
+            // Step 4: Coalesce and color the interference graph, either assigning a register to
+            // each virtual register or choosing virtual registers to spill.
+            match register_allocator::color_interference_graph(
+                &ops,
+                &interference_graph,
+                &spill_costs,
+            ) {
+                Coloring::Colored {
+                    pool,
+                    coalesced_moves,
+                } => break (pool, coalesced_moves),
+                Coloring::Spill(spills) => {
+                    // Step 5: Spill and try again.  Registers which have already been spilled
+                    // can't be split any further, so if only those are left we have to give up.
+                    let spills = spills
+                        .into_iter()
+                        .filter(|reg| !spilled.contains(reg))
+                        .collect::<BTreeSet<_>>();
+                    if spills.is_empty() {
+                        return Err(CompileError::Internal(
+                            "The allocator cannot resolve a register mapping for this program. \
+                             Using #[inline(never)] on some functions may help.",
+                            Span::dummy(),
+                        ));
+                    }
+                    ops = register_allocator::spill_registers(&ops, &spills, &rematerializable)?;
+                    spilled.extend(spills);
+                }
+            }
+        };
+
+        // Step 6: Update all instructions to use the resulting register pool.  MOVEs between
+        // coalesced registers are now redundant and are removed, as are the stack frame
+        // manipulations for functions with no locals.
         let mut buf = vec![];
-        for op in &reduced_ops {
+        for op in &ops {
+            let opcode = op.allocate_registers(&pool);
+            match &opcode {
+                Either::Left(AllocatedOpcode::MOVE(dst, src)) if dst == src => continue,
+                Either::Left(AllocatedOpcode::CFEI(imm) | AllocatedOpcode::CFSI(imm))
+                    if imm.value == 0 =>
+                {
+                    continue
+                }
+                _ => (),
+            }
             buf.push(AllocatedAbstractOp {
-                opcode: op.allocate_registers(&pool),
+                opcode,
                 comment: op.comment.clone(),
                 owning_span: op.owning_span.clone(),
             })
         }
 
-        Ok(AllocatedAbstractInstructionSet { ops: buf })
+        let metric = RegisterAllocationMetric {
+            function,
+            spilled_registers: spilled
+                .iter()
+                .filter(|reg| !rematerializable.contains_key(*reg))
+                .count(),
+            rematerialized_registers: spilled
+                .iter()
+                .filter(|reg| rematerializable.contains_key(*reg))
+                .count(),
+            coalesced_moves,
+        };
+
+        Ok((AllocatedAbstractInstructionSet { ops: buf }, metric))
     }
 }
 
@@ -14,6 +14,7 @@ use crate::{
     decl_engine::DeclRefFunction,
     error::*,
     metadata::MetadataManager,
+    source_map::FunctionLocals,
 };
 
 use sway_error::{error::CompileError, warning::CompileWarning, warning::Warning};
@@ -62,6 +63,10 @@ pub struct FuelAsmBuilder<'ir, 'eng> {
     pub(super) entries: Vec<(Function, Label, Vec<Op>, Option<DeclRefFunction>)>,
     pub(super) non_entries: Vec<Vec<Op>>,
 
+    // The locals stored in the stack frame of each function, with the span of the function which
+    // owns the `CFEI` reserving the frame.
+    pub(super) function_locals: Vec<(Span, FunctionLocals)>,
+
     // In progress VM bytecode ops.
     pub(super) cur_bytecode: Vec<Op>,
 }
@@ -76,6 +81,7 @@ pub type FuelAsmBuilderResult = (
         Option<DeclRefFunction>,
     )>,
     Vec<AbstractInstructionSet>,
+    Vec<(Span, FunctionLocals)>,
 );
 
 impl<'ir, 'eng> AsmBuilder for FuelAsmBuilder<'ir, 'eng> {
@@ -113,6 +119,7 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
             md_mgr: MetadataManager::default(),
             entries: Vec::new(),
             non_entries: Vec::new(),
+            function_locals: Vec::new(),
             cur_bytecode: Vec::new(),
         }
     }
@@ -133,6 +140,7 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
                 .into_iter()
                 .map(|ops| AbstractInstructionSet { ops })
                 .collect(),
+            self.function_locals.clone(),
         ))
     }
 
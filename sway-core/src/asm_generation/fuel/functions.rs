@@ -12,6 +12,7 @@ use crate::{
     error::*,
     fuel_prelude::fuel_asm::GTFArgs,
     size_bytes_in_words, size_bytes_round_up_to_word_alignment,
+    source_map::{FunctionLocals, LocalVariable},
 };
 
 use sway_ir::*;
@@ -195,7 +196,14 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
         let mut errors = Vec::new();
 
         if func_is_entry {
-            let result = Into::<CompileResult<()>>::into(self.compile_external_args(function));
+            // Tests are always executed as scripts, whatever kind of program declares them, so
+            // their args are always passed in the script data.
+            let program_kind = match test_decl_ref {
+                Some(_) => ProgramKind::Script,
+                None => self.program_kind,
+            };
+            let result =
+                Into::<CompileResult<()>>::into(self.compile_external_args(function, program_kind));
             check!(result, return err(warnings, errors), warnings, errors);
         } else {
             // Make copies of the arg registers.
@@ -314,7 +322,11 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
     }
 
     // Handle loading the arguments of a contract call
-    fn compile_external_args(&mut self, function: Function) -> Result<(), CompileError> {
+    fn compile_external_args(
+        &mut self,
+        function: Function,
+        program_kind: ProgramKind,
+    ) -> Result<(), CompileError> {
         match function.args_iter(self.context).count() {
             // Nothing to do if there are no arguments
             0 => Ok(()),
@@ -324,13 +336,13 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
             1 => {
                 let (_, val) = function.args_iter(self.context).next().unwrap();
                 let single_arg_reg = self.reg_seqr.next();
-                match self.program_kind {
+                match program_kind {
                     ProgramKind::Contract => {
                         self.read_args_base_from_frame(&single_arg_reg);
                     }
                     ProgramKind::Library => {} // Nothing to do here
                     ProgramKind::Script | ProgramKind::Predicate => {
-                        if let ProgramKind::Predicate = self.program_kind {
+                        if let ProgramKind::Predicate = program_kind {
                             self.read_args_base_from_predicate_data(&single_arg_reg);
                         } else {
                             self.read_args_base_from_script_data(&single_arg_reg);
@@ -361,7 +373,7 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
             // Otherwise, the args are bundled together and pointed to by the base register.
             _ => {
                 let args_base_reg = self.reg_seqr.next();
-                match self.program_kind {
+                match program_kind {
                     ProgramKind::Contract => self.read_args_base_from_frame(&args_base_reg),
                     ProgramKind::Library => return Ok(()), // Nothing to do here
                     ProgramKind::Predicate => {
@@ -614,9 +626,10 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
         // Otherwise they go in runtime allocated space, either a register or on the stack.
         //
         // Stack offsets are in words to both enforce alignment and simplify use with LW/SW.
+        let mut locals = Vec::new();
         let (stack_base, init_mut_vars) = function.locals_iter(self.context).fold(
             (0, Vec::new()),
-            |(stack_base, mut init_mut_vars), (_name, ptr)| {
+            |(stack_base, mut init_mut_vars), (name, ptr)| {
                 if let (false, Some(constant)) = (
                     ptr.is_mutable(self.context),
                     ptr.get_initializer(self.context),
@@ -655,6 +668,12 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
                         init_mut_vars.push((stack_base, var_size, data_id));
                     }
 
+                    locals.push(LocalVariable {
+                        name: name.clone(),
+                        offset: stack_base * 8,
+                        size: var_size * 8,
+                    });
+
                     (stack_base + var_size, init_mut_vars)
                 }
             },
@@ -670,20 +689,35 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
             None,
         ));
 
+        // The frame is reserved even if there are no locals as the register allocator may extend
+        // it with slots for spilled registers.  It is removed after allocation if still empty.
         let locals_size = stack_base * 8;
-        if locals_size != 0 {
-            if locals_size > compiler_constants::TWENTY_FOUR_BITS {
-                todo!("Enormous stack usage for locals.");
-            }
-            self.cur_bytecode.push(Op {
-                opcode: Either::Left(VirtualOp::CFEI(VirtualImmediate24 {
-                    value: locals_size as u32,
-                })),
-                comment: format!("allocate {locals_size} bytes for locals"),
-                owning_span: None,
-            });
+        if locals_size > compiler_constants::TWENTY_FOUR_BITS {
+            todo!("Enormous stack usage for locals.");
         }
 
+        // The `CFEI` is owned by the function so that debuggers can find where its frame starts
+        // using the source map, which records the locals stored in the frame.
+        let span = self
+            .md_mgr
+            .md_to_span(self.context, function.get_metadata(self.context));
+        if let (Some(span), false) = (&span, locals.is_empty()) {
+            self.function_locals.push((
+                span.clone(),
+                FunctionLocals {
+                    function: function.get_name(self.context).to_string(),
+                    locals,
+                },
+            ));
+        }
+        self.cur_bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::CFEI(VirtualImmediate24 {
+                value: locals_size as u32,
+            })),
+            comment: format!("allocate {locals_size} bytes for locals"),
+            owning_span: span,
+        });
+
         // Initialise that stack variables which require it.
         for (var_stack_offs, var_word_size, var_data_id) in init_mut_vars {
             // Load our initialiser from the data section.
@@ -774,18 +808,16 @@ impl<'ir, 'eng> FuelAsmBuilder<'ir, 'eng> {
             .locals_ctxs
             .pop()
             .expect("Calls guaranteed to save locals context.");
-        if locals_size != 0 {
-            if locals_size > compiler_constants::TWENTY_FOUR_BITS {
-                todo!("Enormous stack usage for locals.");
-            }
-            self.cur_bytecode.push(Op {
-                opcode: Either::Left(VirtualOp::CFSI(VirtualImmediate24 {
-                    value: locals_size as u32,
-                })),
-                comment: format!("free {locals_size} bytes for locals"),
-                owning_span: None,
-            });
+        if locals_size > compiler_constants::TWENTY_FOUR_BITS {
+            todo!("Enormous stack usage for locals.");
         }
+        self.cur_bytecode.push(Op {
+            opcode: Either::Left(VirtualOp::CFSI(VirtualImmediate24 {
+                value: locals_size as u32,
+            })),
+            comment: format!("free {locals_size} bytes for locals"),
+            owning_span: None,
+        });
     }
 
     pub(super) fn locals_base_reg(&self) -> &VirtualRegister {
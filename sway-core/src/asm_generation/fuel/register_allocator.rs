@@ -1,14 +1,18 @@
 use crate::{
     asm_generation::fuel::compiler_constants,
     asm_lang::{
-        allocated_ops::AllocatedRegister, virtual_register::*, ControlFlowOp, Label, Op, VirtualOp,
+        allocated_ops::AllocatedRegister, virtual_register::*, ControlFlowOp, Label, Op,
+        VirtualImmediate12, VirtualImmediate24, VirtualOp,
     },
 };
 
 use either::Either;
-use petgraph::graph::{node_index, NodeIndex};
+use petgraph::graph::NodeIndex;
 use rustc_hash::FxHashSet;
-use std::collections::{BTreeSet, HashMap};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+};
 use sway_error::error::CompileError;
 use sway_types::span::Span;
 
@@ -265,205 +269,994 @@ pub(crate) fn create_interference_graph(
     (interference_graph, reg_to_node_map)
 }
 
-/// Given a list of instructions `ops` and a corresponding interference_graph, generate a new,
-/// smaller list of instructions, where unnecessary MOVE instructions have been removed. When an
-/// unnecessary MOVE is detected and removed, the two virtual registers used by the MOVE are said
-/// to be "coalesced" and the two corresponding nodes in the graph are then merged.
+/// The weight of a register use or def is multiplied by this for each level of loop nesting it is
+/// found within, reflecting that it is likely to be executed many more times.
+const LOOP_DEPTH_WEIGHT: f64 = 10.0;
+
+/// Loops nested deeper than this are weighted as if they were at this depth.
+const MAX_WEIGHTED_LOOP_DEPTH: u32 = 6;
+
+/// Spilling a register to memory requires a store after every def and a load before every use,
+/// whereas rematerializing a constant only re-executes its cheap def before each use.
+const REMATERIALIZATION_COST_FACTOR: f64 = 0.5;
+
+/// Approximate the loop nesting depth of each instruction in `ops`.
 ///
-/// Two important aspects of this for our implementation:
-/// * When two registers are coalesced, a new node with a new virtual register (generated using the
-///   register sequencer) is created in the interference graph.
-/// * When a MOVE instruction is removed, the offset of each subsequent instruction has to be
-/// updated, as well as the immediate values for some or all jump instructions (`ji`, `jnei`, and
-/// `jnzi for now).
+/// Any jump backwards to an earlier label is treated as a loop back edge and every instruction
+/// between the label and the jump is considered to be within that loop.  This relies on the
+/// instructions being laid out in reverse post order, which is how the IR blocks are compiled.
 ///
-pub(crate) fn coalesce_registers(
-    ops: &[Op],
-    interference_graph: &mut InterferenceGraph,
-    reg_to_node_map: &mut HashMap<VirtualRegister, NodeIndex>,
-) -> Vec<Op> {
-    // A map from the virtual registers that are removed to the virtual registers that they are
-    // replaced with during the coalescing process.
-    let mut reg_to_reg_map: HashMap<&VirtualRegister, &VirtualRegister> = HashMap::new();
+pub(crate) fn loop_depths(ops: &[Op]) -> Vec<u32> {
+    let mut label_to_index: HashMap<Label, usize> = HashMap::new();
+    for (idx, op) in ops.iter().enumerate() {
+        if let Either::Right(ControlFlowOp::Label(op_label)) = op.opcode {
+            label_to_index.insert(op_label, idx);
+        }
+    }
+
+    let mut depths = vec![0; ops.len()];
+    for (jump_idx, op) in ops.iter().enumerate() {
+        if let Either::Right(
+            ControlFlowOp::Jump(label)
+            | ControlFlowOp::JumpIfNotEq(_, _, label)
+            | ControlFlowOp::JumpIfNotZero(_, label),
+        ) = &op.opcode
+        {
+            match label_to_index.get(label) {
+                Some(&label_idx) if label_idx <= jump_idx => {
+                    for depth in &mut depths[label_idx..=jump_idx] {
+                        *depth += 1;
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
 
-    // To hold the final *reduced* list of ops
-    let mut reduced_ops: Vec<Op> = Vec::with_capacity(ops.len());
+    depths
+}
 
+/// Find the virtual registers which may be rematerialized rather than spilled to memory.
+///
+/// These are registers defined exactly once by an instruction which loads a constant, either an
+/// immediate via `MOVI` or a value from the data section.  The defining instruction can be safely
+/// re-executed immediately before each use instead of storing and reloading the value.
+///
+pub(crate) fn rematerializable_registers(ops: &[Op]) -> HashMap<VirtualRegister, Op> {
+    let mut defs: HashMap<&VirtualRegister, Vec<&Op>> = HashMap::new();
     for op in ops {
-        match &op.opcode {
-            Either::Left(VirtualOp::MOVE(x, y)) => {
-                match (x, y) {
-                    (VirtualRegister::Virtual(_), VirtualRegister::Virtual(_)) => {
-                        // Use reg_to_reg_map to figure out what x and y have been replaced
-                        // with. We keep looking for mappings within reg_to_reg_map until we find a
-                        // register that doesn't map to any other.
-                        let mut r1 = x;
-                        while let Some(t) = reg_to_reg_map.get(r1) {
-                            r1 = t;
-                        }
-                        let mut r2 = y;
-                        while let Some(t) = reg_to_reg_map.get(r2) {
-                            r2 = t;
-                        }
+        for reg in op.def_registers() {
+            if matches!(reg, VirtualRegister::Virtual(_)) {
+                defs.entry(reg).or_default().push(op);
+            }
+        }
+    }
 
-                        // Find the interference graph nodes that corresponding to r1 and r2
-                        let ix1 = reg_to_node_map.get(r1).unwrap();
-                        let ix2 = reg_to_node_map.get(r2).unwrap();
+    defs.into_iter()
+        .filter_map(|(reg, reg_defs)| {
+            let [def_op] = reg_defs.as_slice() else {
+                return None;
+            };
+            matches!(
+                def_op.opcode,
+                Either::Left(VirtualOp::MOVI(..) | VirtualOp::LWDataId(..))
+            )
+            .then(|| (reg.clone(), (*def_op).clone()))
+        })
+        .collect()
+}
 
-                        // If r1 and r2 are the same, the MOVE instruction can be safely removed,
-                        // i.e., not added to reduced_ops
-                        if r1 == r2 {
-                            continue;
-                        }
+/// Estimate the cost of spilling each virtual register in `ops`.
+///
+/// The cost is the number of uses and defs of the register, each weighted by the loop depth at
+/// which they're found.  Rematerializable registers are cheaper to spill.  Registers in
+/// `unspillable` are given an infinite cost so they're only ever chosen as a last resort; these are
+/// registers which have already been spilled and whose live ranges are now as short as they can be.
+///
+/// The base register for the locals is never spilled since it is needed to address the spill
+/// slots.
+///
+pub(crate) fn compute_spill_costs(
+    ops: &[Op],
+    rematerializable: &HashMap<VirtualRegister, Op>,
+    unspillable: &FxHashSet<VirtualRegister>,
+) -> HashMap<VirtualRegister, f64> {
+    let mut costs: HashMap<VirtualRegister, f64> = HashMap::new();
+    for (op, depth) in ops.iter().zip(loop_depths(ops)) {
+        let weight = LOOP_DEPTH_WEIGHT.powi(depth.min(MAX_WEIGHTED_LOOP_DEPTH) as i32);
+        for reg in op.use_registers().into_iter().chain(op.def_registers()) {
+            if matches!(reg, VirtualRegister::Virtual(_)) {
+                *costs.entry(reg.clone()).or_default() += weight;
+            }
+        }
+    }
 
-                        // If r1 and r2 are connected in the interference graph (i.e. their
-                        // respective liveness ranges overalp), preserve the MOVE instruction by
-                        // adding it to reduced_ops
-                        if interference_graph.contains_edge(*ix1, *ix2) {
-                            reduced_ops.push(op.clone());
-                            continue;
-                        }
+    let locals_base_reg = find_locals_frame(ops).and_then(|frame| frame.base_reg);
+    for (reg, cost) in costs.iter_mut() {
+        if unspillable.contains(reg) || Some(reg) == locals_base_reg.as_ref() {
+            *cost = f64::INFINITY;
+        } else if rematerializable.contains_key(reg) {
+            *cost *= REMATERIALIZATION_COST_FACTOR;
+        }
+    }
 
-                        // The MOVE instruction can now be safely removed. That is, we simply don't
-                        // add it to the reduced_ops vector. Also, we combine the two nodes ix1 and
-                        // ix2 into ix1 and then we remove ix2 from the graph. We also have
-                        // to do some bookkeeping.
-                        //
-                        // Note that because the interference graph is of type StableGraph, the
-                        // node index corresponding to each virtual register does not change when
-                        // some graph nodes are added or removed.
-
-                        // Add all of ix2(r2)'s edges to `ix1(r1)`
-                        for neighbor in interference_graph.neighbors(*ix2).collect::<Vec<_>>() {
-                            interference_graph.add_edge(neighbor, *ix1, ());
-                        }
+    costs
+}
+
+/// The result of coloring the interference graph.
+pub(crate) enum Coloring {
+    /// Every virtual register was assigned a register.  Virtual registers which were coalesced
+    /// share the same register, making the MOVEs between them redundant.
+    Colored {
+        pool: RegisterPool,
+        coalesced_moves: usize,
+    },
+    /// The graph could not be colored.  These virtual registers must be spilled and the allocation
+    /// attempted again.
+    Spill(BTreeSet<VirtualRegister>),
+}
 
-                        // Remove ix2 by setting its weight to `None`.
-                        interference_graph[*ix2] = None;
+/// Given a list of instructions `ops`, the corresponding interference graph and the spill cost of
+/// each virtual register, assign a register to each virtual register or find a set of virtual
+/// registers to spill.
+///
+/// The algorithm used is iterated register coalescing (George and Appel, 1996), an extension of
+/// Chaitin-Briggs graph coloring which interleaves the removal of MOVE instructions with the
+/// simplification of the graph.  A MOVE is only coalesced when it is conservatively safe to do so,
+/// i.e., when the combined node is guaranteed to remain colorable.
+///
+/// Algorithm:
+/// ===============================================================================================
+/// 1. Build: Create the worklists of nodes.  A node is 'significant' if it has k or more
+///    neighbors and 'move related' if it is the source or destination of a MOVE.
+/// 2. Simplify: Remove a non move related node with fewer than k neighbors from the graph and push
+///    it on the stack.  It can always be colored, whatever colors its neighbors are assigned.
+/// 3. Coalesce: Combine the nodes of a MOVE if the Briggs or George conservative test passes.
+///    Repeat 2. and 3. while possible.
+/// 4. Freeze: If neither is possible, pick a low degree move related node and give up on
+///    coalescing its MOVEs, allowing it to be simplified.
+/// 5. Spill: If there are only significant nodes left, pick the one with the lowest spill cost
+///    relative to its degree and optimistically push it on the stack as a potential spill.
+/// 6. Select: Pop the stack, assigning each node a register not used by its neighbors.  If a
+///    potential spill can't be colored it becomes an actual spill.
+/// ===============================================================================================
+///
+pub(crate) fn color_interference_graph(
+    ops: &[Op],
+    interference_graph: &InterferenceGraph,
+    spill_costs: &HashMap<VirtualRegister, f64>,
+) -> Coloring {
+    let mut node_ids: HashMap<NodeIndex, usize> = HashMap::new();
+    let mut regs = Vec::new();
+    for node in interference_graph.node_indices() {
+        if let Some(reg) = &interference_graph[node] {
+            node_ids.insert(node, regs.len());
+            regs.push(reg.clone());
+        }
+    }
 
-                        // Update the register maps
-                        reg_to_node_map.insert(r2.clone(), *ix1);
-                        reg_to_reg_map.insert(r2, r1);
-                    }
-                    _ => {
-                        // Preserve the MOVE instruction if either registers used in the MOVE is
-                        // special registers (i.e. *not* a VirtualRegister::Virtual(_))
-                        reduced_ops.push(op.clone());
-                    }
-                }
+    let edges = interference_graph
+        .edge_indices()
+        .filter_map(|edge| {
+            let (a, b) = interference_graph.edge_endpoints(edge)?;
+            Some((*node_ids.get(&a)?, *node_ids.get(&b)?))
+        })
+        .collect::<Vec<_>>();
+
+    let reg_ids: HashMap<&VirtualRegister, usize> =
+        regs.iter().enumerate().map(|(id, reg)| (reg, id)).collect();
+    let moves = ops
+        .iter()
+        .filter_map(|op| match &op.opcode {
+            Either::Left(VirtualOp::MOVE(dst, src)) => {
+                Some((*reg_ids.get(dst)?, *reg_ids.get(src)?))
             }
-            _ => {
-                // Preserve all other instructions
-                reduced_ops.push(op.clone());
+            _ => None,
+        })
+        .filter(|(dst, src)| dst != src)
+        .collect::<Vec<_>>();
+
+    let costs = regs
+        .iter()
+        .map(|reg| spill_costs.get(reg).copied().unwrap_or(0.0))
+        .collect();
+
+    let mut coalescing = IteratedCoalescing::new(
+        compiler_constants::NUM_ALLOCATABLE_REGISTERS as usize,
+        regs,
+        &edges,
+        moves,
+        costs,
+    );
+    match coalescing.run() {
+        Ok(colors) => {
+            let mut pool = RegisterPool::init();
+            for (reg, color) in coalescing.regs.into_iter().zip(colors) {
+                pool.registers[color].used_by.insert(reg);
+            }
+            Coloring::Colored {
+                pool,
+                coalesced_moves: coalescing.coalesced_moves,
+            }
+        }
+        Err(spills) => Coloring::Spill(spills),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum NodeState {
+    Simplify,
+    Freeze,
+    Spill,
+    SelectStack,
+    Coalesced,
+    Colored,
+    Spilled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MoveState {
+    Worklist,
+    Active,
+    Coalesced,
+    Constrained,
+    Frozen,
+}
+
+/// The state for the iterated register coalescing algorithm.  Nodes are the indices of the virtual
+/// registers in `regs` and moves are the indices of the MOVEs in `moves`.  The naming follows the
+/// presentation in Appel's 'Modern Compiler Implementation'.
+struct IteratedCoalescing {
+    num_colors: usize,
+    regs: Vec<VirtualRegister>,
+    spill_costs: Vec<f64>,
+
+    adj_set: FxHashSet<(usize, usize)>,
+    adj_list: Vec<Vec<usize>>,
+    degree: Vec<usize>,
+    state: Vec<NodeState>,
+    alias: Vec<usize>,
+    color: Vec<Option<usize>>,
+
+    moves: Vec<(usize, usize)>,
+    move_state: Vec<MoveState>,
+    move_list: Vec<Vec<usize>>,
+
+    simplify_worklist: BTreeSet<usize>,
+    freeze_worklist: BTreeSet<usize>,
+    spill_worklist: BTreeSet<usize>,
+    worklist_moves: BTreeSet<usize>,
+    select_stack: Vec<usize>,
+
+    coalesced_moves: usize,
+}
+
+impl IteratedCoalescing {
+    fn new(
+        num_colors: usize,
+        regs: Vec<VirtualRegister>,
+        edges: &[(usize, usize)],
+        moves: Vec<(usize, usize)>,
+        spill_costs: Vec<f64>,
+    ) -> Self {
+        let num_nodes = regs.len();
+        let mut coalescing = IteratedCoalescing {
+            num_colors,
+            regs,
+            spill_costs,
+            adj_set: FxHashSet::default(),
+            adj_list: vec![Vec::new(); num_nodes],
+            degree: vec![0; num_nodes],
+            state: vec![NodeState::Simplify; num_nodes],
+            alias: (0..num_nodes).collect(),
+            color: vec![None; num_nodes],
+            move_state: vec![MoveState::Worklist; moves.len()],
+            move_list: vec![Vec::new(); num_nodes],
+            worklist_moves: (0..moves.len()).collect(),
+            moves,
+            simplify_worklist: BTreeSet::new(),
+            freeze_worklist: BTreeSet::new(),
+            spill_worklist: BTreeSet::new(),
+            select_stack: Vec::with_capacity(num_nodes),
+            coalesced_moves: 0,
+        };
+
+        for &(a, b) in edges {
+            coalescing.add_edge(a, b);
+        }
+        for (move_idx, &(dst, src)) in coalescing.moves.iter().enumerate() {
+            coalescing.move_list[dst].push(move_idx);
+            coalescing.move_list[src].push(move_idx);
+        }
+
+        coalescing
+    }
+
+    /// Color the graph, returning the color for each node or the registers to spill.
+    fn run(&mut self) -> Result<Vec<usize>, BTreeSet<VirtualRegister>> {
+        self.make_worklist();
+        loop {
+            if let Some(node) = pop_first(&mut self.simplify_worklist) {
+                self.simplify(node);
+            } else if let Some(move_idx) = pop_first(&mut self.worklist_moves) {
+                self.coalesce(move_idx);
+            } else if let Some(node) = pop_first(&mut self.freeze_worklist) {
+                self.freeze(node);
+            } else if !self.spill_worklist.is_empty() {
+                self.select_spill();
+            } else {
+                break;
+            }
+        }
+        self.assign_colors();
+
+        let spilled_nodes = (0..self.regs.len())
+            .filter(|&node| self.state[node] == NodeState::Spilled)
+            .collect::<FxHashSet<_>>();
+        if spilled_nodes.is_empty() {
+            Ok((0..self.regs.len())
+                .map(|node| {
+                    self.color[self.get_alias(node)]
+                        .expect("All non-spilled nodes have been colored.")
+                })
+                .collect())
+        } else {
+            // Spill every register in the spilled nodes, including those which were coalesced into
+            // them.  Coalescing will be redone from scratch after the spill code is inserted.
+            Err((0..self.regs.len())
+                .filter(|&node| spilled_nodes.contains(&self.get_alias(node)))
+                .map(|node| self.regs[node].clone())
+                .collect())
+        }
+    }
+
+    fn add_edge(&mut self, a: usize, b: usize) {
+        if a != b && self.adj_set.insert((a, b)) {
+            self.adj_set.insert((b, a));
+            self.adj_list[a].push(b);
+            self.adj_list[b].push(a);
+            self.degree[a] += 1;
+            self.degree[b] += 1;
+        }
+    }
+
+    fn make_worklist(&mut self) {
+        for node in 0..self.regs.len() {
+            if self.degree[node] >= self.num_colors {
+                self.set_state(node, NodeState::Spill);
+            } else if self.is_move_related(node) {
+                self.set_state(node, NodeState::Freeze);
+            } else {
+                self.set_state(node, NodeState::Simplify);
+            }
+        }
+    }
+
+    /// Move a node to a new state, keeping the worklists in sync.
+    fn set_state(&mut self, node: usize, state: NodeState) {
+        match self.state[node] {
+            NodeState::Simplify => self.simplify_worklist.remove(&node),
+            NodeState::Freeze => self.freeze_worklist.remove(&node),
+            NodeState::Spill => self.spill_worklist.remove(&node),
+            _ => false,
+        };
+        match state {
+            NodeState::Simplify => self.simplify_worklist.insert(node),
+            NodeState::Freeze => self.freeze_worklist.insert(node),
+            NodeState::Spill => self.spill_worklist.insert(node),
+            _ => false,
+        };
+        self.state[node] = state;
+    }
+
+    /// The neighbors of a node which are still in the graph.
+    fn adjacent(&self, node: usize) -> Vec<usize> {
+        self.adj_list[node]
+            .iter()
+            .copied()
+            .filter(|&n| !matches!(self.state[n], NodeState::SelectStack | NodeState::Coalesced))
+            .collect()
+    }
+
+    /// The MOVEs involving a node which may yet be coalesced.
+    fn node_moves(&self, node: usize) -> Vec<usize> {
+        self.move_list[node]
+            .iter()
+            .copied()
+            .filter(|&m| matches!(self.move_state[m], MoveState::Active | MoveState::Worklist))
+            .collect()
+    }
+
+    fn is_move_related(&self, node: usize) -> bool {
+        !self.node_moves(node).is_empty()
+    }
+
+    fn get_alias(&self, mut node: usize) -> usize {
+        while self.state[node] == NodeState::Coalesced {
+            node = self.alias[node];
+        }
+        node
+    }
+
+    fn simplify(&mut self, node: usize) {
+        // The node has already been popped from the simplify worklist.
+        self.state[node] = NodeState::SelectStack;
+        self.select_stack.push(node);
+        for neighbor in self.adjacent(node) {
+            self.decrement_degree(neighbor);
+        }
+    }
+
+    fn decrement_degree(&mut self, node: usize) {
+        let degree = self.degree[node];
+        self.degree[node] = degree - 1;
+        if degree == self.num_colors && self.state[node] == NodeState::Spill {
+            // The node is no longer significant, so the MOVEs of it and its neighbors may now be
+            // coalescable.
+            let mut nodes = self.adjacent(node);
+            nodes.push(node);
+            self.enable_moves(&nodes);
+            if self.is_move_related(node) {
+                self.set_state(node, NodeState::Freeze);
+            } else {
+                self.set_state(node, NodeState::Simplify);
+            }
+        }
+    }
+
+    fn enable_moves(&mut self, nodes: &[usize]) {
+        for &node in nodes {
+            for move_idx in self.node_moves(node) {
+                if self.move_state[move_idx] == MoveState::Active {
+                    self.move_state[move_idx] = MoveState::Worklist;
+                    self.worklist_moves.insert(move_idx);
+                }
             }
         }
     }
 
-    // Create a *final* reg-to-reg map that We keep looking for mappings within reg_to_reg_map
-    // until we find a register that doesn't map to any other.
-    let mut final_reg_to_reg_map: HashMap<&VirtualRegister, &VirtualRegister> = HashMap::new();
-    for reg in reg_to_reg_map.keys() {
-        let mut temp = reg;
-        while let Some(t) = reg_to_reg_map.get(temp) {
-            temp = t;
+    fn coalesce(&mut self, move_idx: usize) {
+        // The move has already been popped from the worklist.
+        let (dst, src) = self.moves[move_idx];
+        let u = self.get_alias(dst);
+        let v = self.get_alias(src);
+
+        if u == v {
+            self.move_state[move_idx] = MoveState::Coalesced;
+            self.coalesced_moves += 1;
+            self.add_work_list(u);
+        } else if self.adj_set.contains(&(u, v)) {
+            self.move_state[move_idx] = MoveState::Constrained;
+            self.add_work_list(u);
+            self.add_work_list(v);
+        } else if self.briggs_test(u, v) || self.george_test(u, v) {
+            self.move_state[move_idx] = MoveState::Coalesced;
+            self.coalesced_moves += 1;
+            self.combine(u, v);
+            self.add_work_list(u);
+        } else {
+            self.move_state[move_idx] = MoveState::Active;
+        }
+    }
+
+    /// Move a node which is no longer move related, and which has low degree, to the simplify
+    /// worklist.
+    fn add_work_list(&mut self, node: usize) {
+        if self.state[node] == NodeState::Freeze
+            && !self.is_move_related(node)
+            && self.degree[node] < self.num_colors
+        {
+            self.set_state(node, NodeState::Simplify);
+        }
+    }
+
+    /// Briggs: the combined node will have fewer than k significant neighbors.
+    fn briggs_test(&self, u: usize, v: usize) -> bool {
+        let mut neighbors = self.adjacent(u);
+        neighbors.extend(self.adjacent(v));
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors
+            .into_iter()
+            .filter(|&n| self.degree[n] >= self.num_colors)
+            .count()
+            < self.num_colors
+    }
+
+    /// George: every neighbor of `v` is either insignificant or already a neighbor of `u`.
+    fn george_test(&self, u: usize, v: usize) -> bool {
+        self.adjacent(v)
+            .into_iter()
+            .all(|t| self.degree[t] < self.num_colors || self.adj_set.contains(&(t, u)))
+    }
+
+    /// Merge `v` into `u`.
+    fn combine(&mut self, u: usize, v: usize) {
+        self.set_state(v, NodeState::Coalesced);
+        self.alias[v] = u;
+        let v_moves = std::mem::take(&mut self.move_list[v]);
+        self.move_list[u].extend(v_moves.iter().copied());
+        self.move_list[v] = v_moves;
+        self.spill_costs[u] += self.spill_costs[v];
+        self.enable_moves(&[v]);
+        for neighbor in self.adjacent(v) {
+            self.add_edge(neighbor, u);
+            self.decrement_degree(neighbor);
+        }
+        if self.degree[u] >= self.num_colors && self.state[u] == NodeState::Freeze {
+            self.set_state(u, NodeState::Spill);
+        }
+    }
+
+    fn freeze(&mut self, node: usize) {
+        // The node has already been popped from the freeze worklist.
+        self.state[node] = NodeState::Simplify;
+        self.simplify_worklist.insert(node);
+        self.freeze_moves(node);
+    }
+
+    /// Give up on coalescing the MOVEs involving `node`.
+    fn freeze_moves(&mut self, node: usize) {
+        let u = self.get_alias(node);
+        for move_idx in self.node_moves(node) {
+            let (dst, src) = self.moves[move_idx];
+            let v = if self.get_alias(src) == u {
+                self.get_alias(dst)
+            } else {
+                self.get_alias(src)
+            };
+            self.move_state[move_idx] = MoveState::Frozen;
+            self.worklist_moves.remove(&move_idx);
+            if self.state[v] == NodeState::Freeze
+                && !self.is_move_related(v)
+                && self.degree[v] < self.num_colors
+            {
+                self.set_state(v, NodeState::Simplify);
+            }
         }
-        final_reg_to_reg_map.insert(reg, temp);
     }
 
-    // Update the registers for all instructions using final_reg_to_reg_map
-    for new_op in &mut reduced_ops {
-        *new_op = new_op.update_register(&final_reg_to_reg_map);
+    /// Pick the significant node with the lowest spill cost relative to its degree, i.e., the one
+    /// which is cheapest to spill and whose removal best simplifies the graph.
+    fn select_spill(&mut self) {
+        let node = self
+            .spill_worklist
+            .iter()
+            .copied()
+            .min_by(|&a, &b| {
+                let a_cost = self.spill_costs[a] / self.degree[a] as f64;
+                let b_cost = self.spill_costs[b] / self.degree[b] as f64;
+                a_cost.partial_cmp(&b_cost).unwrap_or(Ordering::Equal)
+            })
+            .expect("Spill worklist is not empty.");
+        self.set_state(node, NodeState::Simplify);
+        self.freeze_moves(node);
+    }
+
+    fn assign_colors(&mut self) {
+        while let Some(node) = self.select_stack.pop() {
+            let mut ok_colors = vec![true; self.num_colors];
+            for &neighbor in &self.adj_list[node] {
+                let neighbor = self.get_alias(neighbor);
+                if let (NodeState::Colored, Some(color)) =
+                    (self.state[neighbor], self.color[neighbor])
+                {
+                    ok_colors[color] = false;
+                }
+            }
+            match ok_colors.iter().position(|&ok| ok) {
+                Some(color) => {
+                    self.state[node] = NodeState::Colored;
+                    self.color[node] = Some(color);
+                }
+                None => self.state[node] = NodeState::Spilled,
+            }
+        }
     }
+}
+
+fn pop_first(set: &mut BTreeSet<usize>) -> Option<usize> {
+    let first = set.iter().next().copied()?;
+    set.remove(&first);
+    Some(first)
+}
+
+/// The stack frame reserved for the locals of a function, see `FuelAsmBuilder::init_locals()`.
+/// Spilled registers are given slots at the end of this frame.
+struct LocalsFrame {
+    /// The index of the `CFEI` which reserves the frame.
+    cfe_idx: usize,
+    /// The index of the `CFSI` which frees the frame, if the function returns to a caller.
+    cfs_idx: Option<usize>,
+    /// The size of the frame in bytes.
+    size: u32,
+    /// The register holding the address of the start of the frame.  This is `None` if it was
+    /// removed as redundant because the function never refers to its locals.
+    base_reg: Option<VirtualRegister>,
+}
 
-    reduced_ops
+/// The function prologue saves `$sp` to a virtual register immediately before reserving the stack
+/// frame for its locals with a `CFEI`, and the epilogue frees it with a `CFSI`.  Any other frame
+/// manipulations can only come from inline assembly within the function body, so the first `CFEI`
+/// and the last `CFSI` are those for the locals.
+fn find_locals_frame(ops: &[Op]) -> Option<LocalsFrame> {
+    let (cfe_idx, size) = ops
+        .iter()
+        .enumerate()
+        .find_map(|(idx, op)| match &op.opcode {
+            Either::Left(VirtualOp::CFEI(imm)) => Some((idx, imm.value)),
+            _ => None,
+        })?;
+    let base_reg = match cfe_idx.checked_sub(1).map(|idx| &ops[idx].opcode) {
+        Some(Either::Left(VirtualOp::MOVE(
+            base_reg @ VirtualRegister::Virtual(_),
+            VirtualRegister::Constant(ConstantRegister::StackPointer),
+        ))) => Some(base_reg.clone()),
+        _ => None,
+    };
+    let cfs_idx = ops
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(idx, op)| match &op.opcode {
+            Either::Left(VirtualOp::CFSI(imm)) if imm.value == size && idx > cfe_idx => Some(idx),
+            _ => None,
+        });
+
+    Some(LocalsFrame {
+        cfe_idx,
+        cfs_idx,
+        size,
+        base_reg,
+    })
 }
 
-/// Given an interference graph and a integer k, figure out if the graph k-colorable. Graph
-/// coloring is an NP-complete problem, but the algorithm below is a simple stack based
-/// approximation that relies on the fact that any node n in the graph that has fewer than k
-/// neighbors can always be colored.
+/// Given a list of instructions `ops` and a set of virtual registers to spill, generate a new list
+/// of instructions where the live range of each spilled register is split into many very short
+/// ranges, making the interference graph easier to color.
 ///
-/// Algorithm:
-/// ===============================================================================================
-/// 1. Pick any node n such that degree(n) < k and put it on the stack along with its neighbors.
-/// 2. Remove node n and all its edges from the graph
-///    - This may make some new nodes have fewer than k neighbours which is nice.
-/// 3. If some vertex n still has k or more neighbors, then the graph is not k colorable, and we
-///    have to spill. For now, we will just error out, but we may be able to do better in the
-///    future.
-/// ===============================================================================================
+/// * Rematerializable registers have their single def removed and re-executed immediately before
+///   each use.
+/// * Every other register is given a word sized slot at the end of the function's locals frame.  It
+///   is stored to the slot after each def and loaded from it before each use.
 ///
-/// As we don't implement spilling just yet, I've modified the algorithm above to assume k=infinity
-/// and moving the colorability checking until the register assignment phase. The reason for this
-/// is that the algorithm above can be too conservative and may bail our early even though a valid
-/// assignment is actually available. We can revisit this decision when decide to implement
-/// spilling.
+/// Instructions before the locals frame has been reserved, i.e., in the function prologue, keep
+/// their values in registers; any spilled registers they define are stored immediately after the
+/// frame is reserved.  Similarly, any spilled registers used after the frame is freed in the
+/// epilogue are loaded immediately before it is freed.
 ///
-pub(crate) fn color_interference_graph(
-    interference_graph: &mut InterferenceGraph,
-) -> Vec<(VirtualRegister, BTreeSet<VirtualRegister>)> {
-    let mut stack = Vec::with_capacity(interference_graph.node_count());
-
-    // Raw for loop here is safe because we are not actually removing any nodes from the graph at
-    // any point (i.e. we're never calling `remove_node()`). This means that each `index` below
-    // correspond to a valid `NodeIndex` in the graph.
-    for index in 0..interference_graph.node_count() {
-        // Convert to a `NodeIndex`
-        let node = node_index(index);
-
-        // Nodes with weight `None` are dead
-        if interference_graph[node].is_none() {
+pub(crate) fn spill_registers(
+    ops: &[Op],
+    spills: &BTreeSet<VirtualRegister>,
+    rematerializable: &HashMap<VirtualRegister, Op>,
+) -> Result<Vec<Op>, CompileError> {
+    let frame = find_locals_frame(ops).ok_or(CompileError::Internal(
+        "The allocator cannot resolve a register mapping for this program and is unable to \
+         spill registers as the function has no stack frame.",
+        Span::dummy(),
+    ))?;
+
+    // The spill slots are addressed relative to the locals base register, so if it was removed
+    // then it must be saved again.
+    let Some(base_reg) = frame.base_reg else {
+        let mut ops = ops.to_vec();
+        ops.insert(
+            frame.cfe_idx,
+            Op::register_move(
+                VirtualRegister::Virtual("spill_base".into()),
+                VirtualRegister::Constant(ConstantRegister::StackPointer),
+                "save locals base register",
+                None,
+            ),
+        );
+        return spill_registers(&ops, spills, rematerializable);
+    };
+
+    // The locals base register is needed to address the spill slots so it can't be spilled itself.
+    let spills = spills
+        .iter()
+        .filter(|reg| **reg != base_reg)
+        .collect::<BTreeSet<_>>();
+
+    // Assign a stack slot to each spilled register, as a word offset from the locals base.
+    let spill_slots: HashMap<&VirtualRegister, u64> = spills
+        .iter()
+        .copied()
+        .filter(|reg| !rematerializable.contains_key(*reg))
+        .enumerate()
+        .map(|(idx, reg)| (reg, frame.size as u64 / 8 + idx as u64))
+        .collect();
+    let frame_size = frame.size as u64 + spill_slots.len() as u64 * 8;
+    if frame_size > compiler_constants::TWENTY_FOUR_BITS
+        || spill_slots
+            .values()
+            .any(|&slot| slot > compiler_constants::TWELVE_BITS)
+    {
+        return Err(CompileError::Internal(
+            "The allocator cannot resolve a register mapping for this program as there are too \
+             many locals to address the stack slots for spilled registers.",
+            Span::dummy(),
+        ));
+    }
+
+    let spill_store = |reg: &VirtualRegister, slot: u64| Op {
+        opcode: Either::Left(VirtualOp::SW(
+            base_reg.clone(),
+            reg.clone(),
+            VirtualImmediate12 { value: slot as u16 },
+        )),
+        comment: "spill register to stack".into(),
+        owning_span: None,
+    };
+    let spill_load = |reg: &VirtualRegister, slot: u64| Op {
+        opcode: Either::Left(VirtualOp::LW(
+            reg.clone(),
+            base_reg.clone(),
+            VirtualImmediate12 { value: slot as u16 },
+        )),
+        comment: "reload spilled register from stack".into(),
+        owning_span: None,
+    };
+
+    let mut spilled_ops = Vec::with_capacity(ops.len());
+    for (idx, op) in ops.iter().enumerate() {
+        if idx == frame.cfe_idx {
+            spilled_ops.push(Op {
+                opcode: Either::Left(VirtualOp::CFEI(VirtualImmediate24 {
+                    value: frame_size as u32,
+                })),
+                comment: format!("allocate {frame_size} bytes for locals and spills"),
+                owning_span: op.owning_span.clone(),
+            });
+
+            // Store the spilled registers which were defined in the prologue.
+            let prologue_defs = ops[..idx]
+                .iter()
+                .flat_map(|op| op.def_registers())
+                .collect::<BTreeSet<_>>();
+            for reg in prologue_defs {
+                if let Some(&slot) = spill_slots.get(reg) {
+                    spilled_ops.push(spill_store(reg, slot));
+                }
+            }
             continue;
         }
 
-        // Grab all neighbors with node weight not equal to `None`
-        let neighbors = interference_graph
-            .neighbors(node)
-            .filter_map(|n| interference_graph[n].clone())
-            .collect();
+        if Some(idx) == frame.cfs_idx {
+            // Load the spilled registers which are used in the epilogue.
+            let epilogue_uses = ops[idx + 1..]
+                .iter()
+                .flat_map(|op| op.use_registers())
+                .collect::<BTreeSet<_>>();
+            for reg in epilogue_uses {
+                if let Some(&slot) = spill_slots.get(reg) {
+                    spilled_ops.push(spill_load(reg, slot));
+                }
+            }
+
+            spilled_ops.push(Op {
+                opcode: Either::Left(VirtualOp::CFSI(VirtualImmediate24 {
+                    value: frame_size as u32,
+                })),
+                comment: format!("free {frame_size} bytes for locals and spills"),
+                owning_span: op.owning_span.clone(),
+            });
+            continue;
+        }
+
+        // Drop the defs of rematerialized registers, they'll be recreated where they're needed.
+        if op
+            .def_registers()
+            .iter()
+            .any(|reg| spills.contains(*reg) && rematerializable.contains_key(*reg))
+        {
+            continue;
+        }
+
+        let in_frame = idx > frame.cfe_idx && frame.cfs_idx.map_or(true, |cfs_idx| idx < cfs_idx);
+        for reg in op.use_registers() {
+            if !spills.contains(reg) {
+                continue;
+            }
+            if let Some(remat_op) = rematerializable.get(reg) {
+                spilled_ops.push(Op {
+                    comment: "rematerialize spilled register".into(),
+                    ..remat_op.clone()
+                });
+            } else if in_frame {
+                spilled_ops.push(spill_load(reg, spill_slots[reg]));
+            }
+        }
 
-        // Build the stack
-        stack.push((interference_graph[node].clone().unwrap(), neighbors));
+        spilled_ops.push(op.clone());
 
-        // Remove `node` by setting its weight to `None`.
-        interference_graph[node] = None;
+        if in_frame {
+            for reg in op.def_registers() {
+                if let Some(&slot) = spill_slots.get(reg) {
+                    spilled_ops.push(spill_store(reg, slot));
+                }
+            }
+        }
     }
 
-    stack
+    Ok(spilled_ops)
 }
 
-/// Use the stack generated by the coloring algorithm to figure out a register assignment for each
-/// virtual register. The idea here is to successively pop the stack while selecting a register to
-/// each virtual register. A register r is available to a virtual register v if the intersection of
-/// the neighbors of v (available from the stack) and the list of virtual registers already used by
-/// r (available in the used_by field) is empty.
-///
-pub(crate) fn assign_registers(
-    stack: &mut Vec<(VirtualRegister, BTreeSet<VirtualRegister>)>,
-) -> Result<RegisterPool, CompileError> {
-    let mut pool = RegisterPool::init();
-    while let Some((reg, neighbors)) = stack.pop() {
-        if matches!(reg, VirtualRegister::Virtual(_)) {
-            let available =
-                pool.registers
-                    .iter_mut()
-                    .find(|RegisterAllocationStatus { reg: _, used_by }| {
-                        neighbors.intersection(used_by).count() == 0
-                    });
-
-            if let Some(RegisterAllocationStatus { reg: _, used_by }) = available {
-                used_by.insert(reg.clone());
-            } else {
-                // Error out for now if no available register is found
-                return Err(CompileError::Internal(
-                    "The allocator cannot resolve a register mapping for this program. \
-                     This is a temporary artifact of the early stage version of this \
-                     compiler.  Using #[inline(never)] on some functions may help.",
-                    Span::dummy(),
-                ));
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asm_generation::fuel::abstract_instruction_set::AbstractInstructionSet;
+    use crate::asm_lang::{allocated_ops::AllocatedOpcode, VirtualImmediate18};
+
+    fn virtual_reg(name: &str) -> VirtualRegister {
+        VirtualRegister::Virtual(name.into())
+    }
+
+    fn const_reg(reg: ConstantRegister) -> VirtualRegister {
+        VirtualRegister::Constant(reg)
+    }
+
+    fn op(opcode: VirtualOp) -> Op {
+        Op::unowned_new_with_comment(opcode, "")
+    }
+
+    fn cfei(value: u32) -> Op {
+        op(VirtualOp::CFEI(VirtualImmediate24 { value }))
+    }
+
+    fn cfsi(value: u32) -> Op {
+        op(VirtualOp::CFSI(VirtualImmediate24 { value }))
+    }
+
+    /// A function which needs one more register than there are allocatable registers: each of its
+    /// values is defined before any of them is used.  The values aren't constants, so they can't
+    /// be rematerialized.
+    fn high_pressure_ops(locals_frame: Vec<Op>, epilogue: Vec<Op>) -> Vec<Op> {
+        let num_regs = compiler_constants::NUM_ALLOCATABLE_REGISTERS as usize + 1;
+        let one = const_reg(ConstantRegister::One);
+        let mut ops = locals_frame;
+        for idx in 0..num_regs {
+            ops.push(op(VirtualOp::ADD(
+                virtual_reg(&format!("r{idx}")),
+                one.clone(),
+                one.clone(),
+            )));
+        }
+        ops.push(op(VirtualOp::ADD(
+            virtual_reg("sum0"),
+            virtual_reg("r0"),
+            virtual_reg("r1"),
+        )));
+        for idx in 2..num_regs {
+            ops.push(op(VirtualOp::ADD(
+                virtual_reg(&format!("sum{}", idx - 1)),
+                virtual_reg(&format!("sum{}", idx - 2)),
+                virtual_reg(&format!("r{idx}")),
+            )));
         }
+        ops.extend(epilogue);
+        ops.push(op(VirtualOp::RET(virtual_reg(&format!(
+            "sum{}",
+            num_regs - 2
+        )))));
+        ops
     }
 
-    Ok(pool)
+    #[test]
+    fn coalesce_move() {
+        let (a, b, c) = (virtual_reg("a"), virtual_reg("b"), virtual_reg("c"));
+        let ops = vec![
+            op(VirtualOp::MOVI(a.clone(), VirtualImmediate18 { value: 1 })),
+            Op::register_move(b.clone(), a.clone(), "", None),
+            op(VirtualOp::ADD(c.clone(), b.clone(), b.clone())),
+            op(VirtualOp::RET(c)),
+        ];
+        let live_out = liveness_analysis(&ops);
+        let (interference_graph, _) = create_interference_graph(&ops, &live_out);
+        let spill_costs = compute_spill_costs(&ops, &HashMap::new(), &FxHashSet::default());
+
+        let Coloring::Colored {
+            pool,
+            coalesced_moves,
+        } = color_interference_graph(&ops, &interference_graph, &spill_costs)
+        else {
+            panic!("the registers should be colorable");
+        };
+        assert_eq!(coalesced_moves, 1);
+        assert!(pool.get_register(&a).is_some());
+        assert_eq!(pool.get_register(&a), pool.get_register(&b));
+    }
+
+    #[test]
+    fn spill_under_pressure() {
+        let locals_base = virtual_reg("locals_base");
+        let ops = high_pressure_ops(
+            vec![
+                Op::register_move(
+                    locals_base,
+                    const_reg(ConstantRegister::StackPointer),
+                    "",
+                    None,
+                ),
+                cfei(8),
+            ],
+            vec![cfsi(8)],
+        );
+
+        let (allocated, metric) = AbstractInstructionSet { ops }.allocate_registers().unwrap();
+        assert!(metric.spilled_registers > 0);
+        assert_eq!(metric.rematerialized_registers, 0);
+
+        // The frame is extended with a slot for each spilled register, after the locals.
+        let frame_size = 8 + 8 * metric.spilled_registers as u32;
+        let frame_ops = allocated
+            .ops
+            .iter()
+            .filter_map(|op| match &op.opcode {
+                Either::Left(AllocatedOpcode::CFEI(imm)) => Some(("cfei", imm.value)),
+                Either::Left(AllocatedOpcode::CFSI(imm)) => Some(("cfsi", imm.value)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(frame_ops, vec![("cfei", frame_size), ("cfsi", frame_size)]);
+        assert!(allocated
+            .ops
+            .iter()
+            .any(|op| matches!(op.opcode, Either::Left(AllocatedOpcode::SW(..)))));
+        assert!(allocated
+            .ops
+            .iter()
+            .any(|op| matches!(op.opcode, Either::Left(AllocatedOpcode::LW(..)))));
+    }
+
+    #[test]
+    fn spill_without_locals_base() {
+        // A function with no locals has no base register for its frame, so one must be saved to
+        // address the spill slots.
+        let ops = high_pressure_ops(vec![cfei(0)], vec![cfsi(0)]);
+        let spills = [virtual_reg("r0")].into_iter().collect();
+
+        let spilled_ops = spill_registers(&ops, &spills, &HashMap::new()).unwrap();
+        let spill_base = virtual_reg("spill_base");
+        assert!(matches!(
+            &spilled_ops[0].opcode,
+            Either::Left(VirtualOp::MOVE(
+                reg,
+                VirtualRegister::Constant(ConstantRegister::StackPointer),
+            )) if *reg == spill_base
+        ));
+        assert!(matches!(
+            &spilled_ops[1].opcode,
+            Either::Left(VirtualOp::CFEI(VirtualImmediate24 { value: 8 }))
+        ));
+        assert!(matches!(
+            &spilled_ops[3].opcode,
+            Either::Left(VirtualOp::SW(base, reg, VirtualImmediate12 { value: 0 }))
+                if *base == spill_base && *reg == virtual_reg("r0")
+        ));
+
+        // The whole function can then be allocated.
+        let (_, metric) = AbstractInstructionSet { ops }.allocate_registers().unwrap();
+        assert!(metric.spilled_registers > 0);
+    }
+
+    #[test]
+    fn empty_frame_is_removed() {
+        let a = virtual_reg("a");
+        let one = const_reg(ConstantRegister::One);
+        let ops = vec![
+            cfei(0),
+            op(VirtualOp::ADD(a.clone(), one.clone(), one)),
+            cfsi(0),
+            op(VirtualOp::RET(a)),
+        ];
+
+        let (allocated, metric) = AbstractInstructionSet { ops }.allocate_registers().unwrap();
+        assert_eq!(metric.spilled_registers, 0);
+        assert!(!allocated.ops.iter().any(|op| matches!(
+            op.opcode,
+            Either::Left(AllocatedOpcode::CFEI(_) | AllocatedOpcode::CFSI(_))
+        )));
+        assert_eq!(allocated.ops.len(), 2);
+    }
 }
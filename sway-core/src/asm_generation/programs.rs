@@ -11,8 +11,12 @@ use super::fuel::{
 use crate::{
     asm_lang::{allocated_ops::AllocatedOp, Label},
     decl_engine::DeclRefFunction,
+    source_map::FunctionLocals,
 };
 
+use sway_types::Span;
+use sway_utils::RegisterAllocationMetric;
+
 type SelectorOpt = Option<[u8; 4]>;
 type FnName = String;
 type ImmOffset = u64;
@@ -36,6 +40,7 @@ pub(super) struct AbstractProgram {
     entries: Vec<AbstractEntry>,
     non_entries: Vec<AbstractInstructionSet>,
     reg_seqr: RegisterSequencer,
+    function_locals: Vec<(Span, FunctionLocals)>,
 }
 
 /// The entry point of an abstract program.
@@ -55,6 +60,8 @@ pub(super) struct AllocatedProgram {
     prologue: AllocatedAbstractInstructionSet,
     functions: Vec<AllocatedAbstractInstructionSet>,
     entries: Vec<(SelectorOpt, Label, FnName, Option<DeclRefFunction>)>,
+    register_allocation_metrics: Vec<RegisterAllocationMetric>,
+    function_locals: Vec<(Span, FunctionLocals)>,
 }
 
 /// A FinalProgram represents code which may be serialized to VM bytecode.
@@ -64,6 +71,8 @@ pub(super) enum FinalProgram {
         data_section: DataSection,
         ops: Vec<AllocatedOp>,
         entries: Vec<(SelectorOpt, ImmOffset, FnName, Option<DeclRefFunction>)>,
+        register_allocation_metrics: Vec<RegisterAllocationMetric>,
+        function_locals: Vec<(Span, FunctionLocals)>,
     },
     Evm {
         ops: Vec<etk_asm::ops::AbstractOp>,
@@ -13,9 +13,11 @@ use crate::{
         AllocatedAbstractOp, ConstantRegister, ControlFlowOp, VirtualImmediate12,
         VirtualImmediate18,
     },
+    source_map::FunctionLocals,
 };
 
 use sway_error::error::CompileError;
+use sway_types::Span;
 
 use either::Either;
 
@@ -26,6 +28,7 @@ impl AbstractProgram {
         entries: Vec<AbstractEntry>,
         non_entries: Vec<AbstractInstructionSet>,
         reg_seqr: RegisterSequencer,
+        function_locals: Vec<(Span, FunctionLocals)>,
     ) -> Self {
         AbstractProgram {
             kind,
@@ -33,6 +36,7 @@ impl AbstractProgram {
             entries,
             non_entries,
             reg_seqr,
+            function_locals,
         }
     }
 
@@ -70,14 +74,16 @@ impl AbstractProgram {
             .collect::<Result<Vec<_>, _>>()?;
 
         // Allocate the registers for each function.
-        let functions = abstract_functions
+        let (functions, register_allocation_metrics): (Vec<_>, Vec<_>) = abstract_functions
             .into_iter()
             .map(|fn_ops| {
                 fn_ops
                     .allocate_registers()
-                    .map(AllocatedAbstractInstructionSet::emit_pusha_popa)
+                    .map(|(fn_ops, metric)| (fn_ops.emit_pusha_popa(), metric))
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .unzip();
 
         // XXX need to verify that the stack use for each function is balanced.
 
@@ -87,6 +93,8 @@ impl AbstractProgram {
             prologue,
             functions,
             entries,
+            register_allocation_metrics,
+            function_locals: self.function_locals,
         })
     }
 
@@ -35,6 +35,8 @@ impl AllocatedProgram {
             data_section: self.data_section,
             ops,
             entries,
+            register_allocation_metrics: self.register_allocation_metrics,
+            function_locals: self.function_locals,
         })
     }
 }
@@ -15,6 +15,8 @@ impl FinalProgram {
                 data_section,
                 ops,
                 entries,
+                register_allocation_metrics,
+                function_locals,
             } => FinalizedAsm {
                 data_section,
                 program_section: InstructionSet::Fuel { ops },
@@ -29,6 +31,8 @@ impl FinalProgram {
                     })
                     .collect(),
                 abi: None,
+                register_allocation_metrics,
+                function_locals,
             },
             FinalProgram::Evm { ops, abi } => FinalizedAsm {
                 data_section: DataSection {
@@ -38,6 +42,8 @@ impl FinalProgram {
                 program_kind: super::ProgramKind::Script,
                 entries: vec![],
                 abi: Some(ProgramABI::Evm(abi)),
+                register_allocation_metrics: vec![],
+                function_locals: vec![],
             },
             FinalProgram::MidenVM { ops } => FinalizedAsm {
                 data_section: DataSection {
@@ -48,6 +54,8 @@ impl FinalProgram {
                 program_kind: super::ProgramKind::Script,
                 entries: vec![],
                 abi: None, /* TODO? */
+                register_allocation_metrics: vec![],
+                function_locals: vec![],
             },
         }
     }
@@ -32,6 +32,77 @@ pub enum BuildTarget {
     MidenVM,
 }
 
+/// What the optimizer should aim to minimise, see [sway_ir::OptimizationGoal].
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+    EnumString,
+)]
+pub enum OptimizationGoal {
+    #[default]
+    #[serde(rename = "size")]
+    #[clap(name = "size")]
+    #[strum(serialize = "size")]
+    Size,
+    #[serde(rename = "gas")]
+    #[clap(name = "gas")]
+    #[strum(serialize = "gas")]
+    Gas,
+}
+
+impl From<OptimizationGoal> for sway_ir::OptimizationGoal {
+    fn from(goal: OptimizationGoal) -> Self {
+        match goal {
+            OptimizationGoal::Size => sway_ir::OptimizationGoal::Size,
+            OptimizationGoal::Gas => sway_ir::OptimizationGoal::Gas,
+        }
+    }
+}
+
+/// How much IR optimization to perform when no explicit list of IR passes is given.
+///
+/// Represented in manifests as an integer, e.g. `optimization-level = 0`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum OptimizationLevel {
+    /// Only run the passes required by the build target.
+    O0,
+    /// Run the standard optimization pipeline, see [sway_ir::create_o1_pass_group].
+    #[default]
+    O1,
+}
+
+impl TryFrom<u8> for OptimizationLevel {
+    type Error = String;
+
+    fn try_from(level: u8) -> Result<Self, Self::Error> {
+        match level {
+            0 => Ok(OptimizationLevel::O0),
+            1 => Ok(OptimizationLevel::O1),
+            _ => Err(format!(
+                "invalid optimization level {level}, expected 0 or 1"
+            )),
+        }
+    }
+}
+
+impl From<OptimizationLevel> for u8 {
+    fn from(level: OptimizationLevel) -> Self {
+        match level {
+            OptimizationLevel::O0 => 0,
+            OptimizationLevel::O1 => 1,
+        }
+    }
+}
+
 /// Configuration for the overall build and compilation process.
 #[derive(Clone)]
 pub struct BuildConfig {
@@ -46,6 +117,11 @@ pub struct BuildConfig {
     pub(crate) print_finalized_asm: bool,
     pub(crate) print_ir: bool,
     pub(crate) include_tests: bool,
+    pub(crate) inline_threshold: Option<u64>,
+    pub(crate) optimization_goal: OptimizationGoal,
+    pub(crate) optimization_level: OptimizationLevel,
+    pub(crate) ir_passes: Option<Vec<String>>,
+    pub(crate) print_ir_after: Vec<String>,
     pub time_phases: bool,
     pub metrics_outfile: Option<String>,
 }
@@ -90,6 +166,11 @@ impl BuildConfig {
             print_finalized_asm: false,
             print_ir: false,
             include_tests: false,
+            inline_threshold: None,
+            optimization_goal: OptimizationGoal::default(),
+            optimization_level: OptimizationLevel::default(),
+            ir_passes: None,
+            print_ir_after: Vec::new(),
             time_phases: false,
             metrics_outfile: None,
         }
@@ -156,6 +237,54 @@ impl BuildConfig {
         }
     }
 
+    /// The maximum estimated growth in code size tolerated by the inliner, or `None` to use the
+    /// IR default.
+    pub fn inline_threshold(self, inline_threshold: Option<u64>) -> Self {
+        Self {
+            inline_threshold,
+            ..self
+        }
+    }
+
+    /// Whether the optimizer should favour smaller bytecode or lower gas usage.
+    ///
+    /// Default: [OptimizationGoal::Size]
+    pub fn optimization_goal(self, optimization_goal: OptimizationGoal) -> Self {
+        Self {
+            optimization_goal,
+            ..self
+        }
+    }
+
+    /// The IR optimization pipeline to run when `ir_passes` isn't set.
+    ///
+    /// Default: [OptimizationLevel::O1]
+    pub fn optimization_level(self, optimization_level: OptimizationLevel) -> Self {
+        Self {
+            optimization_level,
+            ..self
+        }
+    }
+
+    /// An explicit list of IR passes to run, in order, in place of the pipeline selected by the
+    /// optimization level.  Passes required by the build target are always run afterwards.
+    ///
+    /// Default: `None`
+    pub fn ir_passes(self, ir_passes: Option<Vec<String>>) -> Self {
+        Self { ir_passes, ..self }
+    }
+
+    /// Print the IR after each of the named IR passes is run.  The name `all` prints the IR after
+    /// every pass.
+    ///
+    /// Default: empty
+    pub fn print_ir_after(self, print_ir_after: Vec<String>) -> Self {
+        Self {
+            print_ir_after,
+            ..self
+        }
+    }
+
     pub fn canonical_root_module(&self) -> Arc<PathBuf> {
         self.canonical_root_module.clone()
     }
@@ -26,7 +26,7 @@ use crate::{error::*, source_map::SourceMap};
 pub use asm_generation::from_ir::compile_ir_to_asm;
 use asm_generation::FinalizedAsm;
 pub use asm_generation::{CompiledBytecode, FinalizedEntry};
-pub use build_config::{BuildConfig, BuildTarget};
+pub use build_config::{BuildConfig, BuildTarget, OptimizationGoal, OptimizationLevel};
 use control_flow_analysis::ControlFlowGraph;
 use metadata::MetadataManager;
 use std::collections::HashMap;
@@ -35,9 +35,9 @@ use std::sync::Arc;
 use sway_ast::AttributeDecl;
 use sway_error::handler::{ErrorEmitted, Handler};
 use sway_ir::{
-    create_o1_pass_group, register_known_passes, Context, Kind, Module, PassManager,
-    ARGDEMOTION_NAME, CONSTDEMOTION_NAME, DCE_NAME, MEMCPYOPT_NAME, MISCDEMOTION_NAME,
-    MODULEPRINTER_NAME, RETDEMOTION_NAME,
+    create_o1_pass_group, insert_after_each, insert_after_pass, register_known_passes, Context,
+    Kind, Module, PassGroup, PassManager, ARGDEMOTION_NAME, CONSTDEMOTION_NAME, DCE_NAME,
+    MEMCPYOPT_NAME, MISCDEMOTION_NAME, MODULEPRINTER_NAME, RETDEMOTION_NAME,
 };
 use sway_types::constants::DOC_COMMENT_ATTRIBUTE_NAME;
 use sway_types::SourceEngine;
@@ -603,10 +603,79 @@ pub(crate) fn compile_ast_to_ir_to_asm(
         errors.extend(e);
     }
 
+    // Configure the inliner heuristic.
+    ir.inline_config.goal = build_config.optimization_goal.into();
+    if let Some(threshold) = build_config.inline_threshold {
+        ir.inline_config.threshold = threshold;
+    }
+
     // Initialize the pass manager and register known passes.
     let mut pass_mgr = PassManager::default();
     register_known_passes(&mut pass_mgr);
-    let mut pass_group = create_o1_pass_group();
+    let pass_group = check!(
+        CompileResult::with_handler(|handler| create_pass_group(handler, &pass_mgr, build_config)),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+
+    // Run the passes.
+    let res = CompileResult::with_handler(|handler| {
+        if let Err(ir_error) = pass_mgr.run(&mut ir, &pass_group) {
+            Err(handler.emit_err(CompileError::InternalOwned(
+                ir_error.to_string(),
+                span::Span::dummy(),
+            )))
+        } else {
+            Ok(())
+        }
+    });
+    check!(res, return err(warnings, errors), warnings, errors);
+
+    let final_asm = check!(
+        compile_ir_to_asm(&ir, Some(build_config)),
+        return err(warnings, errors),
+        warnings,
+        errors
+    );
+
+    ok(final_asm, warnings, errors)
+}
+
+/// Build the group of IR passes to run from the build config.  This is either the explicit list
+/// of passes in `ir_passes` or the pipeline for the optimization level, followed by any passes
+/// required by the build target.
+fn create_pass_group(
+    handler: &Handler,
+    pass_mgr: &PassManager,
+    build_config: &BuildConfig,
+) -> Result<PassGroup, ErrorEmitted> {
+    let lookup_pass = |name: &str| {
+        pass_mgr
+            .lookup_registered_pass(name)
+            .map(|pass| pass.name)
+            .ok_or_else(|| {
+                handler.emit_err(CompileError::UnknownIrPass {
+                    name: name.to_owned(),
+                    valid_passes: pass_mgr.help_text(),
+                    span: span::Span::dummy(),
+                })
+            })
+    };
+
+    let mut pass_group = match &build_config.ir_passes {
+        Some(passes) => {
+            let mut pass_group = PassGroup::default();
+            for name in passes {
+                pass_group.append_pass(lookup_pass(name)?);
+            }
+            pass_group
+        }
+        None => match build_config.optimization_level {
+            OptimizationLevel::O0 => PassGroup::default(),
+            OptimizationLevel::O1 => create_o1_pass_group(),
+        },
+    };
 
     // Target specific transforms should be moved into something more configured.
     if build_config.build_target == BuildTarget::Fuel {
@@ -630,31 +699,26 @@ pub(crate) fn compile_ast_to_ir_to_asm(
         //pass_group.append_pass(SIMPLIFYCFG_NAME);
     }
 
+    for name in &build_config.print_ir_after {
+        pass_group = if name == "all" {
+            insert_after_each(pass_group, MODULEPRINTER_NAME)
+        } else {
+            let pass = lookup_pass(name)?;
+            if !pass_group.contains(pass) {
+                return Err(handler.emit_err(CompileError::IrPassNotInPipeline {
+                    name: name.to_owned(),
+                    span: span::Span::dummy(),
+                }));
+            }
+            insert_after_pass(pass_group, pass, MODULEPRINTER_NAME)
+        };
+    }
+
     if build_config.print_ir {
         pass_group.append_pass(MODULEPRINTER_NAME);
     }
 
-    // Run the passes.
-    let res = CompileResult::with_handler(|handler| {
-        if let Err(ir_error) = pass_mgr.run(&mut ir, &pass_group) {
-            Err(handler.emit_err(CompileError::InternalOwned(
-                ir_error.to_string(),
-                span::Span::dummy(),
-            )))
-        } else {
-            Ok(())
-        }
-    });
-    check!(res, return err(warnings, errors), warnings, errors);
-
-    let final_asm = check!(
-        compile_ir_to_asm(&ir, Some(build_config)),
-        return err(warnings, errors),
-        warnings,
-        errors
-    );
-
-    ok(final_asm, warnings, errors)
+    Ok(pass_group)
 }
 
 /// Given input Sway source code, compile to [CompiledBytecode], containing the asm in bytecode form.
@@ -955,6 +1019,64 @@ fn test_unary_ordering() {
     };
 }
 
+#[cfg(test)]
+fn pass_group_errors(build_config: BuildConfig) -> Vec<CompileError> {
+    let mut pass_mgr = PassManager::default();
+    register_known_passes(&mut pass_mgr);
+    let handler = Handler::default();
+    let _ = create_pass_group(&handler, &pass_mgr, &build_config);
+    handler.consume().0
+}
+
+#[cfg(test)]
+fn pass_group_build_config() -> BuildConfig {
+    BuildConfig::root_from_file_name_and_manifest_path(
+        PathBuf::from("/test/src/main.sw"),
+        PathBuf::from("/test"),
+        BuildTarget::Fuel,
+    )
+}
+
+#[test]
+fn test_create_pass_group_unknown_pass() {
+    let errors = pass_group_errors(
+        pass_group_build_config()
+            .ir_passes(Some(vec!["dce".to_string(), "no_such_pass".to_string()])),
+    );
+    assert!(matches!(
+        errors.as_slice(),
+        [CompileError::UnknownIrPass { name, .. }] if name == "no_such_pass"
+    ));
+
+    let errors = pass_group_errors(
+        pass_group_build_config().print_ir_after(vec!["no_such_pass".to_string()]),
+    );
+    assert!(matches!(
+        errors.as_slice(),
+        [CompileError::UnknownIrPass { name, .. }] if name == "no_such_pass"
+    ));
+}
+
+#[test]
+fn test_create_pass_group_print_ir_after() {
+    let errors = pass_group_errors(
+        pass_group_build_config()
+            .ir_passes(Some(vec!["inline_module".to_string()]))
+            .print_ir_after(vec!["inline_module".to_string(), "all".to_string()]),
+    );
+    assert!(errors.is_empty());
+
+    let errors = pass_group_errors(
+        pass_group_build_config()
+            .ir_passes(Some(vec!["inline_module".to_string()]))
+            .print_ir_after(vec!["mem2reg".to_string()]),
+    );
+    assert!(matches!(
+        errors.as_slice(),
+        [CompileError::IrPassNotInPipeline { name, .. }] if name == "mem2reg"
+    ));
+}
+
 /// Return an irrecoverable compile result deduping any errors and warnings.
 fn deduped_err<T>(warnings: Vec<CompileWarning>, errors: Vec<CompileError>) -> CompileResult<T> {
     err(dedup_unsorted(warnings), dedup_unsorted(errors))
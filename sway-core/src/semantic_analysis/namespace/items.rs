@@ -54,6 +54,14 @@ impl Items {
         &self.symbols
     }
 
+    /// The symbols imported into this scope, by the name they are imported as, along with their
+    /// declarations.
+    pub fn imported_symbols(&self) -> impl Iterator<Item = (&Ident, &ty::TyDecl)> {
+        self.use_synonyms
+            .iter()
+            .map(|(symbol, (_, _, decl))| (symbol, decl))
+    }
+
     pub fn apply_storage_load(
         &self,
         engines: &Engines,
@@ -21,6 +21,10 @@ pub struct SourceMap {
     paths: Vec<PathBuf>,
     /// Mapping from opcode index to source location
     map: HashMap<usize, SourceMapSpan>,
+    /// Mapping from the index of the opcode reserving the stack frame of a function to the
+    /// locals stored in that frame.
+    #[serde(default)]
+    function_locals: HashMap<usize, FunctionLocals>,
 }
 impl SourceMap {
     pub fn new() -> Self {
@@ -62,6 +66,22 @@ impl SourceMap {
         }
     }
 
+    /// Inserts the locals stored in the stack frame reserved by the opcode at `pc`.
+    pub fn insert_function_locals(&mut self, pc: usize, locals: FunctionLocals) {
+        self.function_locals.insert(pc, locals);
+    }
+
+    /// The locals stored in the stack frame reserved by the opcode at `pc`, if it reserves the
+    /// frame of a function.
+    pub fn function_locals(&self, pc: usize) -> Option<&FunctionLocals> {
+        self.function_locals.get(&pc)
+    }
+
+    /// The indices of all the opcodes which have a source location.
+    pub fn addrs(&self) -> impl Iterator<Item = usize> + '_ {
+        self.map.keys().copied()
+    }
+
     /// Inverse source mapping
     pub fn addr_to_span(&self, pc: usize) -> Option<(PathBuf, LocationRange)> {
         self.map.get(&pc).map(|sms| {
@@ -94,3 +114,20 @@ pub struct LocationRange {
     pub start: usize,
     pub end: usize,
 }
+
+/// The locals of a function which are stored in its stack frame.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FunctionLocals {
+    pub function: String,
+    pub locals: Vec<LocalVariable>,
+}
+
+/// A local variable stored in the stack frame of a function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalVariable {
+    pub name: String,
+    /// The offset of the variable from the start of the frame, in bytes.
+    pub offset: u64,
+    /// The size of the variable, in bytes.
+    pub size: u64,
+}
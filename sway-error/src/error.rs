@@ -57,6 +57,14 @@ pub enum CompileError {
          code that triggered this error."
     )]
     InternalOwned(String, Span),
+    #[error("Unknown IR pass \"{name}\".\n{valid_passes}")]
+    UnknownIrPass {
+        name: String,
+        valid_passes: String,
+        span: Span,
+    },
+    #[error("Cannot print the IR after pass \"{name}\": it is not in the IR pipeline being run.")]
+    IrPassNotInPipeline { name: String, span: Span },
     #[error(
         "Predicate declaration contains no main function. Predicates require a main function."
     )]
@@ -670,6 +678,8 @@ impl Spanned for CompileError {
             ParseError { span, .. } => span.clone(),
             Internal(_, span) => span.clone(),
             InternalOwned(_, span) => span.clone(),
+            UnknownIrPass { span, .. } => span.clone(),
+            IrPassNotInPipeline { span, .. } => span.clone(),
             NoPredicateMainFunction(span) => span.clone(),
             PredicateMainDoesNotReturnBool(span) => span.clone(),
             NoScriptMainFunction(span) => span.clone(),
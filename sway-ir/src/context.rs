@@ -13,7 +13,7 @@ use sway_types::SourceEngine;
 use crate::{
     asm::AsmBlockContent, block::BlockContent, function::FunctionContent,
     local_var::LocalVarContent, metadata::Metadatum, module::Kind, module::ModuleContent,
-    module::ModuleIterator, value::ValueContent, Type, TypeContent,
+    module::ModuleIterator, value::ValueContent, InlineConfig, Type, TypeContent,
 };
 
 /// The main IR context handle.
@@ -35,6 +35,9 @@ pub struct Context<'eng> {
 
     pub program_kind: Kind,
 
+    /// Parameters for the inliner heuristic, see [`InlineConfig`].
+    pub inline_config: InlineConfig,
+
     next_unique_sym_tag: u64,
 }
 
@@ -53,6 +56,7 @@ impl<'eng> Context<'eng> {
             metadata: Default::default(),
             next_unique_sym_tag: Default::default(),
             program_kind: Kind::Contract,
+            inline_config: Default::default(),
         };
         Type::create_basic_types(&mut def);
         def
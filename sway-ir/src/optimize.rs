@@ -21,6 +21,8 @@ pub mod constants;
 pub use constants::*;
 pub mod dce;
 pub use dce::*;
+pub mod fn_specialize;
+pub use fn_specialize::*;
 pub mod inline;
 pub use inline::*;
 pub mod mem2reg;
@@ -0,0 +1,274 @@
+//! Function specialization and interprocedural constant propagation.
+//!
+//! Monomorphized functions are often called with the same constant arguments from several call
+//! sites.  The `fn_specialize` pass clones such functions with the constant arguments substituted
+//! into the body and rewrites the call sites to call the specialized clone instead, provided at
+//! least two call sites pass the same constants.  Subsequent constant folding and CFG
+//! simplification can then take advantage of the known values.  Original functions which are no
+//! longer called are cleaned up by `func_dce`.
+//!
+//! The `arg_dce` pass removes function arguments which are never used by the function body,
+//! updating every call site to match.
+
+use rustc_hash::FxHashSet;
+
+use crate::{
+    call_graph, inline_all_function_calls, AnalysisResults, BlockArgument, Constant, ConstantValue,
+    Context, Function, Instruction, IrError, Module, Pass, PassMutability, ScopedPass, Value,
+    ValueDatum,
+};
+
+pub const FN_SPECIALIZE_NAME: &str = "fn_specialize";
+
+pub fn create_fn_specialize_pass() -> Pass {
+    Pass {
+        name: FN_SPECIALIZE_NAME,
+        descr: "Specialize functions on constant call arguments.",
+        deps: vec![],
+        runner: ScopedPass::ModulePass(PassMutability::Transform(fn_specialize)),
+    }
+}
+
+pub const ARG_DCE_NAME: &str = "arg_dce";
+
+pub fn create_arg_dce_pass() -> Pass {
+    Pass {
+        name: ARG_DCE_NAME,
+        descr: "Dead function argument elimination.",
+        deps: vec![],
+        runner: ScopedPass::ModulePass(PassMutability::Transform(arg_dce)),
+    }
+}
+
+/// Functions with more instructions than this are never cloned, as the code size increase would
+/// outweigh the benefit of the known arguments.
+const MAX_SPECIALIZE_INSTRS_COUNT: usize = 100;
+
+/// The minimum number of call sites passing the same constant arguments for a function to be
+/// specialized on them.  A clone used by a single call site is unlikely to be worth its size.
+const MIN_SPECIALIZE_CALL_SITES: usize = 2;
+
+/// The maximum number of specialized clones made of a single function.  If there are more
+/// distinct sets of constant arguments then only the most frequently used are specialized.
+const MAX_SPECIALIZATIONS_PER_FN: usize = 4;
+
+/// The constant arguments passed at a call site, by argument position.  Non-constant arguments are
+/// `None`.
+type ConstArgs = Vec<Option<Constant>>;
+
+fn const_args_eq(context: &Context, lhs: &ConstArgs, rhs: &ConstArgs) -> bool {
+    lhs.len() == rhs.len()
+        && lhs.iter().zip(rhs.iter()).all(|pair| match pair {
+            (None, None) => true,
+            (Some(l), Some(r)) => l.eq(context, r),
+            _otherwise => false,
+        })
+}
+
+pub fn fn_specialize(
+    context: &mut Context,
+    _: &AnalysisResults,
+    module: Module,
+) -> Result<bool, IrError> {
+    let cg =
+        call_graph::build_call_graph(context, &module.function_iter(context).collect::<Vec<_>>());
+
+    // Visit callers before callees so that constants passed straight through a specialized
+    // function to its own callees are propagated further down the call graph in a single run.
+    let mut functions = call_graph::callee_first_order(&cg);
+    functions.reverse();
+
+    let mut modified = false;
+    for function in functions {
+        if function.is_entry(context)
+            || function.num_instructions(context) > MAX_SPECIALIZE_INSTRS_COUNT
+        {
+            continue;
+        }
+
+        // Group the call sites to this function by the constant arguments they pass.  Recursive
+        // calls are left alone, otherwise we could keep on specializing forever.
+        let mut groups: Vec<(ConstArgs, Vec<Value>)> = Vec::new();
+        for caller in module.function_iter(context) {
+            if caller == function {
+                continue;
+            }
+            for (_block, call_val) in caller.instruction_iter(context) {
+                let Some(Instruction::Call(callee, args)) = call_val.get_instruction(context)
+                else {
+                    continue;
+                };
+                if *callee != function {
+                    continue;
+                }
+                let const_args = args
+                    .iter()
+                    .map(|arg| {
+                        arg.get_constant(context)
+                            .filter(|c| !matches!(c.value, ConstantValue::Undef))
+                            .cloned()
+                    })
+                    .collect::<ConstArgs>();
+                if const_args.iter().all(Option::is_none) {
+                    continue;
+                }
+                match groups
+                    .iter_mut()
+                    .find(|(group_args, _)| const_args_eq(context, group_args, &const_args))
+                {
+                    Some((_, call_sites)) => call_sites.push(call_val),
+                    None => groups.push((const_args, vec![call_val])),
+                }
+            }
+        }
+
+        // Prefer the sets of constant arguments which are used most often.
+        groups.retain(|(_, call_sites)| call_sites.len() >= MIN_SPECIALIZE_CALL_SITES);
+        groups.sort_by(|(_, lhs), (_, rhs)| rhs.len().cmp(&lhs.len()));
+        groups.truncate(MAX_SPECIALIZATIONS_PER_FN);
+
+        for (const_args, call_sites) in groups {
+            let specialized_fn = specialize_function(context, module, function, &const_args)?;
+            for call_val in call_sites {
+                let Some(Instruction::Call(_, args)) = call_val.get_instruction(context) else {
+                    unreachable!("Call sites are definitely call instructions.");
+                };
+                let new_args = args
+                    .iter()
+                    .zip(const_args.iter())
+                    .filter_map(|(arg, const_arg)| const_arg.is_none().then_some(*arg))
+                    .collect();
+                *call_val.get_instruction_mut(context).unwrap() =
+                    Instruction::Call(specialized_fn, new_args);
+            }
+            modified = true;
+        }
+    }
+
+    Ok(modified)
+}
+
+/// Create a clone of `function` which takes only its non-constant arguments and has the constant
+/// arguments in `const_args` substituted into its body.
+fn specialize_function(
+    context: &mut Context,
+    module: Module,
+    function: Function,
+    const_args: &ConstArgs,
+) -> Result<Function, IrError> {
+    let args = function
+        .args_iter(context)
+        .zip(const_args.iter())
+        .filter(|(_, const_arg)| const_arg.is_none())
+        .map(|((name, arg_val), _)| {
+            (
+                name.clone(),
+                arg_val.get_type(context).unwrap(),
+                arg_val.get_metadata(context),
+            )
+        })
+        .collect();
+    let unique_id = context.get_unique_id();
+    let name = format!("{}_spec_{unique_id}", function.get_name(context));
+    let ret_type = function.get_return_type(context);
+    let metadata = function.get_metadata(context);
+    let specialized_fn = Function::new(
+        context, module, name, args, ret_type, None, false, false, metadata,
+    );
+
+    // The body of the new function is initially just a call to the original function, passing
+    // the constants along with the remaining arguments.  Inlining that call then clones the
+    // original body with the constants in place of the arguments.
+    let mut new_args = specialized_fn
+        .args_iter(context)
+        .map(|(_name, arg_val)| *arg_val)
+        .collect::<Vec<_>>()
+        .into_iter();
+    let call_args = const_args
+        .iter()
+        .map(|const_arg| match const_arg {
+            Some(c) => Value::new_constant(context, c.clone()),
+            None => new_args.next().unwrap(),
+        })
+        .collect::<Vec<_>>();
+
+    let entry_block = specialized_fn.get_entry_block(context);
+    let call_val = entry_block.ins(context).call(function, &call_args);
+    entry_block.ins(context).ret(call_val, ret_type);
+    inline_all_function_calls(context, &specialized_fn)?;
+
+    Ok(specialized_fn)
+}
+
+pub fn arg_dce(
+    context: &mut Context,
+    _: &AnalysisResults,
+    module: Module,
+) -> Result<bool, IrError> {
+    // Entry functions have an externally visible signature which we can't change.
+    let candidates = module
+        .function_iter(context)
+        .filter(|function| !function.is_entry(context))
+        .collect::<Vec<_>>();
+
+    let mut modified = false;
+    for function in candidates {
+        let used_vals = function
+            .instruction_iter(context)
+            .flat_map(|(_block, ins)| ins.get_instruction(context).unwrap().get_operands())
+            .collect::<FxHashSet<Value>>();
+        let dead_arg_idcs = function
+            .args_iter(context)
+            .enumerate()
+            .filter_map(|(idx, (_name, arg_val))| (!used_vals.contains(arg_val)).then_some(idx))
+            .collect::<FxHashSet<usize>>();
+
+        if !dead_arg_idcs.is_empty() {
+            remove_fn_args(context, function, &dead_arg_idcs);
+            modified = true;
+        }
+    }
+
+    Ok(modified)
+}
+
+fn remove_fn_args(context: &mut Context, function: Function, dead_arg_idcs: &FxHashSet<usize>) {
+    // The remaining arguments need to be renumbered, they're also the entry block args.
+    let live_args = function
+        .args_iter(context)
+        .enumerate()
+        .filter_map(|(idx, arg)| (!dead_arg_idcs.contains(&idx)).then_some(arg.clone()))
+        .collect::<Vec<_>>();
+    for (new_idx, (_name, arg_val)) in live_args.iter().enumerate() {
+        if let ValueDatum::Argument(BlockArgument { idx, .. }) =
+            &mut context.values[arg_val.0].value
+        {
+            *idx = new_idx;
+        }
+    }
+    let entry_block = function.get_entry_block(context);
+    context.blocks[entry_block.0].args = live_args.iter().map(|(_name, val)| *val).collect();
+    context.functions[function.0].arguments = live_args;
+
+    // Drop the corresponding values at every call site.
+    let call_sites = context
+        .module_iter()
+        .flat_map(|module| module.function_iter(context))
+        .flat_map(|caller| caller.instruction_iter(context))
+        .filter_map(
+            |(_block, call_val)| match call_val.get_instruction(context) {
+                Some(Instruction::Call(callee, _)) if *callee == function => Some(call_val),
+                _otherwise => None,
+            },
+        )
+        .collect::<Vec<_>>();
+    for call_val in call_sites {
+        if let Some(Instruction::Call(_, args)) = call_val.get_instruction_mut(context) {
+            *args = args
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, arg)| (!dead_arg_idcs.contains(&idx)).then_some(*arg))
+                .collect();
+        }
+    }
+}
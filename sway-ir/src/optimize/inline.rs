@@ -88,6 +88,81 @@ fn metadata_to_inline(context: &Context, md_idx: Option<MetadataIndex>) -> Optio
     })
 }
 
+/// What the `inline_module` pass should aim to minimise when deciding whether to inline.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OptimizationGoal {
+    /// Prefer smaller bytecode.
+    #[default]
+    Size,
+    /// Prefer lower gas usage, at the expense of larger bytecode.
+    Gas,
+}
+
+/// Configuration for the `inline_module` pass heuristic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InlineConfig {
+    /// The maximum estimated growth in bytecode size, in Fuel VM opcodes, tolerated when inlining
+    /// a function at all of its call sites.
+    pub threshold: u64,
+    pub goal: OptimizationGoal,
+}
+
+/// An estimate of the cost of a function body once lowered to Fuel VM opcodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FunctionCost {
+    /// The approximate number of opcodes.
+    pub size: u64,
+    /// The approximate gas used executing every instruction once.
+    pub gas: u64,
+}
+
+impl FunctionCost {
+    pub fn new(context: &Context, function: &Function) -> Self {
+        function
+            .instruction_iter(context)
+            .fold(FunctionCost::default(), |cost, (_block, ins)| {
+                let (size, gas) = super::target_fuel::instruction_cost(
+                    context,
+                    ins.get_instruction(context).unwrap(),
+                );
+                FunctionCost {
+                    size: cost.size + size,
+                    gas: cost.gas + gas,
+                }
+            })
+    }
+}
+
+/// Decide whether inlining `function` at each of its `num_calls` call sites is worthwhile, given
+/// the cost model and the `config` thresholds.
+pub fn is_profitable_to_inline(
+    context: &Context,
+    function: &Function,
+    num_calls: u64,
+    config: &InlineConfig,
+) -> bool {
+    use super::target_fuel::{CALL_OVERHEAD_GAS, CALL_SITE_OPCODES, FN_FRAME_OPCODES};
+
+    let cost = FunctionCost::new(context, function);
+    let num_args = function.num_args(context) as u64;
+
+    // Once inlined everywhere each call site becomes a copy of the body, but the out-of-line body
+    // along with its frame setup and the call sequences are no longer needed.
+    let inlined_size = cost.size * num_calls;
+    let outlined_size = cost.size + FN_FRAME_OPCODES + num_calls * (CALL_SITE_OPCODES + num_args);
+    let growth = inlined_size.saturating_sub(outlined_size);
+
+    match config.goal {
+        OptimizationGoal::Size => growth <= config.threshold,
+        OptimizationGoal::Gas => {
+            // If the call overhead costs more than the body itself then inlining is always a win,
+            // otherwise each call site is allowed to grow the code by the gas it saves.
+            cost.gas <= CALL_OVERHEAD_GAS
+                || growth <= config.threshold + num_calls * CALL_OVERHEAD_GAS
+        }
+    }
+}
+
 pub fn inline_in_module(
     context: &mut Context,
     _: &AnalysisResults,
@@ -109,12 +184,15 @@ pub fn inline_in_module(
                 counts
             });
 
+    let cg =
+        call_graph::build_call_graph(context, &module.function_iter(context).collect::<Vec<_>>());
+
     let inline_heuristic = |ctx: &Context, func: &Function, _call_site: &Value| {
         let attributed_inline = metadata_to_inline(ctx, func.get_metadata(ctx));
         match attributed_inline {
             Some(Inline::Always) => {
-                // TODO: check if inlining of function is possible
-                // return true;
+                // Directly recursive functions would otherwise be inlined into themselves.
+                return !cg.get(func).map_or(false, |callees| callees.contains(func));
             }
             Some(Inline::Never) => {
                 return false;
@@ -140,17 +218,6 @@ pub fn inline_in_module(
             return true;
         }
 
-        // If the function is called only once then definitely inline it.
-        if call_counts.get(func).copied().unwrap_or(0) == 1 {
-            return true;
-        }
-
-        // If the function is (still) small then also inline it.
-        const MAX_INLINE_INSTRS_COUNT: usize = 4;
-        if func.num_instructions(ctx) <= MAX_INLINE_INSTRS_COUNT {
-            return true;
-        }
-
         // As per https://github.com/FuelLabs/sway/issues/2819 we can hit problems if a function
         // argument is used as a pointer (probably because it has a ref type) although it actually
         // isn't one.  Ref type args which aren't pointers need to be inlined.
@@ -162,11 +229,11 @@ pub fn inline_in_module(
             return true;
         }
 
-        false
+        // Otherwise weigh up the size and gas of the function against its call sites.
+        let num_calls = call_counts.get(func).copied().unwrap_or(0);
+        is_profitable_to_inline(ctx, func, num_calls, &ctx.inline_config)
     };
 
-    let cg =
-        call_graph::build_call_graph(context, &module.function_iter(context).collect::<Vec<_>>());
     let functions = call_graph::callee_first_order(&cg);
     let mut modified = false;
 
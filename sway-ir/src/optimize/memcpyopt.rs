@@ -264,7 +264,8 @@ fn local_copy_prop_prememcpy(context: &mut Context, function: Function) -> Resul
     for (value, replace_with) in replaces.into_iter() {
         match replace_with {
             ReplaceWith::InPlaceLocal(replacement_var) => {
-                let Some(Instruction::GetLocal(redundant_var)) = value.get_instruction(context) else {
+                let Some(Instruction::GetLocal(redundant_var)) = value.get_instruction(context)
+                else {
                     panic!("earlier match now fails");
                 };
                 if redundant_var.is_mutable(context) {
@@ -365,7 +366,12 @@ fn local_copy_prop(
             get_symbol(context, dst_val_ptr),
             get_symbol(context, src_val_ptr),
         ) {
-            if escaped_symbols.contains(&dst_sym) || escaped_symbols.contains(&src_sym) {
+            // A copy of a symbol onto itself has nothing to propagate, and loads rewritten to
+            // read from its source would keep on being rewritten.
+            if dst_sym == src_sym
+                || escaped_symbols.contains(&dst_sym)
+                || escaped_symbols.contains(&src_sym)
+            {
                 return;
             }
             dest_to_copies
@@ -1,6 +1,7 @@
 //! Some Fuel VM specific utilities.
 use crate::{
     context::Context,
+    instruction::{FuelVmInstruction, Instruction},
     irtype::{Type, TypeContent},
 };
 
@@ -12,3 +13,59 @@ pub(super) fn is_demotable_type(context: &Context, ty: &Type) -> bool {
         _ => true,
     }
 }
+
+/// The approximate number of Fuel VM opcodes required to make a call, not including moving the
+/// arguments into place.
+pub(super) const CALL_SITE_OPCODES: u64 = 3;
+
+/// The approximate number of Fuel VM opcodes in a function prologue and epilogue, which are saved
+/// when a function is entirely inlined.
+pub(super) const FN_FRAME_OPCODES: u64 = 10;
+
+/// The approximate gas used by a call, its argument passing and the callee's prologue and
+/// epilogue.
+pub(super) const CALL_OVERHEAD_GAS: u64 = 20;
+
+/// Estimate the cost of an instruction once it has been lowered to Fuel VM opcodes, as a pair of
+/// the number of opcodes and the gas used.
+///
+/// These are deliberately rough and only meant to be compared against each other by heuristics;
+/// they are loosely based on the Fuel VM gas schedule where most ALU and memory opcodes cost 1.
+pub(super) fn instruction_cost(context: &Context, ins: &Instruction) -> (u64, u64) {
+    match ins {
+        Instruction::AsmBlock(asm, _args) => {
+            let num_ops = asm.get_content(context).body.len() as u64;
+            (num_ops, num_ops)
+        }
+        Instruction::BitCast(..) | Instruction::CastPtr(..) | Instruction::Nop => (0, 0),
+        Instruction::BinaryOp { .. }
+        | Instruction::Branch(_)
+        | Instruction::Cmp(..)
+        | Instruction::GetElemPtr { .. }
+        | Instruction::GetLocal(_)
+        | Instruction::IntToPtr(..)
+        | Instruction::PtrToInt(..)
+        | Instruction::Ret(..) => (1, 1),
+        Instruction::ConditionalBranch { .. } => (2, 2),
+        Instruction::Load(_) | Instruction::Store { .. } => (1, 1),
+        Instruction::MemCopyBytes { .. } | Instruction::MemCopyVal { .. } => (2, 3),
+        Instruction::Call(_, args) => {
+            let num_ops = CALL_SITE_OPCODES + args.len() as u64;
+            (num_ops, CALL_OVERHEAD_GAS + args.len() as u64)
+        }
+        Instruction::ContractCall { .. } => (6, 150),
+        Instruction::FuelVm(fuel_vm_instr) => match fuel_vm_instr {
+            FuelVmInstruction::Gtf { .. }
+            | FuelVmInstruction::ReadRegister(_)
+            | FuelVmInstruction::Revert(_) => (1, 1),
+            FuelVmInstruction::Log { .. } => (3, 40),
+            FuelVmInstruction::Smo { .. } => (2, 200),
+            FuelVmInstruction::StateLoadWord(_) | FuelVmInstruction::StateStoreWord { .. } => {
+                (1, 50)
+            }
+            FuelVmInstruction::StateClear { .. }
+            | FuelVmInstruction::StateLoadQuadWord { .. }
+            | FuelVmInstruction::StateStoreQuadWord { .. } => (2, 80),
+        },
+    }
+}
@@ -1,11 +1,13 @@
 use crate::{
-    create_arg_demotion_pass, create_const_combine_pass, create_const_demotion_pass,
-    create_dce_pass, create_dom_fronts_pass, create_dominators_pass, create_escaped_symbols_pass,
-    create_func_dce_pass, create_inline_in_main_pass, create_inline_in_module_pass,
-    create_mem2reg_pass, create_memcpyopt_pass, create_misc_demotion_pass,
-    create_module_printer_pass, create_module_verifier_pass, create_postorder_pass,
-    create_ret_demotion_pass, create_simplify_cfg_pass, Context, Function, IrError, Module,
-    CONSTCOMBINE_NAME, DCE_NAME, FUNC_DCE_NAME, INLINE_MODULE_NAME, MEM2REG_NAME, SIMPLIFYCFG_NAME,
+    create_arg_dce_pass, create_arg_demotion_pass, create_const_combine_pass,
+    create_const_demotion_pass, create_dce_pass, create_dom_fronts_pass, create_dominators_pass,
+    create_escaped_symbols_pass, create_fn_specialize_pass, create_func_dce_pass,
+    create_inline_in_main_pass, create_inline_in_module_pass, create_mem2reg_pass,
+    create_memcpyopt_pass, create_misc_demotion_pass, create_module_printer_pass,
+    create_module_verifier_pass, create_postorder_pass, create_ret_demotion_pass,
+    create_simplify_cfg_pass, Context, Function, IrError, Module, ARG_DCE_NAME, CONSTCOMBINE_NAME,
+    DCE_NAME, FN_SPECIALIZE_NAME, FUNC_DCE_NAME, INLINE_MODULE_NAME, MEM2REG_NAME,
+    SIMPLIFYCFG_NAME,
 };
 use downcast_rs::{impl_downcast, Downcast};
 use rustc_hash::FxHashMap;
@@ -293,6 +295,11 @@ impl PassGroup {
     pub fn append_group(&mut self, group: PassGroup) {
         self.0.push(PassOrGroup::Group(group));
     }
+
+    /// Whether the pass named `pass` is in this group or any of its sub-groups.
+    pub fn contains(&self, pass: &str) -> bool {
+        self.flatten_pass_group().contains(&pass)
+    }
 }
 
 /// A convenience utility to register known passes.
@@ -312,6 +319,8 @@ pub fn register_known_passes(pm: &mut PassManager) {
     pm.register(create_simplify_cfg_pass());
     pm.register(create_func_dce_pass());
     pm.register(create_dce_pass());
+    pm.register(create_fn_specialize_pass());
+    pm.register(create_arg_dce_pass());
     pm.register(create_arg_demotion_pass());
     pm.register(create_const_demotion_pass());
     pm.register(create_ret_demotion_pass());
@@ -324,12 +333,14 @@ pub fn create_o1_pass_group() -> PassGroup {
     let mut o1 = PassGroup::default();
     // Configure to run our passes.
     o1.append_pass(MEM2REG_NAME);
+    o1.append_pass(FN_SPECIALIZE_NAME);
     o1.append_pass(INLINE_MODULE_NAME);
     o1.append_pass(CONSTCOMBINE_NAME);
     o1.append_pass(SIMPLIFYCFG_NAME);
     o1.append_pass(CONSTCOMBINE_NAME);
     o1.append_pass(SIMPLIFYCFG_NAME);
     o1.append_pass(FUNC_DCE_NAME);
+    o1.append_pass(ARG_DCE_NAME);
     o1.append_pass(DCE_NAME);
 
     o1
@@ -343,3 +354,21 @@ pub fn insert_after_each(pg: PassGroup, pass: &'static str) -> PassGroup {
             .collect(),
     )
 }
+
+/// Utility to insert a pass after every occurrence of the pass named `after` in the given group,
+/// including within sub-groups.
+pub fn insert_after_pass(pg: PassGroup, after: &str, pass: &'static str) -> PassGroup {
+    PassGroup(
+        pg.0.into_iter()
+            .flat_map(|p_o_g| match p_o_g {
+                PassOrGroup::Pass(name) if name == after => {
+                    vec![PassOrGroup::Pass(name), PassOrGroup::Pass(pass)]
+                }
+                PassOrGroup::Group(group) => {
+                    vec![PassOrGroup::Group(insert_after_pass(group, after, pass))]
+                }
+                other => vec![other],
+            })
+            .collect(),
+    )
+}
@@ -846,10 +846,10 @@ fn asm_block_to_doc(
         .fold(
             Doc::Empty,
             |doc, AsmArg { initializer, .. }| match initializer {
-                Some(init_val) if init_val.is_constant(context) => {
-                    doc.append(constant_to_doc(context, md_namer, namer, init_val))
+                Some(init_val) => {
+                    doc.append(maybe_constant_to_doc(context, md_namer, namer, init_val))
                 }
-                _otherwise => doc,
+                None => doc,
             },
         )
         .append(Doc::line(
@@ -0,0 +1,362 @@
+// A structure-aware random IR generator.  Each generated program is verified, round-tripped
+// through the printer and parser and then run through each of the optimization passes, verifying
+// and round-tripping again after each one.
+//
+// Programs are generated from a seed by a small deterministic PRNG, so a failure can be reproduced
+// by generating the program for the reported seed.
+
+use sway_ir::{
+    register_known_passes, BinaryOpKind, Block, Constant, Context, Function, Kind, MetadataIndex,
+    Module, PassGroup, PassManager, Predicate, Type, TypeOption, Value, ARGDEMOTION_NAME,
+    ARG_DCE_NAME, CONSTCOMBINE_NAME, CONSTDEMOTION_NAME, DCE_NAME, FN_SPECIALIZE_NAME,
+    FUNC_DCE_NAME, INLINE_MAIN_NAME, INLINE_MODULE_NAME, MEM2REG_NAME, MEMCPYOPT_NAME,
+    MISCDEMOTION_NAME, RETDEMOTION_NAME, SIMPLIFYCFG_NAME,
+};
+use sway_types::SourceEngine;
+
+const NUM_SEEDS: u64 = 200;
+
+const TRANSFORM_PASSES: &[&str] = &[
+    MEM2REG_NAME,
+    INLINE_MODULE_NAME,
+    INLINE_MAIN_NAME,
+    CONSTCOMBINE_NAME,
+    SIMPLIFYCFG_NAME,
+    FUNC_DCE_NAME,
+    DCE_NAME,
+    FN_SPECIALIZE_NAME,
+    ARG_DCE_NAME,
+    ARGDEMOTION_NAME,
+    CONSTDEMOTION_NAME,
+    RETDEMOTION_NAME,
+    MISCDEMOTION_NAME,
+    MEMCPYOPT_NAME,
+];
+
+// -------------------------------------------------------------------------------------------------
+
+#[test]
+fn fuzz_verify_and_round_trip() {
+    let source_engine = SourceEngine::default();
+    for seed in 0..NUM_SEEDS {
+        let ir = random_ir(&source_engine, seed);
+        check_ir(ir, seed, "generation");
+    }
+}
+
+#[test]
+fn fuzz_passes() {
+    let source_engine = SourceEngine::default();
+    for seed in 0..NUM_SEEDS {
+        for &pass in TRANSFORM_PASSES {
+            let mut ir = random_ir(&source_engine, seed);
+            let mut pass_mgr = PassManager::default();
+            register_known_passes(&mut pass_mgr);
+            let mut pass_group = PassGroup::default();
+            pass_group.append_pass(pass);
+            pass_mgr.run(&mut ir, &pass_group).unwrap_or_else(|err| {
+                panic!("Seed {seed}: pass {pass} failed: {err}\n{ir}");
+            });
+            check_ir(ir, seed, pass);
+        }
+    }
+}
+
+fn check_ir(ir: Context, seed: u64, after: &str) {
+    let ir = ir.verify().unwrap_or_else(|err| {
+        panic!("Seed {seed}: verification failed after {after}: {err}");
+    });
+
+    let printed = sway_ir::printer::to_string(&ir);
+    let parsed = sway_ir::parser::parse(&printed, ir.source_engine()).unwrap_or_else(|err| {
+        panic!("Seed {seed}: failed to parse IR after {after}: {err}\n{printed}");
+    });
+    let reprinted = sway_ir::printer::to_string(&parsed);
+    assert_eq!(
+        printed, reprinted,
+        "Seed {seed}: IR is not stable through print -> parse -> print after {after}."
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+// The generator.
+
+// A xorshift PRNG, good enough for picking instructions.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len() as u64) as usize]
+    }
+}
+
+fn random_ir(source_engine: &SourceEngine, seed: u64) -> Context {
+    let mut rng = Rng::new(seed);
+    let mut context = Context::new(source_engine);
+    context.program_kind = Kind::Script;
+    let module = Module::new(&mut context, Kind::Script);
+
+    let u64_ty = Type::get_uint64(&context);
+    let bool_ty = Type::get_bool(&context);
+
+    // A configurable which is sometimes used in place of a constant.
+    let config_const = Constant::new_uint(&mut context, 64, rng.below(100));
+    let config_val = Value::new_configurable(&mut context, config_const);
+    module.add_global_configurable(&mut context, vec!["C0".to_owned()], config_val);
+
+    // Declare all the functions first.  Calls are only made to functions declared later which
+    // keeps the call graph acyclic, as required by `inline_main`.
+    let num_fns = 1 + rng.below(4);
+    let functions = (0..num_fns)
+        .map(|fn_idx| {
+            let args = if fn_idx == 0 {
+                Vec::new()
+            } else {
+                (0..rng.below(4))
+                    .map(|arg_idx| {
+                        let ty = if rng.chance(70) { u64_ty } else { bool_ty };
+                        (format!("a{arg_idx}"), ty, None)
+                    })
+                    .collect()
+            };
+            let name = if fn_idx == 0 {
+                "main".to_owned()
+            } else {
+                format!("f{fn_idx}")
+            };
+            Function::new(
+                &mut context,
+                module,
+                name,
+                args,
+                u64_ty,
+                None,
+                false,
+                fn_idx == 0,
+                None,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for (fn_idx, function) in functions.iter().enumerate() {
+        random_fn_body(
+            &mut context,
+            &mut rng,
+            *function,
+            &functions[fn_idx + 1..],
+            config_val,
+        );
+    }
+
+    context
+}
+
+fn random_fn_body(
+    context: &mut Context,
+    rng: &mut Rng,
+    function: Function,
+    callees: &[Function],
+    config_val: Value,
+) {
+    let u64_ty = Type::get_uint64(context);
+
+    // Locals are initialized, as they may be loaded before they're stored to and `mem2reg`
+    // expects an initializer in that case, as would be generated by the compiler.
+    let locals = (0..rng.below(3))
+        .map(|idx| {
+            let initializer = Constant::new_uint(context, 64, rng.below(10));
+            function
+                .new_local_var(context, format!("l{idx}"), u64_ty, Some(initializer), true)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let mut blocks = vec![function.get_entry_block(context)];
+    for _ in 0..rng.below(4) {
+        let block = function.create_block(context, None);
+        for _ in 0..rng.below(3) {
+            block.new_arg(context, u64_ty);
+        }
+        blocks.push(block);
+    }
+
+    for (block_idx, block) in blocks.iter().enumerate() {
+        // Values are only used within the block which defines them, so there are no dominance
+        // concerns.  Data flows between blocks via block args and locals instead.
+        let mut u64_vals = vec![Constant::get_uint(context, 64, rng.below(1000))];
+        let mut bool_vals = Vec::new();
+        for arg in block.arg_iter(context).copied().collect::<Vec<_>>() {
+            if arg.get_type(context).is(Type::is_bool, context) {
+                bool_vals.push(arg);
+            } else {
+                u64_vals.push(arg);
+            }
+        }
+
+        for _ in 0..rng.below(8) {
+            let ins_val = match rng.below(6) {
+                0 => {
+                    let op = rng.pick(&[
+                        BinaryOpKind::Add,
+                        BinaryOpKind::Sub,
+                        BinaryOpKind::Mul,
+                        BinaryOpKind::And,
+                        BinaryOpKind::Or,
+                        BinaryOpKind::Xor,
+                    ]);
+                    let lhs = u64_operand(context, rng, &u64_vals, config_val);
+                    let rhs = u64_operand(context, rng, &u64_vals, config_val);
+                    let val = block.ins(context).binary_op(op, lhs, rhs);
+                    u64_vals.push(val);
+                    val
+                }
+                1 => {
+                    let pred = rng.pick(&[
+                        Predicate::Equal,
+                        Predicate::LessThan,
+                        Predicate::GreaterThan,
+                    ]);
+                    let lhs = u64_operand(context, rng, &u64_vals, config_val);
+                    let rhs = u64_operand(context, rng, &u64_vals, config_val);
+                    let val = block.ins(context).cmp(pred, lhs, rhs);
+                    bool_vals.push(val);
+                    val
+                }
+                2 if !locals.is_empty() => {
+                    let local_ptr = block.ins(context).get_local(rng.pick(&locals));
+                    let stored_val = u64_operand(context, rng, &u64_vals, config_val);
+                    block.ins(context).store(local_ptr, stored_val)
+                }
+                3 if !locals.is_empty() => {
+                    let local_ptr = block.ins(context).get_local(rng.pick(&locals));
+                    let val = block.ins(context).load(local_ptr);
+                    u64_vals.push(val);
+                    val
+                }
+                4 if !callees.is_empty() => {
+                    let callee = rng.pick(callees);
+                    let arg_tys = callee
+                        .args_iter(context)
+                        .map(|(_name, arg_val)| arg_val.get_type(context).unwrap())
+                        .collect::<Vec<_>>();
+                    let args = arg_tys
+                        .into_iter()
+                        .map(|ty| {
+                            if ty.is_bool(context) {
+                                if bool_vals.is_empty() || rng.chance(20) {
+                                    Constant::get_bool(context, rng.chance(50))
+                                } else {
+                                    rng.pick(&bool_vals)
+                                }
+                            } else {
+                                u64_operand(context, rng, &u64_vals, config_val)
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    let val = block.ins(context).call(callee, &args);
+                    u64_vals.push(val);
+                    val
+                }
+                _otherwise => {
+                    let logged_val = u64_operand(context, rng, &u64_vals, config_val);
+                    let log_id = Constant::get_uint(context, 64, rng.below(10));
+                    block.ins(context).log(logged_val, u64_ty, log_id)
+                }
+            };
+            maybe_add_metadata(context, rng, ins_val);
+        }
+
+        // Blocks only ever branch forward, and always to at least the next block so that every
+        // block is reachable.
+        let term_val = if block_idx == blocks.len() - 1 {
+            let ret_val = rng.pick(&u64_vals);
+            block.ins(context).ret(ret_val, u64_ty)
+        } else {
+            let next_block = blocks[block_idx + 1];
+            let next_args = random_block_args(context, rng, next_block, &u64_vals);
+            let later_blocks = &blocks[block_idx + 2..];
+            if later_blocks.is_empty() || rng.chance(40) {
+                block.ins(context).branch(next_block, next_args)
+            } else {
+                let other_block = rng.pick(later_blocks);
+                let other_args = random_block_args(context, rng, other_block, &u64_vals);
+                let cond_val = if bool_vals.is_empty() {
+                    let lhs = rng.pick(&u64_vals);
+                    block.ins(context).cmp(Predicate::Equal, lhs, config_val)
+                } else {
+                    rng.pick(&bool_vals)
+                };
+                if rng.chance(50) {
+                    block.ins(context).conditional_branch(
+                        cond_val,
+                        next_block,
+                        other_block,
+                        next_args,
+                        other_args,
+                    )
+                } else {
+                    block.ins(context).conditional_branch(
+                        cond_val,
+                        other_block,
+                        next_block,
+                        other_args,
+                        next_args,
+                    )
+                }
+            }
+        };
+        maybe_add_metadata(context, rng, term_val);
+    }
+}
+
+fn u64_operand(
+    context: &mut Context,
+    rng: &mut Rng,
+    u64_vals: &[Value],
+    config_val: Value,
+) -> Value {
+    if rng.chance(10) {
+        config_val
+    } else if rng.chance(20) {
+        Constant::get_uint(context, 64, rng.below(1000))
+    } else {
+        rng.pick(u64_vals)
+    }
+}
+
+fn random_block_args(
+    context: &Context,
+    rng: &mut Rng,
+    block: Block,
+    u64_vals: &[Value],
+) -> Vec<Value> {
+    (0..block.num_args(context))
+        .map(|_| rng.pick(u64_vals))
+        .collect()
+}
+
+fn maybe_add_metadata(context: &mut Context, rng: &mut Rng, val: Value) {
+    if rng.chance(25) {
+        let md_idx = MetadataIndex::new_integer(context, rng.below(100));
+        val.add_metadatum(context, Some(md_idx));
+    }
+}
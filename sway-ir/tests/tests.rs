@@ -1,11 +1,12 @@
 use std::path::PathBuf;
 
 use sway_ir::{
-    create_arg_demotion_pass, create_const_combine_pass, create_const_demotion_pass,
-    create_dce_pass, create_dom_fronts_pass, create_dominators_pass, create_escaped_symbols_pass,
+    create_arg_dce_pass, create_arg_demotion_pass, create_const_combine_pass,
+    create_const_demotion_pass, create_dce_pass, create_dom_fronts_pass, create_dominators_pass,
+    create_escaped_symbols_pass, create_fn_specialize_pass, create_inline_in_module_pass,
     create_mem2reg_pass, create_memcpyopt_pass, create_misc_demotion_pass, create_postorder_pass,
-    create_ret_demotion_pass, create_simplify_cfg_pass, optimize as opt, Context, PassGroup,
-    PassManager,
+    create_ret_demotion_pass, create_simplify_cfg_pass, optimize as opt, Context, OptimizationGoal,
+    PassGroup, PassManager,
 };
 use sway_types::SourceEngine;
 
@@ -119,6 +120,30 @@ fn inline() {
 
 // -------------------------------------------------------------------------------------------------
 
+#[test]
+fn inline_module() {
+    run_tests("inline_module", |first_line, ir: &mut Context| {
+        // The first line holds the inliner config, e.g., `// threshold 10 gas`.
+        let words = first_line.split(' ').collect::<Vec<_>>();
+        for param_and_arg in words.windows(2) {
+            if param_and_arg[0] == "threshold" {
+                ir.inline_config.threshold = param_and_arg[1].parse().unwrap();
+            }
+        }
+        if words.contains(&"gas") {
+            ir.inline_config.goal = OptimizationGoal::Gas;
+        }
+
+        let mut pass_mgr = PassManager::default();
+        let mut pass_group = PassGroup::default();
+        let pass = pass_mgr.register(create_inline_in_module_pass());
+        pass_group.append_pass(pass);
+        pass_mgr.run(ir, &pass_group).unwrap()
+    })
+}
+
+// -------------------------------------------------------------------------------------------------
+
 // Clippy suggests using the map iterator below directly instead of collecting from it first, but
 // if we try that then we have borrowing issues with `ir` which is used within the closure.
 #[allow(clippy::needless_collect)]
@@ -252,6 +277,34 @@ fn memcpyopt() {
 
 // -------------------------------------------------------------------------------------------------
 
+#[allow(clippy::needless_collect)]
+#[test]
+fn fn_specialize() {
+    run_tests("fn_specialize", |_first_line, ir: &mut Context| {
+        let mut pass_mgr = PassManager::default();
+        let mut pass_group = PassGroup::default();
+        let pass = pass_mgr.register(create_fn_specialize_pass());
+        pass_group.append_pass(pass);
+        pass_mgr.run(ir, &pass_group).unwrap()
+    })
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(clippy::needless_collect)]
+#[test]
+fn arg_dce() {
+    run_tests("arg_dce", |_first_line, ir: &mut Context| {
+        let mut pass_mgr = PassManager::default();
+        let mut pass_group = PassGroup::default();
+        let pass = pass_mgr.register(create_arg_dce_pass());
+        pass_group.append_pass(pass);
+        pass_mgr.run(ir, &pass_group).unwrap()
+    })
+}
+
+// -------------------------------------------------------------------------------------------------
+
 #[test]
 fn serialize() {
     // This isn't running a pass, it's just confirming that the IR can be loaded and printed, and
@@ -260,3 +313,47 @@ fn serialize() {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+#[test]
+fn round_trip() {
+    // Every test input should survive being printed and parsed again unchanged.  This catches
+    // printer output which the parser can't read back, and anything the parser silently drops.
+    let source_engine = SourceEngine::default();
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let tests_dir: PathBuf = format!("{manifest_dir}/tests").into();
+    for sub_dir in std::fs::read_dir(tests_dir).unwrap() {
+        let sub_dir = sub_dir.unwrap().path();
+        if !sub_dir.is_dir() {
+            continue;
+        }
+        for entry in std::fs::read_dir(sub_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().map_or(true, |ext| ext != "ir") {
+                continue;
+            }
+
+            let input = std::fs::read_to_string(&path).unwrap();
+            let ir = sway_ir::parser::parse(&input, &source_engine).unwrap_or_else(|err| {
+                panic!("{}: {err}", path.display());
+            });
+            let printed = sway_ir::printer::to_string(&ir);
+
+            let reparsed = sway_ir::parser::parse(&printed, &source_engine).unwrap_or_else(|err| {
+                panic!(
+                    "{}: failed to parse printed IR: {err}\n{printed}",
+                    path.display()
+                );
+            });
+            let reprinted = sway_ir::printer::to_string(&reparsed);
+
+            assert_eq!(
+                printed,
+                reprinted,
+                "{}: IR is not stable through print -> parse -> print.",
+                path.display()
+            );
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -1,4 +1,4 @@
-use crate::error::LanguageServerError;
+use crate::{core::document::TextDocument, error::LanguageServerError};
 use lsp_types::{Position, Range, TextEdit};
 use std::sync::Arc;
 use swayfmt::Formatter;
@@ -21,3 +21,35 @@ pub fn get_page_text_edit(
         new_text: formatted_code,
     })
 }
+
+pub fn get_range_text_edit(
+    document: &TextDocument,
+    range: Range,
+    formatter: &mut Formatter,
+) -> Result<TextEdit, LanguageServerError> {
+    let start = document.position_to_byte(range.start);
+    let end = document.position_to_byte(range.end);
+    // the range is snapped to the items or statements enclosing it
+    let formatted_range = formatter
+        .format_range(Arc::from(document.get_text()), start..end, None)
+        .map_err(LanguageServerError::FormatError)?;
+
+    Ok(TextEdit {
+        range: Range::new(
+            document.byte_to_lsp_position(formatted_range.range.start),
+            document.byte_to_lsp_position(formatted_range.range.end),
+        ),
+        new_text: formatted_range.formatted_code,
+    })
+}
+
+/// Formats the item or statement ended by the character typed before `position`.
+pub fn get_on_type_text_edit(
+    document: &TextDocument,
+    position: Position,
+    formatter: &mut Formatter,
+) -> Result<TextEdit, LanguageServerError> {
+    let end = document.position_to_byte(position);
+    let start = document.byte_to_lsp_position(end.saturating_sub(1));
+    get_range_text_edit(document, Range::new(start, position), formatter)
+}
@@ -44,6 +44,16 @@ impl TextDocument {
     pub fn get_text(&self) -> String {
         self.content.to_string()
     }
+
+    /// Returns the byte index of a position within the document.
+    pub fn position_to_byte(&self, position: Position) -> usize {
+        self.content.char_to_byte(self.position_to_index(position))
+    }
+
+    /// Returns the position of a byte index within the document.
+    pub fn byte_to_lsp_position(&self, byte_index: usize) -> Position {
+        self.byte_to_position(byte_index)
+    }
 }
 
 // private methods
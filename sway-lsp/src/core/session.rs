@@ -2,7 +2,7 @@ use crate::{
     capabilities::{
         self,
         diagnostic::{get_diagnostics, Diagnostics},
-        formatting::get_page_text_edit,
+        formatting::{get_on_type_text_edit, get_page_text_edit, get_range_text_edit},
         runnable::{Runnable, RunnableMainFn, RunnableTestFn},
     },
     core::{
@@ -359,6 +359,40 @@ impl Session {
             .map(|page_text_edit| vec![page_text_edit])
     }
 
+    pub fn format_range(
+        &self,
+        url: &Url,
+        range: Range,
+    ) -> Result<Vec<TextEdit>, LanguageServerError> {
+        let document = self
+            .documents
+            .try_get(url.path())
+            .try_unwrap()
+            .ok_or_else(|| DocumentError::DocumentNotFound {
+                path: url.path().to_string(),
+            })?;
+
+        get_range_text_edit(&document, range, &mut <_>::default())
+            .map(|range_text_edit| vec![range_text_edit])
+    }
+
+    pub fn format_on_type(
+        &self,
+        url: &Url,
+        position: Position,
+    ) -> Result<Vec<TextEdit>, LanguageServerError> {
+        let document = self
+            .documents
+            .try_get(url.path())
+            .try_unwrap()
+            .ok_or_else(|| DocumentError::DocumentNotFound {
+                path: url.path().to_string(),
+            })?;
+
+        get_on_type_text_edit(&document, position, &mut <_>::default())
+            .map(|on_type_text_edit| vec![on_type_text_edit])
+    }
+
     pub fn handle_open_file(&self, uri: &Url) {
         if !self.documents.contains_key(uri.path()) {
             if let Ok(text_document) = TextDocument::build_from_path(uri.path()) {
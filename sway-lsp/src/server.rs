@@ -98,6 +98,11 @@ pub fn capabilities() -> ServerCapabilities {
         definition_provider: Some(OneOf::Left(true)),
         document_formatting_provider: Some(OneOf::Left(true)),
         document_highlight_provider: Some(OneOf::Left(true)),
+        document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+            first_trigger_character: "}".to_string(),
+            more_trigger_character: Some(vec![";".to_string()]),
+        }),
+        document_range_formatting_provider: Some(OneOf::Left(true)),
         document_symbol_provider: Some(OneOf::Left(true)),
         execute_command_provider: Some(ExecuteCommandOptions {
             commands: vec![],
@@ -473,6 +478,35 @@ impl LanguageServer for Backend {
             })
     }
 
+    async fn range_formatting(
+        &self,
+        params: DocumentRangeFormattingParams,
+    ) -> jsonrpc::Result<Option<Vec<TextEdit>>> {
+        self.get_uri_and_session(&params.text_document.uri)
+            .and_then(|(uri, session)| session.format_range(&uri, params.range).map(Some))
+            .or_else(|err| {
+                tracing::error!("{}", err.to_string());
+                Ok(None)
+            })
+    }
+
+    async fn on_type_formatting(
+        &self,
+        params: DocumentOnTypeFormattingParams,
+    ) -> jsonrpc::Result<Option<Vec<TextEdit>>> {
+        let text_document_position = params.text_document_position;
+        self.get_uri_and_session(&text_document_position.text_document.uri)
+            .and_then(|(uri, session)| {
+                session
+                    .format_on_type(&uri, text_document_position.position)
+                    .map(Some)
+            })
+            .or_else(|err| {
+                tracing::error!("{}", err.to_string());
+                Ok(None)
+            })
+    }
+
     async fn rename(&self, params: RenameParams) -> jsonrpc::Result<Option<WorkspaceEdit>> {
         match self.get_uri_and_session(&params.text_document_position.text_document.uri) {
             Ok((uri, session)) => {
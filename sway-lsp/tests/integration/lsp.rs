@@ -202,6 +202,112 @@ pub(crate) async fn format_request(service: &mut LspService<Backend>, uri: &Url)
     formatting
 }
 
+/// Unformat the `value` field of the `Data` initializer of the `doc_comments` example, as
+/// `value :NumberOrString::Number(20) ,`.
+async fn unformat_data_value(service: &mut LspService<Backend>, uri: &Url) {
+    let params = json!({
+        "textDocument": {
+            "uri": uri,
+            "version": 2
+        },
+        "contentChanges": [
+            {
+                "range": {
+                    "start": {
+                        "line": 45,
+                        "character": 17
+                    },
+                    "end": {
+                        "line": 45,
+                        "character": 46
+                    }
+                },
+                "rangeLength": 29,
+                "text": " :NumberOrString::Number(20) ,",
+            }
+        ]
+    });
+    let did_change = Request::build("textDocument/didChange")
+        .params(params)
+        .finish();
+    let response = call_request(service, did_change).await;
+    assert_eq!(response, Ok(None));
+}
+
+/// The edit formatting the statement declaring `_data` in the `doc_comments` example, once its
+/// `value` field is unformatted.
+fn data_statement_text_edits() -> serde_json::Value {
+    json!([
+        {
+            "range": {
+                "start": {
+                    "line": 44,
+                    "character": 0
+                },
+                "end": {
+                    "line": 47,
+                    "character": 10
+                }
+            },
+            "newText": "        let mut _data = Data {\n            value: NumberOrString::Number(20),\n            address: 1337,\n        };",
+        }
+    ])
+}
+
+pub(crate) async fn range_format_request(service: &mut LspService<Backend>, uri: &Url) -> Request {
+    unformat_data_value(service, uri).await;
+    let params = json!({
+        "textDocument": {
+            "uri": uri,
+        },
+        "range": {
+            "start": {
+                "line": 45,
+                "character": 0
+            },
+            "end": {
+                "line": 45,
+                "character": 37
+            }
+        },
+        "options": {
+            "tabSize": 4,
+            "insertSpaces": true
+        },
+    });
+    let range_formatting = build_request_with_id("textDocument/rangeFormatting", params, 1);
+    let response = call_request(service, range_formatting.clone()).await;
+    let expected = Response::from_ok(1.into(), data_statement_text_edits());
+    assert_json_eq!(expected, response.ok().unwrap());
+    range_formatting
+}
+
+pub(crate) async fn on_type_format_request(
+    service: &mut LspService<Backend>,
+    uri: &Url,
+) -> Request {
+    unformat_data_value(service, uri).await;
+    let params = json!({
+        "textDocument": {
+            "uri": uri,
+        },
+        "position": {
+            "line": 47,
+            "character": 10
+        },
+        "ch": ";",
+        "options": {
+            "tabSize": 4,
+            "insertSpaces": true
+        },
+    });
+    let on_type_formatting = build_request_with_id("textDocument/onTypeFormatting", params, 1);
+    let response = call_request(service, on_type_formatting.clone()).await;
+    let expected = Response::from_ok(1.into(), data_statement_text_edits());
+    assert_json_eq!(expected, response.ok().unwrap());
+    on_type_formatting
+}
+
 pub(crate) async fn highlight_request(service: &mut LspService<Backend>, uri: &Url) -> Request {
     let params = json!({
         "textDocument": {
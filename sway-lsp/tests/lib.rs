@@ -1740,6 +1740,16 @@ lsp_capability_test!(
     lsp::format_request,
     doc_comments_dir().join("src/main.sw")
 );
+lsp_capability_test!(
+    range_format,
+    lsp::range_format_request,
+    doc_comments_dir().join("src/main.sw")
+);
+lsp_capability_test!(
+    on_type_format,
+    lsp::on_type_format_request,
+    doc_comments_dir().join("src/main.sw")
+);
 lsp_capability_test!(
     highlight,
     lsp::highlight_request,
@@ -58,6 +58,10 @@ pub const CFG_ATTRIBUTE_NAME: &str = "cfg";
 pub const CFG_TARGET_ARG_NAME: &str = "target";
 pub const CFG_PROGRAM_TYPE_ARG_NAME: &str = "program_type";
 
+/// The valid attribute strings related to formatting.
+pub const FMT_ATTRIBUTE_NAME: &str = "fmt";
+pub const FMT_SKIP_ARG_NAME: &str = "skip";
+
 /// The list of valid attributes.
 pub const VALID_ATTRIBUTE_NAMES: &[&str] = &[
     STORAGE_PURITY_ATTRIBUTE_NAME,
@@ -68,4 +72,5 @@ pub const VALID_ATTRIBUTE_NAMES: &[&str] = &[
     PAYABLE_ATTRIBUTE_NAME,
     ALLOW_ATTRIBUTE_NAME,
     CFG_ATTRIBUTE_NAME,
+    FMT_ATTRIBUTE_NAME,
 ];
@@ -7,10 +7,19 @@ pub struct PerformanceMetric {
     pub memory_usage: u64,
 }
 
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RegisterAllocationMetric {
+    pub function: String,
+    pub spilled_registers: usize,
+    pub rematerialized_registers: usize,
+    pub coalesced_moves: usize,
+}
+
 #[derive(Debug, Default, Serialize)]
 pub struct PerformanceData {
     pub bytecode_size: usize,
     pub metrics: Vec<PerformanceMetric>,
+    pub register_allocation: Vec<RegisterAllocationMetric>,
 }
 
 #[macro_export]
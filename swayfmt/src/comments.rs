@@ -1,11 +1,12 @@
 use ropey::Rope;
-use std::{fmt::Write, ops::Range};
-use sway_ast::token::{Comment, CommentKind};
+use std::{collections::HashMap, fmt::Write, ops::Range, sync::Arc};
+use sway_ast::token::{Comment, CommentKind, CommentedTokenTree, CommentedTree};
 use sway_types::{Span, Spanned};
 
 use crate::{
+    config::manifest::Config,
     formatter::FormattedCode,
-    parse::parse_snippet,
+    parse::{lex, parse_snippet},
     utils::map::{
         byte_span::{ByteSpan, LeafSpans},
         comments::CommentMap,
@@ -361,6 +362,314 @@ fn insert_after_span(
     Ok(comment_str.len())
 }
 
+/// Converts `/* */` comments to `//` comments and wraps the comments exceeding the comment
+/// width, as configured by `normalize_comments` and `wrap_comments`.
+///
+/// This is done once the code is formatted, so that comments are wrapped according to their
+/// final indentation.
+pub fn rewrite_comments(
+    formatted_code: &mut FormattedCode,
+    config: &Config,
+) -> Result<(), FormatterError> {
+    if config.comments.normalize_comments {
+        *formatted_code = normalize_comments(formatted_code)?;
+    }
+    if config.comments.wrap_comments {
+        *formatted_code = wrap_comments(formatted_code, config)?;
+    }
+
+    Ok(())
+}
+
+/// Collects the byte ranges of the comments and doc comments of the given code.
+fn comment_ranges(code: &str) -> Result<Vec<Range<usize>>, FormatterError> {
+    fn collect(commented_token_tree: &CommentedTokenTree, ranges: &mut Vec<Range<usize>>) {
+        match commented_token_tree {
+            CommentedTokenTree::Comment(comment) => {
+                ranges.push(comment.span.start()..comment.span.end())
+            }
+            CommentedTokenTree::Tree(CommentedTree::DocComment(doc_comment)) => {
+                ranges.push(doc_comment.span.start()..doc_comment.span.end())
+            }
+            CommentedTokenTree::Tree(CommentedTree::Group(group)) => {
+                for item in group.token_stream.token_trees().iter() {
+                    collect(item, ranges);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let tts = lex(&Arc::from(code))?;
+    let mut ranges = vec![];
+    for commented_token_tree in tts.token_trees().iter() {
+        collect(commented_token_tree, &mut ranges);
+    }
+    Ok(ranges)
+}
+
+/// Replaces the `/* */` comments of the given code by `//` comments where possible.
+fn normalize_comments(code: &str) -> Result<String, FormatterError> {
+    let mut normalized = code.to_string();
+    // Replace comments from the last one, so that the ranges of the others stay valid.
+    for range in comment_ranges(code)?.into_iter().rev() {
+        if let Some(line_comments) = block_to_line_comments(code, &range) {
+            normalized.replace_range(range, &line_comments);
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// Returns the `//` comments equivalent to the `/* */` comment in the given range, or `None` if
+/// it isn't a block comment or can't be converted safely, i.e. when it is followed by code on the
+/// same line, preceded by code and spans multiple lines, or contains nested comments.
+fn block_to_line_comments(code: &str, range: &Range<usize>) -> Option<String> {
+    let inner = code[range.clone()].strip_prefix("/*")?.strip_suffix("*/")?;
+    if inner.starts_with(['*', '!']) || inner.contains("/*") || inner.contains("*/") {
+        return None;
+    }
+
+    let line_start = code[..range.start].rfind('\n').map_or(0, |ix| ix + 1);
+    let line_end = code[range.end..]
+        .find('\n')
+        .map_or(code.len(), |ix| range.end + ix);
+    if !code[range.end..line_end].trim().is_empty() {
+        return None;
+    }
+
+    // Strip the leading `*` of each continuation line, if any.
+    let mut lines = inner
+        .lines()
+        .enumerate()
+        .map(|(ix, line)| {
+            let line = line.trim_start();
+            let line = match line.strip_prefix('*') {
+                Some(line) if ix > 0 => line.strip_prefix(' ').unwrap_or(line),
+                _ => line,
+            };
+            line.trim_end()
+        })
+        .collect::<Vec<_>>();
+    while lines.last().map_or(false, |line| line.is_empty()) {
+        lines.pop();
+    }
+    let first_line = lines.iter().position(|line| !line.is_empty())?;
+    let lines = &lines[first_line..];
+
+    let before = &code[line_start..range.start];
+    let indent = match before.trim().is_empty() {
+        true => before,
+        false if lines.len() == 1 => "",
+        false => return None,
+    };
+    let line_comments = lines
+        .iter()
+        .map(|line| match line.is_empty() {
+            true => "//".to_string(),
+            false => format!("// {line}"),
+        })
+        .collect::<Vec<_>>()
+        .join(&format!("\n{indent}"));
+    Some(line_comments)
+}
+
+/// A `//`, `///` or `//!` comment which is alone on its line.
+struct CommentLine<'a> {
+    /// The whole line, without its newline.
+    line: &'a str,
+    indent: &'a str,
+    prefix: &'a str,
+    /// The text of the comment, without its prefix and the space following it.
+    text: &'a str,
+}
+
+impl<'a> CommentLine<'a> {
+    fn new(line: &'a str, indent_len: usize) -> Option<Self> {
+        let (indent, comment) = line.split_at(indent_len);
+        let prefix = if comment.starts_with("////") {
+            return None;
+        } else if comment.starts_with("///") || comment.starts_with("//!") {
+            &comment[..3]
+        } else if comment.starts_with("//") {
+            &comment[..2]
+        } else {
+            return None;
+        };
+        let text = &comment[prefix.len()..];
+
+        Some(Self {
+            line,
+            indent,
+            prefix,
+            text: text.strip_prefix(' ').unwrap_or(text),
+        })
+    }
+}
+
+/// Wraps the comments of the given code which are alone on their line and exceed the comment
+/// width.  Trailing comments and `/* */` comments are left as they are.
+fn wrap_comments(code: &str, config: &Config) -> Result<String, FormatterError> {
+    let comment_ends = comment_ranges(code)?
+        .into_iter()
+        .map(|range| (range.start, range.end))
+        .collect::<HashMap<_, _>>();
+
+    let mut wrapped = String::with_capacity(code.len());
+    let mut block: Vec<CommentLine> = vec![];
+    let mut offset = 0;
+    for line in code.split_inclusive('\n') {
+        let content = line.trim_end_matches('\n');
+        let indent_len = content.len() - content.trim_start().len();
+        let comment_line = comment_ends
+            .get(&(offset + indent_len))
+            .filter(|end| **end == offset + content.len())
+            .and_then(|_| CommentLine::new(content, indent_len));
+        offset += line.len();
+
+        match comment_line {
+            Some(comment_line) => {
+                // Consecutive comments are reflowed together if they share their indentation and
+                // their prefix.
+                if block.first().map_or(false, |first| {
+                    first.indent != comment_line.indent || first.prefix != comment_line.prefix
+                }) {
+                    write_comment_block(&mut wrapped, &block, config)?;
+                    block.clear();
+                }
+                block.push(comment_line);
+            }
+            None => {
+                write_comment_block(&mut wrapped, &block, config)?;
+                block.clear();
+                write!(wrapped, "{line}")?;
+            }
+        }
+    }
+    write_comment_block(&mut wrapped, &block, config)?;
+    if !code.ends_with('\n') && wrapped.ends_with('\n') {
+        wrapped.pop();
+    }
+
+    Ok(wrapped)
+}
+
+/// Writes a block of consecutive comments sharing their indentation and prefix, reflowing each of
+/// its paragraphs which has a line exceeding the comment width.  Blank lines, headings, tables,
+/// quotes and code are kept as they are, and each list item is a paragraph of its own.
+fn write_comment_block(
+    wrapped: &mut String,
+    block: &[CommentLine],
+    config: &Config,
+) -> Result<(), FormatterError> {
+    let first = match block.first() {
+        Some(first) => first,
+        None => return Ok(()),
+    };
+    let indent_width: usize = first
+        .indent
+        .chars()
+        .map(|c| match c {
+            '\t' => config.whitespace.tab_spaces,
+            _ => 1,
+        })
+        .sum();
+    // The width left for the text of the comments, after their prefix and a space.
+    let width = config
+        .comments
+        .comment_width
+        .saturating_sub(indent_width + first.prefix.len() + 1);
+
+    let mut in_code_block = false;
+    let mut paragraph: Vec<&CommentLine> = vec![];
+    for comment_line in block {
+        let text = comment_line.text;
+        let is_fence = text.trim_start().starts_with("```");
+        let is_verbatim = in_code_block
+            || is_fence
+            || text.trim().is_empty()
+            || text.starts_with("    ")
+            || text.starts_with(['#', '|', '>']);
+        if is_fence {
+            in_code_block = !in_code_block;
+        }
+
+        if is_verbatim || list_marker(text).is_some() {
+            write_paragraph(wrapped, &paragraph, width)?;
+            paragraph.clear();
+        }
+        if is_verbatim {
+            writeln!(wrapped, "{}", comment_line.line)?;
+        } else {
+            paragraph.push(comment_line);
+        }
+    }
+    write_paragraph(wrapped, &paragraph, width)?;
+
+    Ok(())
+}
+
+/// Writes the lines of a paragraph of comments, filling them with as many words as fit within
+/// `width` if any of them exceeds it.  The lines following the first one of a list item are
+/// indented to its text.
+fn write_paragraph(
+    wrapped: &mut String,
+    paragraph: &[&CommentLine],
+    width: usize,
+) -> Result<(), FormatterError> {
+    let first = match paragraph.first() {
+        Some(first) => first,
+        None => return Ok(()),
+    };
+    if paragraph
+        .iter()
+        .all(|comment_line| comment_line.text.chars().count() <= width)
+    {
+        for comment_line in paragraph {
+            writeln!(wrapped, "{}", comment_line.line)?;
+        }
+        return Ok(());
+    }
+
+    let marker = list_marker(first.text).unwrap_or("");
+    let words = paragraph
+        .iter()
+        .enumerate()
+        .flat_map(|(ix, comment_line)| match ix {
+            0 => comment_line.text[marker.len()..].split_whitespace(),
+            _ => comment_line.text.split_whitespace(),
+        });
+    let mut line = marker.to_string();
+    let mut line_is_empty = true;
+    for word in words {
+        if !line_is_empty && line.chars().count() + 1 + word.chars().count() > width {
+            writeln!(wrapped, "{}{} {}", first.indent, first.prefix, line)?;
+            line = " ".repeat(marker.len());
+            line_is_empty = true;
+        }
+        if !line_is_empty {
+            line.push(' ');
+        }
+        line.push_str(word);
+        line_is_empty = false;
+    }
+    writeln!(wrapped, "{}{} {}", first.indent, first.prefix, line)?;
+
+    Ok(())
+}
+
+/// Returns the marker starting a list item, e.g. `- ` or `1. `, along with its trailing space.
+fn list_marker(text: &str) -> Option<&str> {
+    if text.starts_with(['-', '*', '+']) && text[1..].starts_with(' ') {
+        return Some(&text[..2]);
+    }
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    match digits > 0 && text[digits..].starts_with(". ") {
+        true => Some(&text[..digits + 2]),
+        false => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::utils::map::byte_span::ByteSpan;
@@ -5,6 +5,10 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Copy, Clone)]
 pub struct Literals {
     /// Format string literals where necessary.
+    ///
+    /// Not supported: a string literal can't be broken over multiple lines without changing its
+    /// value, as Sway strings don't support line continuations. Enabling it in a `swayfmt.toml`
+    /// only prints a warning.
     pub format_strings: bool,
     /// Format hexadecimal integer literals.
     pub hex_literal_case: HexLiteralCase,
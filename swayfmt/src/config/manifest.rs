@@ -120,12 +120,27 @@ impl ConfigOptions {
                 path: config_path,
                 err: e,
             })?;
-        let toml_de = &mut toml::de::Deserializer::new(&config_str);
+        Self::from_toml_str(&config_str)
+    }
+    /// Parse the `ConfigOptions` from the contents of a `swayfmt.toml`, warning about the options
+    /// which are recognized but can't be supported.
+    fn from_toml_str(config_str: &str) -> Result<Self, ConfigError> {
+        let toml_de = &mut toml::de::Deserializer::new(config_str);
         let config_opts: Self = serde_ignored::deserialize(toml_de, |field| {
             let warning = format!("  WARNING! found unusable configuration: {field}");
             println_yellow_err(&warning);
         })
         .map_err(|e| ConfigError::Deserialize { err: (e) })?;
+        if config_opts
+            .literals
+            .and_then(|literals| literals.format_strings)
+            .unwrap_or(false)
+        {
+            println_yellow_err(
+                "  WARNING! found unusable configuration: literals.format_strings, string \
+                literals can't be broken over several lines without changing their value",
+            );
+        }
         Ok(config_opts)
     }
     /// Given a directory to a forc project containing a `swayfmt.toml`, read the config.
@@ -139,3 +154,22 @@ impl ConfigOptions {
         Self::from_file(file_path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, ConfigOptions};
+
+    #[test]
+    fn format_strings_is_accepted() {
+        for value in [false, true] {
+            let config_str = format!("[literals]\nformat_strings = {value}\n");
+            let config_opts = ConfigOptions::from_toml_str(&config_str).unwrap();
+            let literals = config_opts.literals.unwrap();
+            assert_eq!(literals.format_strings, Some(value));
+            assert_eq!(
+                Config::from_opts(config_opts).literals.format_strings,
+                value
+            );
+        }
+    }
+}
@@ -6,8 +6,10 @@ pub struct Ordering {
     /// Reorder import and extern crate statements alphabetically.
     pub reorder_imports: bool,
     /// Reorder module statements alphabetically in group.
+    ///
+    /// Off by default, as submodules are type-checked in the order they are declared in.
     pub reorder_modules: bool,
-    /// Reorder `impl` items.
+    /// Reorder `impl` items, putting constants before functions.
     pub reorder_impl_items: bool,
 }
 
@@ -15,7 +17,7 @@ impl Default for Ordering {
     fn default() -> Self {
         Self {
             reorder_imports: true,
-            reorder_modules: true,
+            reorder_modules: false,
             reorder_impl_items: false,
         }
     }
@@ -16,6 +16,8 @@ pub enum FormatterError {
     CommentError,
     #[error("Error while formatting newline sequences")]
     NewlineSequenceError,
+    #[error("Error while restoring code with formatting turned off")]
+    SkipError,
     #[error("Cannot format raw hashbang attribute,\nIf this is intended to be a doc comment try using the `//!` syntax instead")]
     HashBangAttributeError,
 }
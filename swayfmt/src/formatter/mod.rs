@@ -1,5 +1,6 @@
-use self::shape::Shape;
-use crate::comments::{write_comments, CommentsContext};
+use self::{shape::Shape, skip::restore_skipped_code};
+use crate::comments::{rewrite_comments, write_comments, CommentsContext};
+use crate::module::{imports::reorder_imports, ordering::reorder_items};
 use crate::parse::parse_file;
 use crate::utils::map::comments::CommentMap;
 use crate::utils::map::{newline::handle_newlines, newline_style::apply_newline_style};
@@ -11,7 +12,11 @@ use std::{fmt::Write, path::Path, sync::Arc};
 use sway_core::BuildConfig;
 use sway_types::{SourceEngine, Spanned};
 
+mod range;
 pub(crate) mod shape;
+pub(crate) mod skip;
+
+pub use self::range::FormattedRange;
 
 #[derive(Debug, Default, Clone)]
 pub struct Formatter {
@@ -56,9 +61,19 @@ impl Formatter {
                 .heuristics_pref
                 .to_width_heuristics(self.config.whitespace.max_width),
         );
-        let src = src.trim();
+        let unformatted_src = src.trim();
 
         let path = build_config.map(|build_config| build_config.canonical_root_module());
+        // Imports are reordered, grouped and merged before formatting the rest of the code.
+        let src = reorder_imports(
+            &self.source_engine,
+            unformatted_src,
+            path.clone(),
+            &self.config,
+        )?;
+        // So are `mod` items and the items of `impl` blocks.
+        let src = reorder_items(&self.source_engine, &src, path.clone(), &self.config)?;
+        let src = src.as_str();
         // Formatted code will be pushed here with raw newline stlye.
         // Which means newlines are not converted into system-specific versions until `apply_newline_style()`.
         // Use the length of src as a hint of the memory size needed for `raw_formatted_code`,
@@ -87,10 +102,19 @@ impl Formatter {
             Arc::from(src),
             &module,
             Arc::from(formatted_code.clone()),
-            path,
+            path.clone(),
             &mut formatted_code,
             self,
         )?;
+        // Wrap and normalize comments, now that their indentation is known
+        rewrite_comments(&mut formatted_code, &self.config)?;
+        // Restore the code which formatting is turned off for
+        restore_skipped_code(
+            &self.source_engine,
+            unformatted_src,
+            &mut formatted_code,
+            path,
+        )?;
         // Replace newlines with specified `NewlineStyle`
         apply_newline_style(
             self.config.whitespace.newline_style,
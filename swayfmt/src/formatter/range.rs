@@ -0,0 +1,207 @@
+//! Formatting of a range of the source code, rather than a whole file.
+use crate::{
+    config::imports::{GroupImports, ImportGranularity},
+    formatter::{FormattedCode, Formatter, FormatterError},
+    parse::parse_file,
+};
+use std::{ops::Range, sync::Arc};
+use sway_ast::{
+    attribute::Annotated, AttributeDecl, CodeBlockContents, ItemImplItem, ItemKind, Module,
+    Statement,
+};
+use sway_core::BuildConfig;
+use sway_types::{Span, Spanned};
+
+/// The code formatted in place of a range of the source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormattedRange {
+    /// The byte range of the source code which was formatted, i.e. the range that was requested
+    /// snapped to the items or statements enclosing it.
+    pub range: Range<usize>,
+    /// The formatted code to replace the range with.
+    pub formatted_code: FormattedCode,
+}
+
+/// A node of the syntax tree which can be formatted in place.
+#[derive(Clone, Copy)]
+pub(super) enum Node<'a> {
+    Module(&'a Module),
+    Item(&'a ItemKind),
+    ImplItem(&'a ItemImplItem),
+    /// A statement or expression, which isn't descended into.
+    Leaf,
+}
+
+/// A node nested within another one.
+pub(super) struct Child<'a> {
+    /// The span of the node, including its attributes.
+    pub(super) span: Span,
+    pub(super) attributes: &'a [AttributeDecl],
+    pub(super) node: Node<'a>,
+}
+
+impl Formatter {
+    /// Formats the items or statements of `src` overlapping the given byte range, leaving the
+    /// rest of the code as it is.  The range is snapped to the innermost items, `impl` items or
+    /// function body statements enclosing it, or to the whole file if it isn't within any item.
+    ///
+    /// Like [Formatter::format], this requires the whole file to parse.  Imports and items aren't
+    /// reordered, as that could move code in or out of the range.
+    pub fn format_range(
+        &mut self,
+        src: Arc<str>,
+        range: Range<usize>,
+        build_config: Option<&BuildConfig>,
+    ) -> Result<FormattedRange, FormatterError> {
+        let config = self.config.clone();
+        self.config.ordering.reorder_imports = false;
+        self.config.ordering.reorder_modules = false;
+        self.config.ordering.reorder_impl_items = false;
+        self.config.imports.group_imports = GroupImports::Preserve;
+        self.config.imports.imports_granularity = ImportGranularity::Preserve;
+        let formatted_code = self.format(src.clone(), build_config);
+        self.config = config;
+        let formatted_code = formatted_code?;
+
+        let path = build_config.map(|build_config| build_config.canonical_root_module());
+        let unformatted_module = parse_file(&self.source_engine, src.clone(), path.clone())?.value;
+        let formatted_module = parse_file(
+            &self.source_engine,
+            Arc::from(formatted_code.as_str()),
+            path,
+        )?
+        .value;
+
+        let snapped = snap_range(
+            Node::Module(&unformatted_module),
+            Node::Module(&formatted_module),
+            &range,
+        );
+        Ok(match snapped {
+            Some((unformatted_span, formatted_span)) => {
+                let range = extend_to_line_start(&src, unformatted_span);
+                let formatted_range = extend_to_line_start(&formatted_code, formatted_span);
+                FormattedRange {
+                    range,
+                    formatted_code: formatted_code[formatted_range].to_string(),
+                }
+            }
+            None => FormattedRange {
+                range: 0..src.len(),
+                formatted_code,
+            },
+        })
+    }
+}
+
+/// Snaps `range` to the innermost nodes enclosing it within the given unformatted node, and
+/// returns the spans of those nodes along with the spans of their formatted counterparts.
+///
+/// Returns `None` if the range doesn't overlap any of the node's children, or the formatted node
+/// doesn't have the same children, in which case the node itself is to be formatted.
+fn snap_range(unformatted: Node, formatted: Node, range: &Range<usize>) -> Option<(Span, Span)> {
+    let unformatted_children = children(unformatted);
+    let formatted_children = children(formatted);
+    if unformatted_children.len() != formatted_children.len() {
+        return None;
+    }
+
+    // An empty range overlaps the node it is within.
+    let end = range.end.max(range.start + 1);
+    let overlapping = unformatted_children
+        .iter()
+        .enumerate()
+        .filter(|(_, child)| child.span.start() < end && range.start < child.span.end())
+        .map(|(ix, _)| ix)
+        .collect::<Vec<_>>();
+    match overlapping.as_slice() {
+        [] => None,
+        [ix] => {
+            let unformatted_child = &unformatted_children[*ix];
+            let formatted_child = &formatted_children[*ix];
+            snap_range(unformatted_child.node, formatted_child.node, range)
+                .or_else(|| Some((unformatted_child.span.clone(), formatted_child.span.clone())))
+        }
+        [first, .., last] => Some((
+            Span::join(
+                unformatted_children[*first].span.clone(),
+                unformatted_children[*last].span.clone(),
+            ),
+            Span::join(
+                formatted_children[*first].span.clone(),
+                formatted_children[*last].span.clone(),
+            ),
+        )),
+    }
+}
+
+/// The nodes nested directly within a node.
+pub(super) fn children(node: Node) -> Vec<Child> {
+    match node {
+        Node::Module(module) => module
+            .items
+            .iter()
+            .map(|item| Child {
+                span: item.span(),
+                attributes: &item.attribute_list,
+                node: Node::Item(&item.value),
+            })
+            .collect(),
+        Node::Item(ItemKind::Fn(item_fn)) | Node::ImplItem(ItemImplItem::Fn(item_fn)) => {
+            code_block_children(item_fn.body.get())
+        }
+        Node::Item(ItemKind::Impl(item_impl)) => item_impl
+            .contents
+            .get()
+            .iter()
+            .map(|item| Child {
+                span: annotated_span(item),
+                attributes: &item.attribute_list,
+                node: Node::ImplItem(&item.value),
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn code_block_children(code_block: &CodeBlockContents) -> Vec<Child> {
+    code_block
+        .statements
+        .iter()
+        .map(|statement| match statement {
+            Statement::Item(item) => Child {
+                span: item.span(),
+                attributes: &item.attribute_list,
+                node: Node::Item(&item.value),
+            },
+            _ => Child {
+                span: statement.span(),
+                attributes: &[],
+                node: Node::Leaf,
+            },
+        })
+        .chain(code_block.final_expr_opt.iter().map(|expr| Child {
+            span: expr.span(),
+            attributes: &[],
+            node: Node::Leaf,
+        }))
+        .collect()
+}
+
+/// The span of an item including its attributes.
+fn annotated_span(item: &Annotated<ItemImplItem>) -> Span {
+    match item.attribute_list.first() {
+        Some(attribute) => Span::join(attribute.span(), item.value.span()),
+        None => item.value.span(),
+    }
+}
+
+/// Extends a span to the start of its line if it is only preceded by whitespace on that line, so
+/// that its indentation is formatted too.
+fn extend_to_line_start(code: &str, span: Span) -> Range<usize> {
+    let line_start = code[..span.start()].rfind('\n').map_or(0, |ix| ix + 1);
+    match code[line_start..span.start()].trim().is_empty() {
+        true => line_start..span.end(),
+        false => span.start()..span.end(),
+    }
+}
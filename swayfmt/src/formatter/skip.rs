@@ -0,0 +1,123 @@
+//! Code left as it is by the formatter: items with a `#[fmt(skip)]` attribute, and the code
+//! between a `// forc-fmt: off` comment and the next `// forc-fmt: on` comment, or the end of the
+//! file.
+use super::range::{children, Node};
+use crate::{
+    formatter::{FormattedCode, FormatterError},
+    parse::parse_file,
+    utils::map::comments::CommentMap,
+};
+use std::{cmp::Reverse, ops::Range, path::PathBuf, sync::Arc};
+use sway_ast::{attribute::AttributeHashKind, AttributeDecl};
+use sway_types::{
+    constants::{FMT_ATTRIBUTE_NAME, FMT_SKIP_ARG_NAME},
+    SourceEngine,
+};
+
+/// The comment turning formatting off.
+const FMT_OFF_COMMENT: &str = "forc-fmt: off";
+/// The comment turning formatting back on.
+const FMT_ON_COMMENT: &str = "forc-fmt: on";
+
+/// Replaces the code which formatting is skipped for within `formatted_code` by its unformatted
+/// version from `src`.
+pub(super) fn restore_skipped_code(
+    source_engine: &SourceEngine,
+    src: &str,
+    formatted_code: &mut FormattedCode,
+    path: Option<Arc<PathBuf>>,
+) -> Result<(), FormatterError> {
+    if !src.contains(FMT_OFF_COMMENT) && !src.contains(FMT_ATTRIBUTE_NAME) {
+        return Ok(());
+    }
+
+    let unformatted_module = parse_file(source_engine, Arc::from(src), path.clone())?.value;
+    let formatted_module =
+        parse_file(source_engine, Arc::from(formatted_code.as_str()), path)?.value;
+    let mut unformatted_items = vec![];
+    skipped_items(Node::Module(&unformatted_module), &mut unformatted_items);
+    let mut formatted_items = vec![];
+    skipped_items(Node::Module(&formatted_module), &mut formatted_items);
+    let unformatted_regions = skipped_regions(src)?;
+    let formatted_regions = skipped_regions(formatted_code)?;
+    if unformatted_items.len() != formatted_items.len()
+        || unformatted_regions.len() != formatted_regions.len()
+    {
+        return Err(FormatterError::SkipError);
+    }
+
+    // Pair the ranges of the formatted code with the unformatted code replacing them, outermost
+    // first so that the code nested within them can be ignored.
+    let mut replacements = formatted_items
+        .into_iter()
+        .zip(unformatted_items)
+        .chain(formatted_regions.into_iter().zip(unformatted_regions))
+        .collect::<Vec<_>>();
+    replacements.sort_by_key(|(formatted, _)| (formatted.start, Reverse(formatted.end)));
+
+    let mut restored_code = FormattedCode::with_capacity(formatted_code.len());
+    let mut last_formatted = 0;
+    for (formatted, unformatted) in replacements {
+        if formatted.start < last_formatted {
+            continue;
+        }
+        restored_code.push_str(&formatted_code[last_formatted..formatted.start]);
+        restored_code.push_str(&src[unformatted]);
+        last_formatted = formatted.end;
+    }
+    restored_code.push_str(&formatted_code[last_formatted..]);
+    *formatted_code = restored_code;
+
+    Ok(())
+}
+
+/// Collects the ranges of the outermost items with a `#[fmt(skip)]` attribute within a node.
+fn skipped_items(node: Node, ranges: &mut Vec<Range<usize>>) {
+    for child in children(node) {
+        if is_skipped(child.attributes) {
+            ranges.push(child.span.start()..child.span.end());
+        } else {
+            skipped_items(child.node, ranges);
+        }
+    }
+}
+
+/// Whether an item with the given attributes has a `#[fmt(skip)]` attribute.
+pub(crate) fn is_skipped(attributes: &[AttributeDecl]) -> bool {
+    attributes
+        .iter()
+        .filter(|attribute_decl| matches!(attribute_decl.hash_kind, AttributeHashKind::Outer(_)))
+        .flat_map(|attribute_decl| attribute_decl.attribute.get().into_iter())
+        .filter(|attribute| attribute.name.as_str() == FMT_ATTRIBUTE_NAME)
+        .flat_map(|attribute| {
+            attribute
+                .args
+                .iter()
+                .flat_map(|args| args.get().into_iter())
+        })
+        .any(|arg| arg.name.as_str() == FMT_SKIP_ARG_NAME)
+}
+
+/// Collects the ranges of code between a `// forc-fmt: off` comment and the next
+/// `// forc-fmt: on` comment, or the end of the code.
+pub(crate) fn skipped_regions(code: &str) -> Result<Vec<Range<usize>>, FormatterError> {
+    let comment_map = CommentMap::from_src(Arc::from(code))?;
+    let mut ranges = vec![];
+    let mut region_start = None;
+    for (byte_span, comment) in comment_map.iter() {
+        let text = comment.span.as_str().trim_start_matches('/').trim();
+        match (text, region_start) {
+            (FMT_OFF_COMMENT, None) => region_start = Some(byte_span.end),
+            (FMT_ON_COMMENT, Some(start)) => {
+                ranges.push(start..byte_span.start);
+                region_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = region_start {
+        ranges.push(start..code.len());
+    }
+
+    Ok(ranges)
+}
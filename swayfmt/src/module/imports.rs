@@ -0,0 +1,322 @@
+//! Reordering, grouping and merging of the `use` items of a module, according to the
+//! `reorder_imports`, `group_imports` and `imports_granularity` options.
+//!
+//! Imports are rearranged in the source code before it is formatted, so that the formatted code
+//! can still be matched against the source it was formatted from to restore its newlines.
+
+use crate::{
+    config::{
+        imports::{GroupImports, ImportGranularity},
+        manifest::Config,
+    },
+    formatter::FormatterError,
+    parse::parse_file,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Arc,
+};
+use sway_ast::{Item, ItemKind, ItemUse, UseTree};
+use sway_types::{SourceEngine, Spanned};
+
+/// A single imported item, i.e. a leaf of a `use` tree along with its path.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Import {
+    is_pub: bool,
+    is_root: bool,
+    path: Vec<String>,
+    /// The imported name, which may be renamed, `self` or a glob.
+    leaf: String,
+}
+
+/// A `use` item of the source code.
+struct UseItem {
+    /// The source code of the item.
+    src: String,
+    imports: Vec<Import>,
+}
+
+/// An imported item in a tree of imports sharing a common path.
+#[derive(Default)]
+struct ImportTree {
+    leaves: BTreeSet<String>,
+    children: BTreeMap<String, ImportTree>,
+}
+
+/// Rearrange the blocks of consecutive `use` items of a module as configured.  A block is ended
+/// by any other item, and by comments or attributes, which aren't moved around.
+pub(crate) fn reorder_imports(
+    source_engine: &SourceEngine,
+    src: &str,
+    path: Option<Arc<PathBuf>>,
+    config: &Config,
+) -> Result<String, FormatterError> {
+    let reorder = config.ordering.reorder_imports;
+    let group = config.imports.group_imports;
+    let granularity = config.imports.imports_granularity;
+    if !reorder
+        && matches!(group, GroupImports::Preserve)
+        && matches!(granularity, ImportGranularity::Preserve)
+    {
+        return Ok(src.to_string());
+    }
+
+    let module = parse_file(source_engine, Arc::from(src), path)?.value;
+    let mut blocks: Vec<Vec<&Item>> = vec![];
+    let mut prev_end = None;
+    for item in &module.items {
+        let is_use = matches!(item.value, ItemKind::Use(_)) && item.attribute_list.is_empty();
+        if !is_use {
+            prev_end = None;
+            continue;
+        }
+        match prev_end {
+            Some(end) if src[end..item.span().start()].trim().is_empty() => {
+                blocks.last_mut().unwrap().push(item)
+            }
+            _ => blocks.push(vec![item]),
+        }
+        prev_end = Some(item.span().end());
+    }
+
+    // Replace the blocks from the last one, so that the spans of the others stay valid.
+    let mut reordered = src.to_string();
+    for block in blocks.iter().rev() {
+        let start = block.first().unwrap().span().start();
+        let end = block.last().unwrap().span().end();
+        if let Some(block_src) = reorder_block(src, block, reorder, group, granularity) {
+            reordered.replace_range(start..end, &block_src);
+        }
+    }
+    Ok(reordered)
+}
+
+/// The source code of a reordered block of `use` items, or `None` if it can't be reordered as
+/// some item doesn't parse, or has comments which would be lost.
+fn reorder_block(
+    src: &str,
+    block: &[&Item],
+    reorder: bool,
+    group: GroupImports,
+    granularity: ImportGranularity,
+) -> Option<String> {
+    // The groups of the source are those separated by blank lines.
+    let mut src_groups: Vec<Vec<UseItem>> = vec![];
+    let mut prev_end = None;
+    for item in block {
+        let item_use = match &item.value {
+            ItemKind::Use(item_use) => item_use,
+            _ => unreachable!("blocks only contain `use` items"),
+        };
+        let use_item = UseItem {
+            src: item.span().as_str().to_string(),
+            imports: flatten_use(item_use)?,
+        };
+        match prev_end {
+            Some(end) if src[end..item.span().start()].matches('\n').count() < 2 => {
+                src_groups.last_mut().unwrap().push(use_item)
+            }
+            _ => src_groups.push(vec![use_item]),
+        }
+        prev_end = Some(item.span().end());
+    }
+
+    // Comments within `use` items can't be kept when merging or splitting them.
+    let has_comments =
+        |use_item: &UseItem| use_item.src.contains("//") || use_item.src.contains("/*");
+    if !matches!(granularity, ImportGranularity::Preserve)
+        && src_groups.iter().flatten().any(has_comments)
+    {
+        return None;
+    }
+
+    let groups = match group {
+        GroupImports::Preserve => src_groups
+            .into_iter()
+            .map(|use_items| apply_granularity(use_items, granularity))
+            .collect(),
+        GroupImports::One => {
+            let use_items = src_groups.into_iter().flatten().collect();
+            vec![apply_granularity(use_items, granularity)]
+        }
+        GroupImports::StdExternalCrate => {
+            let use_items = src_groups.into_iter().flatten().collect();
+            let mut groups = vec![vec![], vec![], vec![]];
+            for (text, imports) in apply_granularity(use_items, granularity) {
+                let group_ix = match imports.first().map(import_root) {
+                    Some("std" | "core" | "alloc") => 0,
+                    Some("self" | "crate" | "super") => 2,
+                    _ => 1,
+                };
+                groups[group_ix].push((text, imports));
+            }
+            groups
+        }
+    };
+
+    let block_src = groups
+        .into_iter()
+        .filter(|group| !group.is_empty())
+        .map(|mut group| {
+            if reorder {
+                group.sort_by_cached_key(|(text, _)| (sort_key(text), text.clone()));
+            }
+            group
+                .into_iter()
+                .map(|(text, _)| text)
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Some(block_src)
+}
+
+/// Merge or split the given `use` items to the given granularity, in the order of their first
+/// appearance.  Returns the source code of each resulting `use` item along with its imports.
+fn apply_granularity(
+    use_items: Vec<UseItem>,
+    granularity: ImportGranularity,
+) -> Vec<(String, Vec<Import>)> {
+    let merge_key = |import: &Import| -> Option<(bool, bool, Vec<String>)> {
+        let key = |path: &[String]| Some((import.is_pub, import.is_root, path.to_vec()));
+        match granularity {
+            ImportGranularity::Preserve | ImportGranularity::Item => None,
+            ImportGranularity::Module => key(&import.path),
+            // Imports of a crate itself are kept apart from those of its items.
+            ImportGranularity::Crate if import.path.is_empty() => None,
+            ImportGranularity::Crate => key(&import.path[..1]),
+            ImportGranularity::One => key(&[]),
+        }
+    };
+
+    if let ImportGranularity::Preserve = granularity {
+        return use_items
+            .into_iter()
+            .map(|use_item| (use_item.src, use_item.imports))
+            .collect();
+    }
+
+    let mut merged: Vec<Vec<Import>> = vec![];
+    let mut merged_ix = BTreeMap::<_, usize>::new();
+    for import in use_items.into_iter().flat_map(|use_item| use_item.imports) {
+        match merge_key(&import) {
+            Some(key) => match merged_ix.get(&key) {
+                Some(ix) => merged[*ix].push(import),
+                None => {
+                    merged_ix.insert(key, merged.len());
+                    merged.push(vec![import]);
+                }
+            },
+            None => merged.push(vec![import]),
+        }
+    }
+    merged
+        .into_iter()
+        .map(|mut imports| {
+            imports.sort();
+            imports.dedup();
+            (render_use(&imports), imports)
+        })
+        .collect()
+}
+
+/// The source code of a single `use` item importing all of the given imports, which share their
+/// visibility and whether they are imported from the root.
+fn render_use(imports: &[Import]) -> String {
+    let mut tree = ImportTree::default();
+    for import in imports {
+        let node = import.path.iter().fold(&mut tree, |node, segment| {
+            node.children.entry(segment.clone()).or_default()
+        });
+        node.leaves.insert(import.leaf.clone());
+    }
+    let first = &imports[0];
+    let items = render_tree(&tree);
+    let tree_src = match items.as_slice() {
+        [item] => item.clone(),
+        _ => format!("{{{}}}", items.join(", ")),
+    };
+    format!(
+        "{}use {}{};",
+        if first.is_pub { "pub " } else { "" },
+        if first.is_root { "::" } else { "" },
+        tree_src
+    )
+}
+
+/// The source code of each tree imported by a node of an import tree.
+fn render_tree(tree: &ImportTree) -> Vec<String> {
+    let mut items = tree.leaves.iter().cloned().collect::<Vec<_>>();
+    for (segment, child) in &tree.children {
+        let child_items = render_tree(child);
+        match child_items.as_slice() {
+            // `self` may only be imported within braces.
+            [item] if item != "self" => items.push(format!("{segment}::{item}")),
+            _ => items.push(format!("{segment}::{{{}}}", child_items.join(", "))),
+        }
+    }
+    items
+}
+
+/// The imports of a `use` item, or `None` if its tree doesn't parse.
+fn flatten_use(item_use: &ItemUse) -> Option<Vec<Import>> {
+    let mut imports = vec![];
+    let mut path = vec![];
+    flatten_tree(
+        &item_use.tree,
+        item_use.visibility.is_some(),
+        item_use.root_import.is_some(),
+        &mut path,
+        &mut imports,
+    )?;
+    Some(imports)
+}
+
+fn flatten_tree(
+    tree: &UseTree,
+    is_pub: bool,
+    is_root: bool,
+    path: &mut Vec<String>,
+    imports: &mut Vec<Import>,
+) -> Option<()> {
+    let leaf = match tree {
+        UseTree::Group { imports: group } => {
+            for tree in group.get() {
+                flatten_tree(tree, is_pub, is_root, path, imports)?;
+            }
+            return Some(());
+        }
+        UseTree::Path { prefix, suffix, .. } => {
+            path.push(prefix.as_str().to_string());
+            flatten_tree(suffix, is_pub, is_root, path, imports)?;
+            path.pop();
+            return Some(());
+        }
+        UseTree::Name { name } => name.as_str().to_string(),
+        UseTree::Rename { name, alias, .. } => format!("{} as {}", name.as_str(), alias.as_str()),
+        UseTree::Glob { .. } => "*".to_string(),
+        UseTree::Error { .. } => return None,
+    };
+    imports.push(Import {
+        is_pub,
+        is_root,
+        path: path.clone(),
+        leaf,
+    });
+    Some(())
+}
+
+/// The first segment of the path of an import, i.e. the crate or module it is imported from.
+fn import_root(import: &Import) -> &str {
+    import.path.first().unwrap_or(&import.leaf)
+}
+
+/// The key `use` items are sorted by within a group: their trees, ignoring case, visibility and
+/// imports from the root.
+fn sort_key(use_src: &str) -> String {
+    let tree = use_src.trim_start_matches("pub").trim_start();
+    let tree = tree.trim_start_matches("use").trim_start();
+    tree.trim_start_matches("::").to_lowercase()
+}
@@ -7,7 +7,9 @@ use std::fmt::Write;
 use sway_ast::{Item, ItemKind, Module, ModuleKind};
 use sway_types::Spanned;
 
+pub(crate) mod imports;
 pub(crate) mod item;
+pub(crate) mod ordering;
 pub(crate) mod submodule;
 
 impl Format for Module {
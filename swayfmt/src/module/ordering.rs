@@ -0,0 +1,190 @@
+//! Reordering of the `mod` items of a module and of the items of its `impl` blocks, according to
+//! the `reorder_modules` and `reorder_impl_items` options.
+//!
+//! Like imports, items are reordered in the source code before it is formatted.  An item is moved
+//! along with its attributes, doc comments and the comments directly above it.
+//!
+//! Items are left in place when some of them are skipped by the formatter, or within a
+//! `// forc-fmt: off` region, as the skipped code is restored in the order it was found in.
+
+use crate::{
+    config::manifest::Config,
+    formatter::{
+        skip::{is_skipped, skipped_regions},
+        FormatterError,
+    },
+    parse::parse_file,
+};
+use std::{cmp::Reverse, ops::Range, path::PathBuf, sync::Arc};
+use sway_ast::{AttributeDecl, ItemImplItem, ItemKind, Submodule};
+use sway_types::{SourceEngine, Span, Spanned};
+
+/// An item to be reordered, along with the comments attached to it.
+struct Chunk {
+    /// The byte range of the item and its comments within the source code.
+    range: Range<usize>,
+    /// The key the items of a run are sorted by.
+    key: (usize, String),
+    /// Whether the item has a `#[fmt(skip)]` attribute.
+    skipped: bool,
+}
+
+/// Sort the groups of consecutive `mod` items of a module alphabetically, and order the items of
+/// its `impl` blocks by kind, constants first, as configured.  The groups of `mod` items are
+/// separated by blank lines and by any other item.
+pub(crate) fn reorder_items(
+    source_engine: &SourceEngine,
+    src: &str,
+    path: Option<Arc<PathBuf>>,
+    config: &Config,
+) -> Result<String, FormatterError> {
+    let ordering = &config.ordering;
+    if !ordering.reorder_modules && !ordering.reorder_impl_items {
+        return Ok(src.to_string());
+    }
+
+    let module = parse_file(source_engine, Arc::from(src), path)?.value;
+    let mut runs: Vec<Vec<Chunk>> = vec![];
+    if ordering.reorder_modules {
+        let mut prev_end = None;
+        for item in &module.items {
+            let submodule = match &item.value {
+                ItemKind::Submodule(submodule) => submodule,
+                _ => {
+                    prev_end = None;
+                    continue;
+                }
+            };
+            let chunk = Chunk {
+                range: chunk_range(
+                    src,
+                    annotated_span(&item.attribute_list, submodule_span(submodule)),
+                ),
+                key: (0, submodule.name.as_str().to_string()),
+                skipped: is_skipped(&item.attribute_list),
+            };
+            match prev_end {
+                Some(end) if src[end..chunk.range.start].matches('\n').count() < 2 => {
+                    runs.last_mut().unwrap().push(chunk)
+                }
+                _ => runs.push(vec![chunk]),
+            }
+            prev_end = Some(runs.last().unwrap().last().unwrap().range.end);
+        }
+    }
+    if ordering.reorder_impl_items {
+        for item in &module.items {
+            if let ItemKind::Impl(item_impl) = &item.value {
+                let chunks = item_impl
+                    .contents
+                    .get()
+                    .iter()
+                    .map(|impl_item| Chunk {
+                        range: chunk_range(
+                            src,
+                            annotated_span(
+                                &impl_item.attribute_list,
+                                impl_item_span(&impl_item.value),
+                            ),
+                        ),
+                        key: match impl_item.value {
+                            ItemImplItem::Const(_) => (0, String::new()),
+                            ItemImplItem::Fn(_) => (1, String::new()),
+                        },
+                        skipped: is_skipped(&impl_item.attribute_list),
+                    })
+                    .collect();
+                runs.push(chunks);
+            }
+        }
+    }
+
+    // Replace the runs from the last one, so that the ranges of the others stay valid.
+    runs.sort_by_key(|run| Reverse(run.first().map(|chunk| chunk.range.start)));
+    let regions = skipped_regions(src)?;
+    let mut reordered = src.to_string();
+    for run in runs.iter().filter(|run| run.len() > 1) {
+        let start = run.first().unwrap().range.start;
+        let end = run.last().unwrap().range.end;
+        if run.iter().any(|chunk| chunk.skipped)
+            || regions
+                .iter()
+                .any(|region| region.start < end && start < region.end)
+        {
+            continue;
+        }
+        let mut order = (0..run.len()).collect::<Vec<_>>();
+        // The sort is stable, so that items of the same kind keep their order.
+        order.sort_by(|a, b| run[*a].key.cmp(&run[*b].key));
+        if order
+            .iter()
+            .enumerate()
+            .all(|(ix, chunk_ix)| ix == *chunk_ix)
+        {
+            continue;
+        }
+
+        // The chunks are moved around, while the whitespace between them stays in place.
+        let mut run_src = String::new();
+        for (ix, chunk_ix) in order.iter().enumerate() {
+            if ix > 0 {
+                run_src.push_str(&src[run[ix - 1].range.end..run[ix].range.start]);
+            }
+            run_src.push_str(&src[run[*chunk_ix].range.clone()]);
+        }
+        reordered.replace_range(start..end, &run_src);
+    }
+    Ok(reordered)
+}
+
+/// The byte range of an item along with the line comments directly above it, and a line comment
+/// following it on its last line.
+fn chunk_range(src: &str, item_span: Span) -> Range<usize> {
+    let line_start = |ix: usize| src[..ix].rfind('\n').map_or(0, |ix| ix + 1);
+    let mut start = item_span.start();
+    if src[line_start(start)..start].trim().is_empty() {
+        start = line_start(start);
+        while start > 0 {
+            let prev_line_start = line_start(start - 1);
+            let prev_line = src[prev_line_start..start - 1].trim_start();
+            if !prev_line.starts_with("//") || prev_line.starts_with("//!") {
+                break;
+            }
+            start = prev_line_start;
+        }
+    }
+
+    let mut end = item_span.end();
+    let line_end = src[end..].find('\n').map_or(src.len(), |ix| end + ix);
+    if src[end..line_end].trim_start().starts_with("//") {
+        end = line_end;
+    }
+    start..end
+}
+
+/// The span of an item including its attributes.
+fn annotated_span(attribute_list: &[AttributeDecl], item_span: Span) -> Span {
+    match attribute_list.first() {
+        Some(attribute) => Span::join(attribute.span(), item_span),
+        None => item_span,
+    }
+}
+
+/// The span of a `mod` item including its visibility, which the span of the item leaves out.
+fn submodule_span(submodule: &Submodule) -> Span {
+    match &submodule.visibility {
+        Some(pub_token) => Span::join(pub_token.span(), submodule.span()),
+        None => submodule.span(),
+    }
+}
+
+/// The span of an `impl` item including the semicolon ending a constant, which the span of the
+/// constant leaves out.
+fn impl_item_span(item: &ItemImplItem) -> Span {
+    match item {
+        ItemImplItem::Const(item_const) => {
+            Span::join(item_const.span(), item_const.semicolon_token.span())
+        }
+        ItemImplItem::Fn(item_fn) => item_fn.span(),
+    }
+}
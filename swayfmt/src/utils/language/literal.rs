@@ -1,4 +1,5 @@
 use crate::{
+    config::literals::HexLiteralCase,
     formatter::*,
     utils::map::byte_span::{ByteSpan, LeafSpans},
 };
@@ -9,14 +10,25 @@ impl Format for Literal {
     fn format(
         &self,
         formatted_code: &mut FormattedCode,
-        _formatter: &mut Formatter,
+        formatter: &mut Formatter,
     ) -> Result<(), FormatterError> {
         match self {
             // TODO: do more digging into `Literal` and see if there is more formatting to do.
             Self::String(lit_string) => write!(formatted_code, "{}", lit_string.span.as_str())?,
             Self::Char(lit_char) => write!(formatted_code, "{}", lit_char.span.as_str())?,
             Self::Int(lit_int) => {
-                write!(formatted_code, "{}", lit_int.span.as_str())?;
+                let lit = lit_int.span.as_str();
+                match lit.strip_prefix("0x") {
+                    Some(digits) => {
+                        let digits = match formatter.config.literals.hex_literal_case {
+                            HexLiteralCase::Preserve => digits.to_string(),
+                            HexLiteralCase::Upper => digits.to_uppercase(),
+                            HexLiteralCase::Lower => digits.to_lowercase(),
+                        };
+                        write!(formatted_code, "0x{digits}")?;
+                    }
+                    None => write!(formatted_code, "{lit}")?,
+                }
                 if let Some((_, ty_span)) = &lit_int.ty_opt {
                     write!(formatted_code, "{}", ty_span.as_str())?;
                 }
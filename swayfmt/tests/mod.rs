@@ -1,5 +1,12 @@
 use std::sync::Arc;
-use swayfmt::{config::user_def::FieldAlignment, Formatter};
+use swayfmt::{
+    config::{
+        imports::{GroupImports, ImportGranularity},
+        literals::HexLiteralCase,
+        user_def::FieldAlignment,
+    },
+    Formatter,
+};
 use test_macros::assert_eq_pretty;
 
 /// Takes a configured formatter as input and formats a given input and checks the actual output against an
@@ -1524,8 +1531,8 @@ use ::utils::numbers::*;
         r#"library;
 
 use std::{u256::U256, vec::*};
-use ::utils::vec::sort;
 use ::utils::numbers::*;
+use ::utils::vec::sort;
 
 // pub fn aggregate_results(results: Vec<Vec<U256>>) -> Vec<U256> {
 //     let mut aggregated = Vec::new();
@@ -1588,3 +1595,494 @@ impl MyContract for Contract {
 "#,
     );
 }
+
+#[test]
+fn reorder_imports() {
+    check(
+        r#"library;
+
+use std::vec::Vec;
+use ::utils::numbers::*;
+use core::ops::Eq;
+use std::{hash::sha256, constants::ZERO_B256};
+
+use foo::Bar;
+use abc::Xyz;
+"#,
+        r#"library;
+
+use core::ops::Eq;
+use std::vec::Vec;
+use std::{constants::ZERO_B256, hash::sha256};
+use ::utils::numbers::*;
+
+use abc::Xyz;
+use foo::Bar;
+"#,
+    );
+}
+
+#[test]
+fn group_imports_std_external_crate() {
+    let mut formatter = Formatter::default();
+    formatter.config.imports.group_imports = GroupImports::StdExternalCrate;
+    check_with_formatter(
+        r#"library;
+
+use ::utils::numbers::*;
+use std::vec::Vec;
+use self::inner::Inner;
+use core::ops::Eq;
+
+use other::Other;
+"#,
+        r#"library;
+
+use core::ops::Eq;
+use std::vec::Vec;
+
+use other::Other;
+use ::utils::numbers::*;
+
+use self::inner::Inner;
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn imports_granularity_crate() {
+    let mut formatter = Formatter::default();
+    formatter.config.imports.imports_granularity = ImportGranularity::Crate;
+    check_with_formatter(
+        r#"library;
+
+use std::vec::Vec;
+use std::hash::{sha256, keccak256};
+use std::vec::*;
+use utils::numbers;
+pub use std::constants::ZERO_B256;
+"#,
+        r#"library;
+
+pub use std::constants::ZERO_B256;
+use std::{hash::{keccak256, sha256}, vec::{*, Vec}};
+use utils::numbers;
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn imports_granularity_module() {
+    let mut formatter = Formatter::default();
+    formatter.config.imports.imports_granularity = ImportGranularity::Module;
+    check_with_formatter(
+        r#"library;
+
+use std::{hash::sha256, vec::Vec};
+use std::hash::keccak256;
+use std::vec::{self, *};
+"#,
+        r#"library;
+
+use std::hash::{keccak256, sha256};
+use std::vec::{*, self, Vec};
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn imports_granularity_item() {
+    let mut formatter = Formatter::default();
+    formatter.config.imports.imports_granularity = ImportGranularity::Item;
+    check_with_formatter(
+        r#"library;
+
+use std::{hash::{keccak256, sha256}, vec::Vec};
+use ::utils::numbers::{One as Uno, Two};
+"#,
+        r#"library;
+
+use std::hash::keccak256;
+use std::hash::sha256;
+use std::vec::Vec;
+use ::utils::numbers::One as Uno;
+use ::utils::numbers::Two;
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn reorder_modules() {
+    let mut formatter = Formatter::default();
+    formatter.config.ordering.reorder_modules = true;
+    check_with_formatter(
+        r#"library;
+
+mod utils;
+// Errors of the library.
+pub mod errors;
+mod admin;
+
+mod tests;
+mod helpers;
+"#,
+        r#"library;
+
+mod admin;
+// Errors of the library.
+pub mod errors;
+mod utils;
+
+mod helpers;
+mod tests;
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn reorder_impl_items() {
+    let mut formatter = Formatter::default();
+    formatter.config.ordering.reorder_impl_items = true;
+    check_with_formatter(
+        r#"library;
+
+impl Foo {
+    /// Doubles the value.
+    fn double(self) -> u64 {
+        self.value * 2
+    }
+
+    const ZERO: u64 = 0;
+
+    fn get(self) -> u64 {
+        self.value
+    }
+
+    #[allow(dead_code)]
+    const ONE: u64 = 1;
+}
+"#,
+        r#"library;
+
+impl Foo {
+    const ZERO: u64 = 0;
+
+    #[allow(dead_code)]
+    const ONE: u64 = 1;
+
+    /// Doubles the value.
+    fn double(self) -> u64 {
+        self.value * 2
+    }
+
+    fn get(self) -> u64 {
+        self.value
+    }
+}
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn reorder_impl_items_with_skipped_code() {
+    let mut formatter = Formatter::default();
+    formatter.config.ordering.reorder_impl_items = true;
+    check_with_formatter(
+        r#"library;
+
+impl Foo {
+    // forc-fmt: off
+    fn   get(self) ->   u64 { self.value }
+    // forc-fmt: on
+    const ZERO: u64 = 0;
+
+    fn double(self) -> u64 {
+        self.value * 2
+    }
+}
+
+impl Bar {
+    fn get(self) -> u64 {
+        self.value
+    }
+
+    const ONE: u64 = 1;
+}
+"#,
+        r#"library;
+
+impl Foo {
+    // forc-fmt: off
+    fn   get(self) ->   u64 { self.value }
+    // forc-fmt: on
+    const ZERO: u64 = 0;
+
+    fn double(self) -> u64 {
+        self.value * 2
+    }
+}
+
+impl Bar {
+    const ONE: u64 = 1;
+
+    fn get(self) -> u64 {
+        self.value
+    }
+}
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn wrap_comments() {
+    let mut formatter = Formatter::default();
+    formatter.config.comments.wrap_comments = true;
+    formatter.config.comments.comment_width = 40;
+    check_with_formatter(
+        r#"library;
+
+/// Returns the sum of `a` and `b`, which must not overflow a `u64`.
+///
+/// # Examples
+///
+/// ```sway
+/// let sum = add(1111111111, 2222222222, 3333333333);
+/// ```
+pub fn add(a: u64, b: u64) -> u64 {
+    // This comment is too long to fit within the comment width, so it is wrapped
+    // along with this line.
+    //
+    // - a list item which is long enough to be wrapped
+    // - short item
+    a + b // trailing comments are never wrapped, whatever their length
+}
+"#,
+        r#"library;
+
+/// Returns the sum of `a` and `b`,
+/// which must not overflow a `u64`.
+///
+/// # Examples
+///
+/// ```sway
+/// let sum = add(1111111111, 2222222222, 3333333333);
+/// ```
+pub fn add(a: u64, b: u64) -> u64 {
+    // This comment is too long to fit
+    // within the comment width, so it
+    // is wrapped along with this line.
+    //
+    // - a list item which is long
+    //   enough to be wrapped
+    // - short item
+    a + b // trailing comments are never wrapped, whatever their length
+}
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn normalize_comments() {
+    let mut formatter = Formatter::default();
+    formatter.config.comments.normalize_comments = true;
+    check_with_formatter(
+        r#"library;
+
+pub struct Foo {
+    a: u64,
+    b: u64, /* trailing */
+}
+
+pub fn foo() -> u64 {
+    let a = /* inlined */ 1;
+    let b = 2;
+    /* A block comment
+     * spanning multiple lines.
+     */
+    a + b
+}
+"#,
+        r#"library;
+
+pub struct Foo {
+    a: u64,
+    b: u64, // trailing
+}
+
+pub fn foo() -> u64 {
+    let a = /* inlined */ 1;
+    let b = 2;
+    // A block comment
+    // spanning multiple lines.
+    a + b
+}
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn format_range() {
+    let unformatted = r#"library;
+
+fn   foo() -> u64 {
+    let x =  1;
+        let y=2;
+    x+y
+}
+
+fn   bar() {}
+"#;
+    let mut formatter = Formatter::default();
+
+    // A range within a statement is snapped to the statement.
+    let start = unformatted.find("let y").unwrap();
+    let formatted = formatter
+        .format_range(Arc::from(unformatted), start + 4..start + 5, None)
+        .unwrap();
+    let end = unformatted.find("2;").unwrap() + 2;
+    assert_eq!(formatted.range, start - 8..end);
+    assert_eq_pretty!(formatted.formatted_code, "    let y = 2;");
+
+    // A range spanning several items is snapped to those items.
+    let start = unformatted.find("fn   foo").unwrap();
+    let end = unformatted.find("bar").unwrap();
+    let formatted = formatter
+        .format_range(Arc::from(unformatted), start..end, None)
+        .unwrap();
+    assert_eq!(formatted.range, start..unformatted.len() - 1);
+    assert_eq_pretty!(
+        formatted.formatted_code,
+        r#"fn foo() -> u64 {
+    let x = 1;
+    let y = 2;
+    x + y
+}
+
+fn bar() {}"#
+    );
+
+    // A range outside of any item is snapped to the whole file.
+    let formatted = formatter
+        .format_range(Arc::from(unformatted), 0..3, None)
+        .unwrap();
+    assert_eq!(formatted.range, 0..unformatted.len());
+}
+
+#[test]
+fn hex_literal_case() {
+    let unformatted = r#"library;
+
+const ADDRESS: b256 = 0xaBcDeF0000000000000000000000000000000000000000000000000000000001;
+
+fn mask(value: u64) -> u64 {
+    match value & 0xfF00 {
+        0xAb00 => 0x1F,
+        _ => 255,
+    }
+}
+"#;
+    let mut formatter = Formatter::default();
+    formatter.config.literals.hex_literal_case = HexLiteralCase::Upper;
+    check_with_formatter(
+        unformatted,
+        r#"library;
+
+const ADDRESS: b256 = 0xABCDEF0000000000000000000000000000000000000000000000000000000001;
+
+fn mask(value: u64) -> u64 {
+    match value & 0xFF00 {
+        0xAB00 => 0x1F,
+        _ => 255,
+    }
+}
+"#,
+        &mut formatter,
+    );
+
+    let mut formatter = Formatter::default();
+    formatter.config.literals.hex_literal_case = HexLiteralCase::Lower;
+    check_with_formatter(
+        unformatted,
+        r#"library;
+
+const ADDRESS: b256 = 0xabcdef0000000000000000000000000000000000000000000000000000000001;
+
+fn mask(value: u64) -> u64 {
+    match value & 0xff00 {
+        0xab00 => 0x1f,
+        _ => 255,
+    }
+}
+"#,
+        &mut formatter,
+    );
+}
+
+#[test]
+fn fmt_skip_attribute() {
+    check(
+        r#"library;
+
+#[fmt(skip)]
+const   TABLE: [u64; 4] = [
+    1,    2,
+    3,    4,
+];
+
+impl   Foo {
+    #[fmt(skip)]
+    fn   bar() ->   u64 { 1 }
+
+    fn   baz() ->   u64 { 2 }
+}
+"#,
+        r#"library;
+
+#[fmt(skip)]
+const   TABLE: [u64; 4] = [
+    1,    2,
+    3,    4,
+];
+
+impl Foo {
+    #[fmt(skip)]
+    fn   bar() ->   u64 { 1 }
+
+    fn baz() -> u64 {
+        2
+    }
+}
+"#,
+    );
+}
+
+#[test]
+fn fmt_off_comments() {
+    check(
+        r#"library;
+
+// forc-fmt: off
+const KEY_A:      b256 = 0x0000000000000000000000000000000000000000000000000000000000000001;
+const KEY_B_LONG: b256 = 0x0000000000000000000000000000000000000000000000000000000000000002;
+// forc-fmt: on
+const   KEY_C: b256 = 0x0000000000000000000000000000000000000000000000000000000000000003;
+"#,
+        r#"library;
+
+// forc-fmt: off
+const KEY_A:      b256 = 0x0000000000000000000000000000000000000000000000000000000000000001;
+const KEY_B_LONG: b256 = 0x0000000000000000000000000000000000000000000000000000000000000002;
+// forc-fmt: on
+const KEY_C: b256 = 0x0000000000000000000000000000000000000000000000000000000000000003;
+"#,
+    );
+}
@@ -156,6 +156,10 @@ impl TestContext {
                                 TestResult::ReturnData(data)
                             }
                             ProgramState::Revert(v) => TestResult::Revert(v),
+                            // Only reachable with breakpoints, when `fuel-vm/debug` is enabled by
+                            // other crates of the workspace.
+                            #[allow(unreachable_patterns)]
+                            _ => unreachable!("the program isn't run with breakpoints"),
                         }
                     }
                     harness::VMExecutionResult::Evm(state) => match state.exit_reason {
@@ -0,0 +1,55 @@
+contract;
+
+use std::{
+    auth::msg_sender,
+    block::height,
+    constants::BASE_ASSET_ID,
+    context::this_balance,
+    storage::storage_api::read,
+};
+
+abi ChainState {
+    fn caller() -> Identity;
+    fn balance() -> u64;
+    #[storage(read)]
+    fn stored_value() -> u64;
+}
+
+impl ChainState for Contract {
+    fn caller() -> Identity {
+        msg_sender().unwrap()
+    }
+
+    fn balance() -> u64 {
+        this_balance(BASE_ASSET_ID)
+    }
+
+    #[storage(read)]
+    fn stored_value() -> u64 {
+        read::<u64>(0x0000000000000000000000000000000000000000000000000000000000000001, 0).unwrap_or(0)
+    }
+}
+
+#[test(block_height = "100")]
+fn test_block_height() {
+    assert(height() == 100);
+}
+
+#[test(caller = "0x0000000000000000000000000000000000000000000000000000000000000042")]
+fn test_caller() {
+    let chain_state = abi(ChainState, CONTRACT_ID);
+    let caller = Address::from(0x0000000000000000000000000000000000000000000000000000000000000042);
+    assert(chain_state.caller() == Identity::Address(caller));
+}
+
+#[test(balances = "0x0000000000000000000000000000000000000000000000000000000000000000:500")]
+fn test_balances() {
+    let chain_state = abi(ChainState, CONTRACT_ID);
+    assert(chain_state.balance() == 500);
+}
+
+#[test(storage_slots = "0x0000000000000000000000000000000000000000000000000000000000000001:0x000000000000002a000000000000000000000000000000000000000000000000")]
+fn test_storage() {
+    let chain_state = abi(ChainState, CONTRACT_ID);
+    assert(chain_state.stored_value() == 42);
+}
@@ -0,0 +1,38 @@
+library;
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+#[test(fuzz)]
+fn xor_is_commutative(a: u64, b: u64) {
+    assert(a ^ b == b ^ a);
+}
+
+#[test(fuzz = "64")]
+fn swap_point(p: Point) {
+    let q = Point { x: p.y, y: p.x };
+    assert(q.x == p.y && q.y == p.x);
+}
+
+#[test(fuzz)]
+fn unwrap_option(o: Option<u64>, b: bool) {
+    match o {
+        Some(v) => assert(o.unwrap() == v),
+        None => assert(o.is_none()),
+    }
+    assert(!!b == b);
+}
+
+#[test(fuzz)]
+fn compare_arrays(a: [u8; 3], h: b256) {
+    let b = a;
+    assert(a[0] == b[0] && a[1] == b[1] && a[2] == b[2]);
+    assert(h == h);
+}
+
+#[test(fuzz = "16", should_revert)]
+fn reverts_for_every_input(code: u64) {
+    revert(code)
+}
@@ -0,0 +1,36 @@
+library;
+
+enum Error {
+    NotOwner: (),
+    InsufficientBalance: u64,
+}
+
+struct Point {
+    x: u64,
+    y: u64,
+}
+
+#[test(should_revert = "Error::NotOwner")]
+fn reverts_with_not_owner() {
+    require(false, Error::NotOwner);
+}
+
+#[test(should_revert = "Error::InsufficientBalance")]
+fn reverts_with_insufficient_balance() {
+    require(1 > 2, Error::InsufficientBalance(42));
+}
+
+#[test(logs = "1, Point { x: 2, y: 3 }, true")]
+fn logs_values() {
+    log(1);
+    log(Point { x: 2, y: 3 });
+    log(true);
+}
+
+#[test(logs = "", transfers = "0", messages = "0")]
+fn emits_nothing() {}
+
+#[test(ignore)]
+fn ignored_failing_test() {
+    revert(0)
+}
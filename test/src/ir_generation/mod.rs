@@ -11,8 +11,9 @@ use sway_core::{
     Engines,
 };
 use sway_ir::{
-    create_inline_in_module_pass, register_known_passes, PassGroup, PassManager, ARGDEMOTION_NAME,
-    CONSTDEMOTION_NAME, DCE_NAME, MEMCPYOPT_NAME, MISCDEMOTION_NAME, RETDEMOTION_NAME,
+    create_inline_in_module_pass, create_o1_pass_group, register_known_passes, PassGroup,
+    PassManager, ARGDEMOTION_NAME, CONSTDEMOTION_NAME, DCE_NAME, MEMCPYOPT_NAME, MISCDEMOTION_NAME,
+    RETDEMOTION_NAME,
 };
 use sway_utils::PerformanceData;
 
@@ -284,6 +285,36 @@ pub(super) async fn run(filter_regex: Option<&regex::Regex>) -> Result<()> {
                     panic!("{} failed IR (de)serialization.", path.display());
                 }
 
+                // Do the same for the fully optimised IR, which exercises the printer and parser on
+                // everything the passes can produce.
+                let mut opt_ir = parsed_ir;
+                let mut pass_mgr = PassManager::default();
+                register_known_passes(&mut pass_mgr);
+                let mut pass_group = create_o1_pass_group();
+                pass_group.append_pass(CONSTDEMOTION_NAME);
+                pass_group.append_pass(ARGDEMOTION_NAME);
+                pass_group.append_pass(RETDEMOTION_NAME);
+                pass_group.append_pass(MISCDEMOTION_NAME);
+                pass_group.append_pass(MEMCPYOPT_NAME);
+                pass_group.append_pass(DCE_NAME);
+                pass_mgr
+                    .run(&mut opt_ir, &pass_group)
+                    .unwrap_or_else(|e| panic!("{}: failed to optimise IR: {e}", path.display()));
+                let opt_ir = opt_ir.verify().unwrap_or_else(|e| {
+                    panic!("{}: optimised IR failed to verify: {e}", path.display())
+                });
+                let opt_ir_output = sway_ir::printer::to_string(&opt_ir);
+                let parsed_opt_ir = sway_ir::parser::parse(&opt_ir_output, engines.se())
+                    .unwrap_or_else(|e| panic!("{}: {e}\n{opt_ir_output}", path.display()));
+                let parsed_opt_ir_output = sway_ir::printer::to_string(&parsed_opt_ir);
+                if opt_ir_output != parsed_opt_ir_output {
+                    tracing::error!(
+                        "{}",
+                        prettydiff::diff_lines(&opt_ir_output, &parsed_opt_ir_output)
+                    );
+                    panic!("{} failed optimised IR (de)serialization.", path.display());
+                }
+
                 run_test_count += 1;
             },
         );
@@ -0,0 +1,34 @@
+// optimisation-inline
+
+script;
+
+// Small enough that copying it into every call site doesn't grow the code.
+fn add_one(x: u64) -> u64 {
+    x + 1
+}
+
+// Too large to copy into three call sites without growing the code.
+fn mix(x: u64) -> u64 {
+    let a = x * 3 + 7;
+    let b = a ^ (x << 2);
+    let c = b * a + x;
+    let d = c / 5 + b % 11;
+    let e = d * d + a;
+    let f = e ^ (c >> 3);
+    f + a + b + c + d + e
+}
+
+fn main() -> u64 {
+    let x = add_one(1) + add_one(2) + add_one(3);
+    mix(x) + mix(x + 1) + mix(x + 2)
+}
+
+// ::check-ir::
+
+// check: fn add_one_
+// check: fn mix_
+
+// ::check-asm::
+
+// not: call add_one_
+// check: call mix_